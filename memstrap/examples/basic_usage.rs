@@ -20,7 +20,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let results = extractor.extract_strings(sample_data, 0);
     println!("Found {} strings:", results.len());
     for (i, result) in results.iter().take(5).enumerate() {
-        println!("  {}. Offset: 0x{:X}, Content: \"{}\"", i + 1, result.offset, result.content);
+        println!("  {}. Offset: {}, Content: \"{}\"", i + 1, result.offset.hex(), result.content);
     }
     
     // Example 2: Search for specific patterns
@@ -36,7 +36,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let email_results = email_extractor.extract_strings(sample_data, 0);
     println!("Found {} email patterns:", email_results.len());
     for result in &email_results {
-        println!("  - Offset: 0x{:X}, Content: \"{}\"", result.offset, result.content);
+        println!("  - Offset: {}, Content: \"{}\"", result.offset.hex(), result.content);
     }
     
     // Example 3: Search for passwords
@@ -52,7 +52,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let password_results = password_extractor.extract_strings(sample_data, 0);
     println!("Found {} password-related strings:", password_results.len());
     for result in &password_results {
-        println!("  - Offset: 0x{:X}, Content: \"{}\"", result.offset, result.content);
+        println!("  - Offset: {}, Content: \"{}\"", result.offset.hex(), result.content);
     }
     
     // Example 4: UTF-16 string extraction
@@ -69,8 +69,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let utf16_results = utf16_extractor.extract_strings(utf16_data, 0);
     println!("Found {} UTF-16 strings:", utf16_results.len());
     for result in &utf16_results {
-        println!("  - Offset: 0x{:X}, Encoding: {}, Content: \"{}\"", 
-                result.offset, result.encoding, result.content);
+        println!("  - Offset: {}, Encoding: {}, Content: \"{}\"", 
+                result.offset.hex(), result.encoding, result.content);
     }
 
     // Example 5: GBK string extraction
@@ -88,8 +88,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let gbk_results = gbk_extractor.extract_strings(gbk_data, 0);
     println!("Found {} GBK strings:", gbk_results.len());
     for result in &gbk_results {
-        println!("  - Offset: 0x{:X}, Encoding: {}, Content: \"{}\"",
-                result.offset, result.encoding, result.content);
+        println!("  - Offset: {}, Encoding: {}, Content: \"{}\"",
+                result.offset.hex(), result.encoding, result.content);
     }
 
     // Example 6: CSV output
@@ -0,0 +1,51 @@
+use crate::error::Result;
+use crate::extractor::FoundString;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Set of known strings to suppress, typically extracted from a baseline image
+#[derive(Debug, Default)]
+pub struct Baseline {
+    known: HashSet<String>,
+}
+
+impl Baseline {
+    /// Load a newline-delimited list of known strings
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read(path)?;
+        Ok(Self::from_text(&String::from_utf8_lossy(&text)))
+    }
+
+    /// Build a baseline from newline-delimited text, ignoring empty lines
+    pub fn from_text(text: &str) -> Self {
+        let known = text
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        Baseline { known }
+    }
+
+    /// Number of known strings
+    pub fn len(&self) -> usize {
+        self.known.len()
+    }
+
+    /// Whether the baseline is empty
+    pub fn is_empty(&self) -> bool {
+        self.known.is_empty()
+    }
+
+    /// Check whether a string's content is already known
+    pub fn contains(&self, content: &str) -> bool {
+        self.known.contains(content)
+    }
+
+    /// Drop known strings from `results`, returning how many were suppressed
+    pub fn suppress(&self, results: &mut Vec<FoundString>) -> usize {
+        let before = results.len();
+        results.retain(|s| !self.contains(&s.content));
+        before - results.len()
+    }
+}
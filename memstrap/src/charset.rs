@@ -0,0 +1,47 @@
+//! Byte-value whitelists for `--charset`
+
+/// Set of byte values a string may be composed of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Charset {
+    allowed: [bool; 256],
+}
+
+impl Charset {
+    /// Charset allowing exactly the given bytes
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut allowed = [false; 256];
+        for &b in bytes {
+            allowed[b as usize] = true;
+        }
+        Charset { allowed }
+    }
+
+    /// Standard base64 alphabet plus `=` padding
+    pub fn base64() -> Self {
+        Self::from_bytes(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=")
+    }
+
+    /// Hex digits in either case
+    pub fn hex() -> Self {
+        Self::from_bytes(b"0123456789abcdefABCDEF")
+    }
+
+    /// ASCII letters and digits
+    pub fn alnum() -> Self {
+        Self::from_bytes(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789")
+    }
+
+    /// Whether `byte` is in the set
+    pub fn contains(&self, byte: u8) -> bool {
+        self.allowed[byte as usize]
+    }
+
+    /// Whether every character of `content` is a byte value in the set
+    ///
+    /// Characters above U+00FF can never match.
+    pub fn accepts(&self, content: &str) -> bool {
+        content
+            .chars()
+            .all(|c| u8::try_from(c).is_ok_and(|b| self.contains(b)))
+    }
+}
@@ -0,0 +1,353 @@
+use base64::Engine;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Category assigned to an extracted string by the triage classifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringKind {
+    /// Windows registry key (`HKLM\...`, `\Registry\Machine\...`)
+    RegistryKey,
+    /// Windows file system path (`C:\...` or UNC `\\server\share`)
+    WindowsPath,
+    /// POSIX path rooted at `/` with at least two components (`/etc/passwd`)
+    PosixPath,
+    /// Date/time (ISO 8601, `YYYY-MM-DD HH:MM:SS`, `MM/DD/YYYY HH:MM:SS` or a FILETIME value)
+    Timestamp,
+    /// Standard base64 blob of at least 16 characters
+    Base64,
+}
+
+impl std::fmt::Display for StringKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringKind::RegistryKey => write!(f, "RegistryKey"),
+            StringKind::WindowsPath => write!(f, "WindowsPath"),
+            StringKind::PosixPath => write!(f, "PosixPath"),
+            StringKind::Timestamp => write!(f, "Timestamp"),
+            StringKind::Base64 => write!(f, "Base64"),
+        }
+    }
+}
+
+fn registry_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)^(?:HK(?:LM|CU|CR|U|CC)\\|HKEY_(?:LOCAL_MACHINE|CURRENT_USER|CLASSES_ROOT|USERS|CURRENT_CONFIG)\\|\\Registry\\(?:Machine|User)\\)",
+        )
+        .unwrap()
+    })
+}
+
+fn windows_path_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        // Drive-letter paths (C:\...) or UNC paths (\\server\share)
+        Regex::new(r"^(?:[A-Za-z]:\\|\\\\[^\\\s]+\\[^\\\s]+)").unwrap()
+    })
+}
+
+fn posix_path_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // Two non-empty components, so "/" alone, "// comment" and "/ a / b" do not count
+    RE.get_or_init(|| Regex::new(r"^/[^/\s]+/[^/\s]+").unwrap())
+}
+
+fn iso_timestamp_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        // YYYY-MM-DD, then T or space, HH:MM:SS, optional fraction and UTC offset
+        Regex::new(r"^\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d{1,9})?(?:Z|[+-]\d{2}:?\d{2})?$").unwrap()
+    })
+}
+
+fn base64_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[A-Za-z0-9+/]{16,}={0,2}$").unwrap())
+}
+
+fn slash_timestamp_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(\d{1,2})/(\d{1,2})/(\d{4}) (\d{2}:\d{2}:\d{2})$").unwrap())
+}
+
+fn filetime_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(?:\d{18}|0[xX][0-9A-Fa-f]{15,16})$").unwrap())
+}
+
+/// FILETIME values (100 ns ticks since 1601) between 1980 and 2100
+const FILETIME_RANGE: std::ops::Range<u64> = 119_600_064_000_000_000..157_469_184_000_000_000;
+/// FILETIME ticks between 1601-01-01 and the Unix epoch
+const FILETIME_UNIX_EPOCH: u64 = 116_444_736_000_000_000;
+
+/// A recognized date/time string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Timestamp {
+    /// Parsed to a single point in time
+    Exact(DateTime<FixedOffset>),
+    /// Recognized, but the day and month could be either way around
+    Ambiguous,
+}
+
+/// Recognize a date/time string in one of the supported formats
+///
+/// Timestamps without a UTC offset are taken as UTC. Slash dates are read as
+/// month/day or day/month when one field is above 12 and are ambiguous otherwise.
+fn parse_timestamp(content: &str) -> Option<Timestamp> {
+    let utc = |naive: NaiveDateTime| Utc.from_utc_datetime(&naive).fixed_offset();
+
+    if iso_timestamp_regex().is_match(content) {
+        let normalized = content.replacen(' ', "T", 1);
+        if let Ok(time) = DateTime::parse_from_rfc3339(&normalized) {
+            return Some(Timestamp::Exact(time));
+        }
+        if let Ok(time) = DateTime::parse_from_str(&normalized, "%Y-%m-%dT%H:%M:%S%.f%z") {
+            return Some(Timestamp::Exact(time));
+        }
+        return NaiveDateTime::parse_from_str(&normalized, "%Y-%m-%dT%H:%M:%S%.f")
+            .ok()
+            .map(|naive| Timestamp::Exact(utc(naive)));
+    }
+
+    if let Some(caps) = slash_timestamp_regex().captures(content) {
+        let first: u32 = caps[1].parse().ok()?;
+        let second: u32 = caps[2].parse().ok()?;
+        let year: i32 = caps[3].parse().ok()?;
+        let (month, day) = match (first > 12, second > 12) {
+            (false, true) => (first, second),
+            (true, false) => (second, first),
+            (false, false) if first == second => (first, second),
+            (false, false) => {
+                // Still a valid date either way round, just not which one
+                NaiveDate::from_ymd_opt(year, first, second)?;
+                NaiveDate::from_ymd_opt(year, second, first)?;
+                return Some(Timestamp::Ambiguous);
+            }
+            (true, true) => return None,
+        };
+        let time = chrono::NaiveTime::parse_from_str(&caps[4], "%H:%M:%S").ok()?;
+        let date = NaiveDate::from_ymd_opt(year, month, day)?;
+        return Some(Timestamp::Exact(utc(date.and_time(time))));
+    }
+
+    if filetime_regex().is_match(content) {
+        let ticks = match content.strip_prefix("0x").or_else(|| content.strip_prefix("0X")) {
+            Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+            None => content.parse().ok()?,
+        };
+        if !FILETIME_RANGE.contains(&ticks) {
+            return None;
+        }
+        let since_epoch = ticks - FILETIME_UNIX_EPOCH;
+        let time = DateTime::from_timestamp(
+            (since_epoch / 10_000_000) as i64,
+            (since_epoch % 10_000_000) as u32 * 100,
+        )?;
+        return Some(Timestamp::Exact(time.fixed_offset()));
+    }
+
+    None
+}
+
+/// Normalize a date/time string to RFC 3339
+///
+/// Returns `None` for strings that are not timestamps and for ambiguous ones
+/// (such as `03/04/2024 10:00:00`) rather than guessing.
+pub fn normalize_timestamp(content: &str) -> Option<String> {
+    match parse_timestamp(content.trim())? {
+        Timestamp::Exact(time) => Some(time.to_rfc3339()),
+        Timestamp::Ambiguous => None,
+    }
+}
+
+/// Shortest printable ASCII run shown in a base64 preview
+const PREVIEW_MIN_RUN: usize = 4;
+/// Characters of a base64 preview kept before it is cut off
+const PREVIEW_MAX_LEN: usize = 80;
+
+/// Whether `content` looks like a base64 blob
+///
+/// Besides the alphabet, the length must be a multiple of 4 and the text must
+/// mix upper case, lower case and a digit or `+`/`/`, which rules out most
+/// identifiers and plain words that happen to use the alphabet.
+fn is_base64(content: &str) -> bool {
+    if !content.len().is_multiple_of(4) || !base64_regex().is_match(content) {
+        return false;
+    }
+    let has = |pattern: fn(&u8) -> bool| content.as_bytes().iter().any(pattern);
+    has(u8::is_ascii_uppercase)
+        && has(u8::is_ascii_lowercase)
+        && has(|&byte| byte.is_ascii_digit() || byte == b'+' || byte == b'/')
+}
+
+/// Decode a string the classifier reports as [`StringKind::Base64`]
+pub fn decode_base64(content: &str) -> Option<Vec<u8>> {
+    let content = content.trim();
+    if !is_base64(content) {
+        return None;
+    }
+    base64::engine::general_purpose::STANDARD.decode(content).ok()
+}
+
+/// Printable ASCII runs of at least four characters in decoded bytes, joined by spaces
+///
+/// Cut off after 80 characters with a trailing `...`.
+pub fn strings_preview(bytes: &[u8]) -> String {
+    let runs: Vec<&str> = bytes
+        .split(|byte| !(0x20..=0x7E).contains(byte))
+        .filter(|run| run.len() >= PREVIEW_MIN_RUN)
+        .filter_map(|run| std::str::from_utf8(run).ok())
+        .collect();
+    let preview = runs.join(" ");
+    match preview.char_indices().nth(PREVIEW_MAX_LEN) {
+        Some((cut, _)) => format!("{}...", &preview[..cut]),
+        None => preview,
+    }
+}
+
+/// Classify a decoded string, returning `None` if no known kind matches
+///
+/// Kinds are checked from most to least specific, so a registry key is never
+/// also reported as a path. Paths come before base64, whose alphabet includes
+/// `/` and which would otherwise claim paths like `/home/user/Documents`; only
+/// a `=`-padded blob that happens to start with `/` stays base64.
+pub fn classify(content: &str) -> Option<StringKind> {
+    let content = content.trim();
+
+    if registry_regex().is_match(content) {
+        Some(StringKind::RegistryKey)
+    } else if windows_path_regex().is_match(content) {
+        Some(StringKind::WindowsPath)
+    } else if parse_timestamp(content).is_some() {
+        Some(StringKind::Timestamp)
+    } else if posix_path_regex().is_match(content) && !(content.ends_with('=') && is_base64(content)) {
+        Some(StringKind::PosixPath)
+    } else if is_base64(content) {
+        Some(StringKind::Base64)
+    } else {
+        None
+    }
+}
+
+/// Whether `content` classifies as a Windows or POSIX file path (`--paths-only`)
+pub fn is_path(content: &str) -> bool {
+    matches!(classify(content), Some(StringKind::WindowsPath | StringKind::PosixPath))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_keys() {
+        for key in [
+            r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\Run",
+            r"HKCU\Software\Classes",
+            r"HKCR\exefile\shell\open\command",
+            r"HKU\S-1-5-21-1000\Environment",
+            r"HKCC\System\CurrentControlSet",
+            r"HKEY_LOCAL_MACHINE\SYSTEM\CurrentControlSet\Services",
+            r"\Registry\Machine\SYSTEM\ControlSet001",
+            r"\REGISTRY\USER\S-1-5-18",
+        ] {
+            assert_eq!(classify(key), Some(StringKind::RegistryKey), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_windows_paths() {
+        for path in [
+            r"C:\Windows\System32\kernel32.dll",
+            r"d:\temp\dump.raw",
+            r"\\fileserver\share\tools\psexec.exe",
+        ] {
+            assert_eq!(classify(path), Some(StringKind::WindowsPath), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_posix_paths() {
+        // "/home/user/Documents" also fits the base64 alphabet, length and character mix
+        for path in ["/etc/passwd", "/usr/lib/x86_64-linux-gnu/libc.so.6", "/tmp/.X11-unix/", "/home/user/Documents"] {
+            assert_eq!(classify(path), Some(StringKind::PosixPath), "{}", path);
+            assert!(is_path(path), "{}", path);
+        }
+        // One component, a comment, spaced slashes, or a base64 blob that happens to start with '/'
+        for other in ["/bin", "// TODO fix", "/ not / a path", "/9j/4AAQSkZJRgABAQEASABIAAD="] {
+            assert_ne!(classify(other), Some(StringKind::PosixPath), "{}", other);
+        }
+        assert!(is_path(r"C:\Windows\System32"));
+        assert!(!is_path(r"HKLM\SOFTWARE\Microsoft"));
+    }
+
+    #[test]
+    fn test_registry_key_is_not_a_path() {
+        // UNC-looking prefix must not win over the registry form
+        assert_eq!(
+            classify(r"\Registry\Machine\SOFTWARE"),
+            Some(StringKind::RegistryKey)
+        );
+        assert_ne!(
+            classify(r"HKLM\SYSTEM\Select"),
+            Some(StringKind::WindowsPath)
+        );
+    }
+
+    #[test]
+    fn test_iso_timestamps() {
+        for (text, normalized) in [
+            ("2024-03-15T08:30:00Z", "2024-03-15T08:30:00+00:00"),
+            ("2024-03-15T08:30:00.250+09:00", "2024-03-15T08:30:00.250+09:00"),
+            ("2024-03-15 08:30:00", "2024-03-15T08:30:00+00:00"),
+        ] {
+            assert_eq!(classify(text), Some(StringKind::Timestamp), "{}", text);
+            assert_eq!(normalize_timestamp(text).as_deref(), Some(normalized), "{}", text);
+        }
+        // Right shape, impossible date
+        assert_eq!(classify("2024-13-45 08:30:00"), None);
+    }
+
+    #[test]
+    fn test_slash_and_filetime_timestamps() {
+        assert_eq!(normalize_timestamp("03/25/2024 10:00:00").as_deref(), Some("2024-03-25T10:00:00+00:00"));
+        assert_eq!(normalize_timestamp("25/03/2024 10:00:00").as_deref(), Some("2024-03-25T10:00:00+00:00"));
+
+        // Day and month could be swapped: recognized, but not normalized
+        assert_eq!(classify("03/04/2024 10:00:00"), Some(StringKind::Timestamp));
+        assert_eq!(normalize_timestamp("03/04/2024 10:00:00"), None);
+
+        // FILETIME for 2021-06-01 12:00:00 UTC, decimal and hex
+        for text in ["132670224000000000", "0x01D756DDA5CAA000"] {
+            assert_eq!(classify(text), Some(StringKind::Timestamp), "{}", text);
+            assert_eq!(normalize_timestamp(text).as_deref(), Some("2021-06-01T12:00:00+00:00"), "{}", text);
+        }
+        // 18 digits, but outside the plausible FILETIME range
+        assert_eq!(classify("999999999999999999"), None);
+    }
+
+    #[test]
+    fn test_base64() {
+        let blob = "cG93ZXJzaGVsbCAtZW5jIEpBQjFBSE1BWlFCeUFBPT0=";
+        assert_eq!(classify(blob), Some(StringKind::Base64));
+        let decoded = decode_base64(blob).unwrap();
+        assert_eq!(decoded, b"powershell -enc JAB1AHMAZQByAA==");
+        assert_eq!(strings_preview(&decoded), "powershell -enc JAB1AHMAZQByAA==");
+        assert_eq!(strings_preview(b"\x01\x02MZ\x90\x00This program\x00ab\xFFcannot"), "This program cannot");
+
+        // Right alphabet, but too short, a length that is not a multiple of 4, or a plain word
+        for lookalike in ["SGVsbG8gV29y", "SGVsbG8gV29ybGQhIQ", "Constantinopolitanus", "abcdefghijklmnop"] {
+            assert_ne!(classify(lookalike), Some(StringKind::Base64), "{}", lookalike);
+            assert_eq!(decode_base64(lookalike), None, "{}", lookalike);
+        }
+    }
+
+    #[test]
+    fn test_unclassified_strings() {
+        assert_eq!(classify("Hello World"), None);
+        assert_eq!(classify("HKLMSOFTWARE"), None);
+        assert_eq!(classify(r"\single\backslash"), None);
+        assert_eq!(classify("C:relative"), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! Output compression for `--compress`
+
+use crate::config::Compression;
+use flate2::write::GzEncoder;
+use std::ffi::OsStr;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Writer compressing everything written to it with the selected codec
+///
+/// [`finish`](CompressedWriter::finish) must be called once all output is
+/// written: it completes the compressed stream and reports any error in
+/// doing so, which dropping the writer would lose.
+pub enum CompressedWriter<W: Write> {
+    /// `--compress none`
+    Plain(W),
+    /// `--compress gzip`
+    Gzip(GzEncoder<W>),
+    /// `--compress zstd`
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    /// Wrap `inner`, compressing with `compression` at the codec's default level
+    pub fn new(inner: W, compression: Compression) -> io::Result<Self> {
+        Ok(match compression {
+            Compression::None => CompressedWriter::Plain(inner),
+            Compression::Gzip => CompressedWriter::Gzip(GzEncoder::new(inner, flate2::Compression::default())),
+            Compression::Zstd => CompressedWriter::Zstd(zstd::Encoder::new(inner, 0)?),
+        })
+    }
+
+    /// Complete the compressed stream and return the inner writer
+    pub fn finish(self) -> io::Result<W> {
+        let mut inner = match self {
+            CompressedWriter::Plain(inner) => inner,
+            CompressedWriter::Gzip(encoder) => encoder.finish()?,
+            CompressedWriter::Zstd(encoder) => encoder.finish()?,
+        };
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            CompressedWriter::Plain(inner) => inner.write(buf),
+            CompressedWriter::Gzip(encoder) => encoder.write(buf),
+            CompressedWriter::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            CompressedWriter::Plain(inner) => inner.flush(),
+            CompressedWriter::Gzip(encoder) => encoder.flush(),
+            CompressedWriter::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// `path` with the extension of `compression` appended, e.g. `out.csv.gz`
+///
+/// A path already ending in that extension is left as it is.
+pub fn compressed_path(path: &Path, compression: Compression) -> PathBuf {
+    match compression.extension() {
+        Some(extension) if path.extension() != Some(OsStr::new(extension)) => {
+            let mut name = path.as_os_str().to_os_string();
+            name.push(".");
+            name.push(extension);
+            PathBuf::from(name)
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_compressed_path() {
+        assert_eq!(compressed_path(Path::new("out.csv"), Compression::Gzip), Path::new("out.csv.gz"));
+        assert_eq!(compressed_path(Path::new("out.csv"), Compression::Zstd), Path::new("out.csv.zst"));
+        assert_eq!(compressed_path(Path::new("out"), Compression::Gzip), Path::new("out.gz"));
+        assert_eq!(compressed_path(Path::new("out.csv.gz"), Compression::Gzip), Path::new("out.csv.gz"));
+        assert_eq!(compressed_path(Path::new("out.csv"), Compression::None), Path::new("out.csv"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = b"Offset,Content\n0x10,hello\n".repeat(100);
+        for compression in [Compression::None, Compression::Gzip, Compression::Zstd] {
+            let mut writer = CompressedWriter::new(Vec::new(), compression).unwrap();
+            writer.write_all(&text).unwrap();
+            let written = writer.finish().unwrap();
+
+            let mut decoded = Vec::new();
+            match compression {
+                Compression::None => decoded = written,
+                Compression::Gzip => {
+                    flate2::read::GzDecoder::new(&written[..]).read_to_end(&mut decoded).unwrap();
+                }
+                Compression::Zstd => decoded = zstd::decode_all(&written[..]).unwrap(),
+            }
+            assert_eq!(decoded, text, "{:?}", compression);
+        }
+    }
+}
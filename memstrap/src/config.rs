@@ -1,5 +1,15 @@
 use clap::{Parser, ValueEnum};
-use std::path::PathBuf;
+use regex::Regex;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use unicode_script::Script;
+
+use crate::charset::Charset;
+use crate::error::MemstrapError;
+use crate::extractor::{DEFAULT_GBK_MAX_LEN, DEFAULT_REPLACEMENT_CHAR};
+use crate::output::OutputOptions;
+use crate::plan::available_memory;
+use crate::stream::{DEFAULT_OUTPUT_BUFFER_KB, DEFAULT_WINDOW_SIZE};
 
 /// Supported string encodings
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
@@ -16,28 +26,384 @@ pub enum EncodingType {
     Gbk,
 }
 
+impl EncodingType {
+    /// Every supported encoding, in scan order
+    pub const ALL: [EncodingType; 5] = [
+        EncodingType::Ascii,
+        EncodingType::Utf8,
+        EncodingType::Utf16Le,
+        EncodingType::Utf16Be,
+        EncodingType::Gbk,
+    ];
+
+    /// [`ALL`](EncodingType::ALL) as a slice, for callers listing the encodings
+    pub fn all() -> &'static [EncodingType] {
+        &Self::ALL
+    }
+
+    /// Stable lower-case name, as accepted by `--encoding` and [`ValueEnum::from_str`]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EncodingType::Ascii => "ascii",
+            EncodingType::Utf8 => "utf8",
+            EncodingType::Utf16Le => "utf16le",
+            EncodingType::Utf16Be => "utf16be",
+            EncodingType::Gbk => "gbk",
+        }
+    }
+}
+
+/// Value of a single `-e/--encoding` flag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodingSelection {
+    /// `all`: every supported encoding
+    All,
+    /// Comma-separated list of specific encodings
+    Only(Vec<EncodingType>),
+}
+
+/// Parse an `--encoding` value, rejecting `all` mixed with specific encodings
+fn parse_encoding_selection(value: &str) -> Result<EncodingSelection, String> {
+    let names: Vec<&str> = value.split(',').map(str::trim).collect();
+    if names.iter().any(|name| name.eq_ignore_ascii_case("all")) {
+        return if names.len() == 1 {
+            Ok(EncodingSelection::All)
+        } else {
+            Err("'all' cannot be combined with specific encodings".to_string())
+        };
+    }
+
+    names
+        .into_iter()
+        .map(|name| EncodingType::from_str(name, true))
+        .collect::<Result<Vec<_>, _>>()
+        .map(EncodingSelection::Only)
+}
+
+/// Whether `path` is the `-` placeholder for standard input
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Whether `path` exists but is neither a regular file nor a directory (a FIFO or device)
+///
+/// Such inputs can only be read front to back, so they are always read into memory.
+pub fn is_special_file(path: &Path) -> bool {
+    !is_stdin(path) && std::fs::metadata(path).is_ok_and(|meta| !meta.is_file() && !meta.is_dir())
+}
+
+/// Smallest minimum length honoured without `--allow-tiny`
+///
+/// Shorter limits turn nearly every printable byte into a result, which floods
+/// the output and can exhaust memory on large inputs.
+pub const MIN_LEN_FLOOR: usize = 3;
+
+/// Supported output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[value(name = "csv")]
+    Csv,
+    #[value(name = "xml")]
+    Xml,
+    /// MessagePack array of result maps
+    #[cfg(feature = "msgpack")]
+    #[value(name = "msgpack")]
+    Msgpack,
+    /// One JSON object per line per result
+    #[cfg(feature = "ndjson")]
+    #[value(name = "ndjson")]
+    Ndjson,
+    /// Parquet file (requires --output)
+    #[cfg(feature = "parquet")]
+    #[value(name = "parquet")]
+    Parquet,
+}
+
+impl OutputFormat {
+    /// File extension of the format, which replaces the `--output` one when several formats are written
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Xml => "xml",
+            #[cfg(feature = "msgpack")]
+            OutputFormat::Msgpack => "msgpack",
+            #[cfg(feature = "ndjson")]
+            OutputFormat::Ndjson => "ndjson",
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// Rendering of captured context bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ContextView {
+    /// Contiguous lowercase hex string
+    #[default]
+    #[value(name = "hex")]
+    Hex,
+    /// 16-byte-per-line `offset  hex bytes  |ascii|` blocks
+    #[value(name = "hexdump")]
+    Hexdump,
+}
+
+/// When search matches are highlighted, selected with `--color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ColorChoice {
+    /// Highlight when writing to a terminal and `NO_COLOR` is not set
+    #[default]
+    #[value(name = "auto")]
+    Auto,
+    /// Always highlight
+    #[value(name = "always")]
+    Always,
+    /// Never highlight
+    #[value(name = "never")]
+    Never,
+}
+
+/// CSV quoting selected with `--quote-style`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum QuoteStyle {
+    /// Quote fields only when they contain a delimiter, quote or line break
+    #[default]
+    #[value(name = "necessary")]
+    Necessary,
+    /// Quote every field
+    #[value(name = "always")]
+    Always,
+    /// Never quote fields (output may be ambiguous)
+    #[value(name = "never")]
+    Never,
+}
+
+/// Compression of the output file selected with `--compress`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Compression {
+    /// Write the output as is
+    #[default]
+    #[value(name = "none")]
+    None,
+    /// gzip (`.gz`)
+    #[value(name = "gzip")]
+    Gzip,
+    /// Zstandard (`.zst`)
+    #[value(name = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Extension appended to the output file name, if any
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+}
+
+/// Parse a `--delimiter` value: one ASCII character, or `\t`/`tab` for a tab
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if value.len() == 1 && value.is_ascii() => Ok(value.as_bytes()[0]),
+        _ => Err(format!("delimiter must be a single ASCII character, got {:?}", value)),
+    }
+}
+
+/// Bytes given in hex on the command line
+///
+/// An alias rather than `Vec<u8>` so clap parses it as one value, not a list.
+pub type HexBytes = Vec<u8>;
+
+/// Parse a hex byte string such as `4D5A` or `4d 5a` (an optional `0x` prefix is allowed)
+fn parse_hex_bytes(value: &str) -> Result<HexBytes, String> {
+    let digits: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let digits = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")).unwrap_or(&digits);
+    if digits.is_empty() {
+        return Err("expected at least one byte of hex".to_string());
+    }
+    hex::decode(digits).map_err(|e| format!("invalid hex {:?}: {}", value, e))
+}
+
+/// Parse an address given in decimal or, with a `0x` prefix, in hex
+pub(crate) fn parse_address(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(digits) => u64::from_str_radix(digits, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|e| format!("invalid address {:?}: {}", value, e))
+}
+
+/// Parse a `--sample-rate` fraction between 0.0 and 1.0
+fn parse_sample_rate(value: &str) -> Result<f64, String> {
+    let rate: f64 = value.trim().parse().map_err(|e| format!("invalid sample rate {:?}: {}", value, e))?;
+    if (0.0..=1.0).contains(&rate) {
+        Ok(rate)
+    } else {
+        Err(format!("sample rate must be between 0.0 and 1.0, got {}", rate))
+    }
+}
+
+/// Parse a fraction between 0.0 and 1.0, naming it `what` in errors
+fn parse_fraction(value: &str, what: &str) -> Result<f32, String> {
+    let fraction: f32 = value.trim().parse().map_err(|e| format!("invalid {} {:?}: {}", what, value, e))?;
+    if (0.0..=1.0).contains(&fraction) {
+        Ok(fraction)
+    } else {
+        Err(format!("{} must be between 0.0 and 1.0, got {}", what, fraction))
+    }
+}
+
+/// Parse a `--min-confidence` value between 0.0 and 1.0
+fn parse_confidence(value: &str) -> Result<f32, String> {
+    parse_fraction(value, "confidence")
+}
+
+/// Parse a `--min-alnum-ratio` value between 0.0 and 1.0
+fn parse_alnum_ratio(value: &str) -> Result<f32, String> {
+    parse_fraction(value, "alphanumeric ratio")
+}
+
+/// Parse a `--script` name, either the full Unicode name (e.g. Cyrillic) or its ISO 15924 code (e.g. Cyrl)
+fn parse_script(value: &str) -> Result<Script, String> {
+    Script::from_full_name(value)
+        .or_else(|| Script::from_short_name(value))
+        .ok_or_else(|| format!("unknown Unicode script {:?} (e.g. Latin, Cyrillic, Han, Arabic)", value))
+}
+
+/// Alphabet selected with `--charset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CharsetKind {
+    /// A-Z, a-z, 0-9, `+`, `/` and `=`
+    #[value(name = "base64")]
+    Base64,
+    /// 0-9, a-f, A-F
+    #[value(name = "hex")]
+    Hex,
+    /// A-Z, a-z, 0-9
+    #[value(name = "alnum")]
+    Alnum,
+    /// The bytes given with `--charset-bytes`
+    #[value(name = "custom")]
+    Custom,
+}
+
+/// Unit `--min-len` and its per-encoding overrides are measured in, selected with `--min-len-unit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum MinLenUnit {
+    /// Each encoding's own unit: bytes for ASCII, UTF-8 and GBK, code units for UTF-16, characters for code pages
+    #[default]
+    #[value(name = "native")]
+    Native,
+    /// Decoded characters, so a limit means the same text length in every encoding
+    #[value(name = "chars")]
+    Chars,
+    /// Bytes of the input, so a UTF-16 or GBK character counts twice
+    #[value(name = "bytes")]
+    Bytes,
+}
+
+/// Output layout selected with `--group-by`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    /// One section per encoding
+    #[value(name = "encoding")]
+    Encoding,
+}
+
+/// How duplicate results (e.g. from chunk overlap) are removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DedupMode {
+    /// Keep every result
+    #[value(name = "none")]
+    None,
+    /// Keep one result per offset
+    #[value(name = "offset")]
+    Offset,
+    /// Keep the first (lowest offset) result per distinct content
+    #[value(name = "content")]
+    Content,
+    /// Drop only results identical in both offset and content
+    #[value(name = "offset-content")]
+    OffsetContent,
+}
+
 /// Command line configuration
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(name = "memstrap")]
 #[command(about = "High-performance CLI tool for memory forensics string extraction")]
 #[command(version = "0.1.0")]
 pub struct Config {
-    /// Path to the memory image or file to scan
-    #[arg(value_name = "FILE_PATH")]
-    pub file_path: PathBuf,
+    /// Paths of the memory images or files to scan (`-` reads a single input from stdin)
+    #[arg(value_name = "FILE_PATH", num_args = 1..)]
+    #[cfg_attr(not(feature = "server"), arg(required = true))]
+    #[cfg_attr(feature = "server", arg(required_unless_present = "serve"))]
+    pub file_path: Vec<PathBuf>,
 
-    /// Output CSV file path (defaults to stdout)
+    /// Serve scans over HTTP on this address (e.g. 127.0.0.1:8080) instead of scanning FILE_PATH
+    #[cfg(feature = "server")]
+    #[arg(long = "serve", value_name = "ADDR", conflicts_with = "file_path")]
+    pub serve: Option<String>,
+
+    /// Output file path (defaults to stdout)
     #[arg(short = 'o', long = "output", value_name = "FILE")]
     pub output: Option<PathBuf>,
 
-    /// Minimum string length to extract
+    /// Write one output per encoding next to --output, e.g. out.ascii.csv and out.utf16le.csv
+    #[arg(long = "split-by-encoding", requires = "output")]
+    pub split_by_encoding: bool,
+
+    /// Compress the output file, appending .gz or .zst to its name (not applied to stdout)
+    #[arg(long = "compress", value_enum, default_value = "none", value_name = "CODEC", requires = "output", conflicts_with = "checkpoint")]
+    pub compress: Compression,
+
+    /// Output format; repeat to write several, each to --output with the format's extension
+    #[arg(short = 'f', long = "format", value_enum, default_value = "csv")]
+    pub format: Vec<OutputFormat>,
+
+    /// Minimum string length to extract (at least 3 unless --allow-tiny is given)
     #[arg(short = 'n', long = "min-len", default_value = "4", value_name = "LENGTH")]
     pub min_len: usize,
 
-    /// Number of threads to use (defaults to CPU core count)
+    /// Minimum length of ASCII strings (overrides --min-len)
+    #[arg(long = "min-len-ascii", value_name = "LENGTH")]
+    pub min_len_ascii: Option<usize>,
+
+    /// Minimum length of UTF-8 strings (overrides --min-len)
+    #[arg(long = "min-len-utf8", value_name = "LENGTH")]
+    pub min_len_utf8: Option<usize>,
+
+    /// Minimum length of UTF-16LE and UTF-16BE strings (overrides --min-len)
+    #[arg(long = "min-len-utf16", value_name = "LENGTH")]
+    pub min_len_utf16: Option<usize>,
+
+    /// Minimum length of GBK strings (overrides --min-len)
+    #[arg(long = "min-len-gbk", value_name = "LENGTH")]
+    pub min_len_gbk: Option<usize>,
+
+    /// Measure minimum lengths in each encoding's own unit, in decoded characters or in input bytes
+    #[arg(long = "min-len-unit", value_enum, default_value = "native", value_name = "UNIT")]
+    pub min_len_unit: MinLenUnit,
+
+    /// Honour minimum lengths below 3 instead of raising them to 3
+    #[arg(long = "allow-tiny")]
+    pub allow_tiny: bool,
+
+    /// Number of threads to use, honored even for large inputs (defaults to CPU core count, capped for large inputs)
     #[arg(short = 'j', long = "threads", value_name = "NUM")]
     pub threads: Option<usize>,
 
+    /// Instead of scanning forward, reconstruct the string ending at each of these input offsets (e.g. a known terminator) by walking backward; comma-separated, decimal or 0x-prefixed hex
+    #[arg(long = "reverse", value_name = "OFFSETS", value_delimiter = ',', value_parser = parse_address, conflicts_with_all = ["partition_table", "sample_rate"])]
+    pub reverse: Vec<u64>,
+
+    /// Scan only this fraction (0.0-1.0) of the input, in evenly spread blocks of up to 1 MiB (windows when streaming), for a quick preview; the output is marked as sampled
+    #[arg(long = "sample-rate", value_name = "FLOAT", value_parser = parse_sample_rate)]
+    pub sample_rate: Option<f64>,
+
     /// Search pattern (can be plain text or regex)
     #[arg(short = 's', long = "search", value_name = "PATTERN")]
     pub search: Option<String>,
@@ -46,17 +412,302 @@ pub struct Config {
     #[arg(short = 'r', long = "regex")]
     pub regex: bool,
 
+    /// Keep only strings whose raw bytes contain this byte sequence, given in hex (e.g. C4E3)
+    #[arg(long = "search-hex", value_name = "HEX", value_parser = parse_hex_bytes)]
+    pub search_hex: Option<HexBytes>,
+
+    /// Report strings that do NOT match the search pattern
+    #[arg(short = 'v', long = "invert-match", requires = "search", conflicts_with = "capture")]
+    pub invert_match: bool,
+
+    /// With a regex search, report only this capture group (0 is the whole match)
+    #[arg(long = "capture", value_name = "N", requires = "regex")]
+    pub capture: Option<usize>,
+
     /// Disable progress bar
     #[arg(long = "no-progress")]
     pub no_progress: bool,
 
-    /// Encoding types to search for
-    #[arg(short = 'e', long = "encoding", value_enum)]
-    pub encodings: Vec<EncodingType>,
+    /// Report progress on stderr as newline-delimited JSON objects ({"bytes_done":..,"bytes_total":..,"strings":..}) instead of a progress bar
+    #[arg(long = "progress-json", conflicts_with = "no_progress")]
+    pub progress_json: bool,
+
+    /// Read the file into memory instead of memory-mapping it (for pseudo-files such as MemProcFS mounts)
+    #[arg(long = "no-mmap")]
+    pub no_mmap: bool,
+
+    /// Encoding types to search for: ascii, utf8, utf16le, utf16be, gbk, or all
+    #[arg(short = 'e', long = "encoding", value_name = "ENCODINGS", value_parser = parse_encoding_selection)]
+    pub encodings: Vec<EncodingSelection>,
+
+    /// Also scan for strings in this code page, given as an encoding_rs label (e.g. windows-1251, koi8-r, shift_jis)
+    #[arg(long = "codepage", value_name = "LABEL")]
+    pub codepage: Option<String>,
+
+    /// Sample the start of each input and scan only the encodings it appears to contain
+    #[arg(long = "auto-encoding", conflicts_with = "encodings")]
+    pub auto_encoding: bool,
 
     /// Show context bytes around found strings (hex format)
     #[arg(short = 'C', long = "context", value_name = "NUM_BYTES")]
     pub context_bytes: Option<usize>,
+
+    /// Context bytes before each string (overrides --context)
+    #[arg(long = "context-before", value_name = "NUM_BYTES")]
+    pub context_before: Option<usize>,
+
+    /// Context bytes after each string (overrides --context)
+    #[arg(long = "context-after", value_name = "NUM_BYTES")]
+    pub context_after: Option<usize>,
+
+    /// Cut context at the nearest null or control character instead of taking the full size
+    #[arg(long = "context-trim")]
+    pub context_trim: bool,
+
+    /// CSV field delimiter: a single ASCII character, or "\t"/"tab" for tab-separated output
+    #[arg(long = "delimiter", default_value = ",", value_name = "CHAR", value_parser = parse_delimiter)]
+    pub delimiter: u8,
+
+    /// When CSV fields are quoted
+    #[arg(long = "quote-style", value_enum, default_value = "necessary", value_name = "STYLE")]
+    pub quote_style: QuoteStyle,
+
+    /// Highlight the part of each string that matched the search pattern in CSV output to the console, never in --output files
+    #[arg(long = "color", value_enum, default_value = "auto", value_name = "WHEN")]
+    pub color: ColorChoice,
+
+    /// Group CSV rows into sections, separated by `# <key>` comment lines
+    #[arg(long = "group-by", value_enum, value_name = "KEY")]
+    pub group_by: Option<GroupBy>,
+
+    /// Write one CSV row per distinct content, listing the encodings and offsets it was found at
+    #[arg(long = "consolidate", conflicts_with = "group_by")]
+    pub consolidate: bool,
+
+    /// How context bytes are rendered in the output
+    #[arg(long = "context-view", value_enum, default_value = "hex", value_name = "VIEW")]
+    pub context_view: ContextView,
+
+    /// Address added to every reported offset, in decimal or 0x-prefixed hex (e.g. 0x100000)
+    #[arg(long = "base-offset", value_name = "ADDR", default_value_t = 0, value_parser = parse_address)]
+    pub base_offset: u64,
+
+    /// Bytes at the start of each input to leave unscanned, in decimal or 0x-prefixed hex; offsets stay absolute
+    #[arg(long = "skip-header", value_name = "BYTES", default_value_t = 0, value_parser = parse_address, conflicts_with = "partition_table")]
+    pub skip_header: u64,
+
+    /// Treat the input as a raw disk image: scan each MBR/GPT partition separately, with partition-relative offsets and a Partition column
+    #[arg(long = "partition-table", conflicts_with_all = ["stream", "checkpoint", "consolidate"])]
+    pub partition_table: bool,
+
+    /// Parse the input as an ELF or PE executable and scan only these sections (e.g. .rodata,.data), adding Section and SectionOffset columns; inputs that cannot be parsed are scanned whole
+    #[cfg(feature = "binary-parse")]
+    #[arg(long = "sections", value_name = "NAMES", value_delimiter = ',', conflicts_with_all = ["partition_table", "skip_header", "reverse", "stream", "checkpoint", "consolidate"])]
+    pub sections: Vec<String>,
+
+    /// Also report every offset of this raw byte sequence, given in hex (e.g. 4D5A)
+    #[arg(long = "signature", value_name = "HEX", value_parser = parse_hex_bytes)]
+    pub signature: Option<HexBytes>,
+
+    /// Maximum byte length of a GBK string; longer runs are split
+    #[arg(long = "gbk-max-len", default_value_t = DEFAULT_GBK_MAX_LEN, value_name = "BYTES")]
+    pub gbk_max_len: usize,
+
+    /// Minimum number of word-like (alphanumeric) tokens a string must contain
+    #[arg(long = "min-words", value_name = "N")]
+    pub min_words: Option<usize>,
+
+    /// Minimum number of distinct characters a string must contain, dropping runs like "AAAAAAAA"
+    #[arg(long = "min-unique-chars", value_name = "N")]
+    pub min_unique_chars: Option<usize>,
+
+    /// Drop ASCII strings whose share of letters and digits (0.0-1.0) is below this value, e.g. "!!!!@@@@####"
+    #[arg(long = "min-alnum-ratio", value_name = "FLOAT", value_parser = parse_alnum_ratio)]
+    pub min_alnum_ratio: Option<f32>,
+
+    /// Keep only strings that look like file paths: Windows drive or UNC paths, or /-rooted POSIX paths with at least two components
+    #[arg(long = "paths-only")]
+    pub paths_only: bool,
+
+    /// Drop strings made up only of whitespace (spaces, tabs, newlines)
+    #[arg(long = "drop-whitespace")]
+    pub drop_whitespace: bool,
+
+    /// Keep only strings made entirely of bytes from this alphabet
+    #[arg(long = "charset", value_enum, value_name = "CHARSET")]
+    pub charset: Option<CharsetKind>,
+
+    /// Allowed bytes for `--charset custom`, given literally (e.g. "01" or "ACGT")
+    #[arg(long = "charset-bytes", value_name = "BYTES", required_if_eq("charset", "custom"))]
+    pub charset_bytes: Option<String>,
+
+    /// Keep only strings containing a character of this Unicode script (e.g. Cyrillic, Han); repeatable
+    #[arg(long = "script", value_name = "NAME", value_parser = parse_script)]
+    pub scripts: Vec<Script>,
+
+    /// Treat tab as part of a string (the default; overrides an earlier --exclude-tab)
+    #[arg(long = "allow-tab", overrides_with = "exclude_tab")]
+    pub allow_tab: bool,
+
+    /// End strings at tab instead of treating it as part of a string
+    #[arg(long = "exclude-tab", overrides_with = "allow_tab")]
+    pub exclude_tab: bool,
+
+    /// Treat CR and LF as part of a string, emitting multi-line strings
+    #[arg(long = "allow-newline")]
+    pub allow_newline: bool,
+
+    /// Treat DEL (0x7F) as part of a string instead of a terminator
+    #[arg(long = "treat-del-as-printable")]
+    pub treat_del_as_printable: bool,
+
+    /// On invalid UTF-8, keep the valid prefix and rescan after the bad byte instead of using '?' placeholders
+    #[arg(long = "follow-utf8-continuation")]
+    pub follow_utf8_continuation: bool,
+
+    /// Placeholder for invalid bytes when a run that is not valid UTF-8 is reported as ASCII
+    #[arg(long = "replacement-char", default_value_t = DEFAULT_REPLACEMENT_CHAR, value_name = "CHAR")]
+    pub replacement_char: char,
+
+    /// Drop invalid bytes instead of replacing them when a run that is not valid UTF-8 is reported as ASCII
+    #[arg(long = "drop-invalid", conflicts_with = "replacement_char")]
+    pub drop_invalid: bool,
+
+    /// Experimental: join ASCII and UTF-16LE runs into one string when the width switches midway
+    ///
+    /// A heuristic: at each position the encoding giving the longer printable
+    /// run is followed. Needs both ASCII (or UTF-8) and UTF-16LE selected.
+    #[arg(long = "adaptive", conflicts_with = "capture")]
+    pub adaptive: bool,
+
+    /// Newline-delimited list of known strings to suppress from the results
+    #[arg(long = "baseline", value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+
+    /// CSV of start,end,label regions of the image (e.g. exported by MemProcFS); adds a Region column naming the region each string lies in, by its offset in the image
+    #[arg(long = "region-map", value_name = "FILE")]
+    pub region_map: Option<PathBuf>,
+
+    /// Deduplication key applied after merging chunk results (default offset)
+    #[arg(long = "dedup", value_enum, value_name = "MODE")]
+    pub dedup: Option<DedupMode>,
+
+    /// Debugging aid: keep every chunk's raw results without any deduplication, so strings in the chunk overlap may appear twice
+    #[arg(long = "no-dedup", conflicts_with = "dedup")]
+    pub no_dedup: bool,
+
+    /// Merge equal strings detected at overlapping ranges in several encodings, keeping the first listed (e.g. utf16le,utf8,ascii)
+    #[arg(long = "encoding-priority", value_enum, value_delimiter = ',', value_name = "ENCODINGS")]
+    pub encoding_priority: Vec<EncodingType>,
+
+    /// Join strings of the same encoding separated by at most GAP bytes into one result
+    #[arg(long = "merge-adjacent", value_name = "GAP")]
+    pub merge_adjacent: Option<u64>,
+
+    /// Keep only the first (lowest-offset) string in each N-byte bucket of offsets
+    #[arg(long = "bucket", value_name = "N", value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "reverse")]
+    pub bucket: Option<u64>,
+
+    /// Report runs of closely-spaced strings (string tables) with their size and average length
+    #[arg(long = "detect-tables", conflicts_with = "reverse")]
+    pub detect_tables: bool,
+
+    /// Largest gap in bytes between consecutive strings of a table for --detect-tables
+    #[arg(long = "table-gap", default_value = "8", value_name = "BYTES")]
+    pub table_gap: u64,
+
+    /// Also report the ASCII characters of each UTF-16 string as a narrow row tagged embedded_ascii, with the wide string's offset in a WideOffset column
+    #[arg(long = "embedded-ascii")]
+    pub embedded_ascii: bool,
+
+    /// Pair ASCII/UTF-8 strings with an identical nearby UTF-16 copy, reported as one row with a WideOffset column
+    #[arg(long = "correlate-wide")]
+    pub correlate_wide: bool,
+
+    /// Maximum distance in bytes between the narrow and wide copies for --correlate-wide
+    #[arg(long = "correlate-distance", default_value = "4096", value_name = "BYTES")]
+    pub correlate_distance: u64,
+
+    /// Bytes of overlap between adjacent chunks, must be smaller than the chunk size
+    #[arg(long = "overlap", default_value = "4096", value_name = "BYTES")]
+    pub overlap: usize,
+
+    /// Read the file in sequential windows and write results as each window completes
+    #[arg(long = "stream")]
+    pub stream: bool,
+
+    /// Bytes per window in streaming mode
+    #[arg(long = "window-size", default_value_t = DEFAULT_WINDOW_SIZE, value_name = "BYTES")]
+    pub window_size: usize,
+
+    /// Output buffer size in streaming mode; rows are flushed at window boundaries
+    #[arg(long = "output-buffer", default_value_t = DEFAULT_OUTPUT_BUFFER_KB, value_name = "KB")]
+    pub output_buffer: usize,
+
+    /// Memory budget in MB; larger inputs are scanned in streaming mode (defaults to the available memory)
+    #[arg(long = "max-memory", value_name = "MB")]
+    pub max_memory: Option<u64>,
+
+    /// Record progress in FILE and resume from it on restart, appending to the output (implies --stream)
+    #[arg(long = "checkpoint", value_name = "FILE")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Browse results in an interactive terminal UI after extraction
+    #[cfg(feature = "tui")]
+    #[arg(long = "tui")]
+    pub tui: bool,
+
+    /// Suppress informational messages (implies --no-progress)
+    #[arg(short = 'q', long = "quiet")]
+    pub quiet: bool,
+
+    /// Print a machine-readable timing line to stderr on completion
+    #[arg(long = "timing")]
+    pub timing: bool,
+
+    /// Print the scan plan (threads, chunking, encodings, estimated memory) and exit without scanning
+    #[arg(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// Print only the number of matching strings per encoding, without building or writing the results
+    #[arg(long = "count-only", conflicts_with_all = ["output", "reverse", "sample_rate", "stream", "checkpoint"])]
+    pub count_only: bool,
+
+    /// Add a Kind column classifying strings (registry keys, Windows and POSIX paths, timestamps)
+    #[arg(long = "classify")]
+    pub classify: bool,
+
+    /// Add a NormalizedTimestamp column with date/time strings converted to RFC 3339
+    #[arg(long = "normalize-timestamps")]
+    pub normalize_timestamps: bool,
+
+    /// Add Base64Hex and Base64Preview columns with the decoded bytes of base64 blobs and the strings inside them
+    #[arg(long = "decode-base64")]
+    pub decode_base64: bool,
+
+    /// Record the SHA-256 of each input in the output (CSV comment line, XML attribute, NDJSON source_sha256 field)
+    #[arg(long = "emit-hash")]
+    pub emit_hash: bool,
+
+    /// Add a RawBytesHex column with the exact matched bytes
+    #[arg(long = "emit-raw-hex")]
+    pub emit_raw_hex: bool,
+
+    /// Add a DecodeNote column saying how decoding fell short (e.g. utf8_fallback_ascii, gbk_partial)
+    #[arg(long = "emit-decode-notes")]
+    pub emit_decode_notes: bool,
+
+    /// Add a Confidence column with the share of a GBK string's multibyte characters that decoded cleanly
+    #[arg(long = "emit-confidence")]
+    pub emit_confidence: bool,
+
+    /// Drop GBK strings whose decode confidence (0.0-1.0) is below this value
+    #[arg(long = "min-confidence", value_name = "FLOAT", value_parser = parse_confidence)]
+    pub min_confidence: Option<f32>,
+
+    /// Add a GapBefore column with the bytes between the previous string in the same chunk and each string
+    #[arg(long = "emit-gaps")]
+    pub emit_gaps: bool,
 }
 
 impl Config {
@@ -70,11 +721,297 @@ impl Config {
                 EncodingType::Utf16Le,
                 EncodingType::Utf16Be,
             ]
+        } else if self.encodings.contains(&EncodingSelection::All) {
+            EncodingType::ALL.to_vec()
+        } else {
+            let mut encodings = Vec::new();
+            for selection in &self.encodings {
+                if let EncodingSelection::Only(list) = selection {
+                    for &encoding in list {
+                        if !encodings.contains(&encoding) {
+                            encodings.push(encoding);
+                        }
+                    }
+                }
+            }
+            encodings
+        }
+    }
+
+    /// Check that `-e all` was not combined with other `-e` flags
+    pub fn check_encodings(&self) -> Result<(), MemstrapError> {
+        if self.encodings.len() > 1 && self.encodings.contains(&EncodingSelection::All) {
+            return Err(MemstrapError::Config(
+                "--encoding all cannot be combined with specific encodings".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that the inputs exist and can be combined with the selected modes and output
+    pub fn check_inputs(&self) -> Result<(), MemstrapError> {
+        for path in self.file_path.iter().filter(|path| !is_stdin(path)) {
+            if !path.exists() {
+                return Err(MemstrapError::FileNotFound(path.clone()));
+            }
+            if path.is_dir() {
+                return Err(MemstrapError::NotRegularFile(path.clone()));
+            }
+        }
+        if let Some(path) = self.file_path.iter().find(|path| is_special_file(path)) {
+            if self.streaming() {
+                return Err(MemstrapError::Config(format!(
+                    "streaming mode cannot read '{}', which is a FIFO or device",
+                    path.display()
+                )));
+            }
+            if self.auto_encoding {
+                return Err(MemstrapError::Config(format!(
+                    "--auto-encoding cannot sample '{}', which is a FIFO or device",
+                    path.display()
+                )));
+            }
+        }
+
+        let stdin = self.file_path.iter().any(|path| is_stdin(path));
+        if stdin && self.file_path.len() > 1 {
+            return Err(MemstrapError::Config("'-' (stdin) must be the only input".to_string()));
+        }
+        if stdin && self.streaming() {
+            return Err(MemstrapError::Config("streaming mode cannot read from stdin".to_string()));
+        }
+        if stdin && self.auto_encoding {
+            return Err(MemstrapError::Config("--auto-encoding cannot sample stdin".to_string()));
+        }
+        if self.checkpoint.is_some() && self.file_path.len() > 1 {
+            return Err(MemstrapError::Config("--checkpoint supports a single input file".to_string()));
+        }
+        if self.format.len() > 1 && self.output.is_none() {
+            return Err(MemstrapError::Config("several --format values require --output".to_string()));
+        }
+        for (i, &format) in self.format.iter().enumerate() {
+            if self.format[..i].contains(&format) {
+                return Err(MemstrapError::Config(format!("--format {} is given more than once", format.extension())));
+            }
+            #[cfg(feature = "ndjson")]
+            let ndjson = format == OutputFormat::Ndjson;
+            #[cfg(not(feature = "ndjson"))]
+            let ndjson = false;
+            if self.emit_hash && !matches!(format, OutputFormat::Csv | OutputFormat::Xml) && !ndjson {
+                return Err(MemstrapError::Config("--emit-hash is only supported for CSV, XML and NDJSON output".to_string()));
+            }
+            if self.consolidate && format != OutputFormat::Csv {
+                return Err(MemstrapError::Config("--consolidate is only supported for CSV output".to_string()));
+            }
+            #[cfg(feature = "parquet")]
+            if format == OutputFormat::Parquet && self.output.is_none() {
+                return Err(MemstrapError::Config("parquet output requires --output".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check for flag combinations that contradict each other
+    ///
+    /// Most are also rejected while parsing the command line, but a `Config`
+    /// built or modified in code skips those checks; without this they would
+    /// silently produce empty or unfiltered results. [`run`](crate::run()) calls
+    /// it before scanning.
+    pub fn validate(&self) -> Result<(), MemstrapError> {
+        let invalid = |reason: &str| Err(MemstrapError::Config(reason.to_string()));
+        if self.regex && self.search.is_none() {
+            return invalid("--regex requires a --search pattern");
+        }
+        if self.capture.is_some() && !self.regex {
+            return invalid("--capture requires --regex");
+        }
+        if self.invert_match && self.search.is_none() {
+            return invalid("--invert-match requires a --search pattern");
+        }
+        if self.invert_match && self.capture.is_some() {
+            return invalid("--capture cannot be combined with --invert-match, which reports non-matching strings");
+        }
+        if self.charset == Some(CharsetKind::Custom) && self.charset_bytes.is_none() {
+            return invalid("--charset custom requires --charset-bytes");
+        }
+        if self.charset_bytes.is_some() && self.charset != Some(CharsetKind::Custom) {
+            return invalid("--charset-bytes only applies to --charset custom");
+        }
+        let min_len_gbk = self.floored_min_len(self.min_len_gbk.unwrap_or(self.min_len));
+        if min_len_gbk > self.gbk_max_len && self.get_encodings().contains(&EncodingType::Gbk) {
+            return Err(MemstrapError::Config(format!(
+                "the GBK minimum length ({}) exceeds --gbk-max-len ({}), so no GBK string could be reported",
+                min_len_gbk, self.gbk_max_len
+            )));
+        }
+        Ok(())
+    }
+
+    /// Context sizes `(before, after)`, with `--context-before`/`--context-after` overriding `--context`
+    pub fn context_sizes(&self) -> (Option<usize>, Option<usize>) {
+        (
+            self.context_before.or(self.context_bytes),
+            self.context_after.or(self.context_bytes),
+        )
+    }
+
+    /// `--min-len`, raised to [`MIN_LEN_FLOOR`] unless `--allow-tiny` is set
+    pub fn effective_min_len(&self) -> usize {
+        self.floored_min_len(self.min_len)
+    }
+
+    fn floored_min_len(&self, min_len: usize) -> usize {
+        if self.allow_tiny {
+            min_len
+        } else {
+            min_len.max(MIN_LEN_FLOOR)
+        }
+    }
+
+    /// Warning for a minimum length below [`MIN_LEN_FLOOR`], if any was given
+    ///
+    /// Without `--allow-tiny` such lengths are raised to the floor; with it
+    /// they are kept, and the warning says what to expect.
+    pub fn tiny_min_len_warning(&self) -> Option<String> {
+        let overrides = [self.min_len_ascii, self.min_len_utf8, self.min_len_utf16, self.min_len_gbk];
+        let smallest = overrides.into_iter().flatten().fold(self.min_len, usize::min);
+        if smallest >= MIN_LEN_FLOOR {
+            None
+        } else if self.allow_tiny {
+            Some(format!(
+                "minimum length {} reports almost every printable byte and can exhaust memory on large inputs",
+                smallest
+            ))
         } else {
-            self.encodings.clone()
+            Some(format!(
+                "minimum length {} is below {}; using {} (pass --allow-tiny to keep it)",
+                smallest, MIN_LEN_FLOOR, MIN_LEN_FLOOR
+            ))
         }
     }
 
+    /// Per-encoding minimum lengths given with `--min-len-<encoding>`, raised like [`effective_min_len`](Self::effective_min_len)
+    pub fn min_len_overrides(&self) -> Vec<(EncodingType, usize)> {
+        [
+            (EncodingType::Ascii, self.min_len_ascii),
+            (EncodingType::Utf8, self.min_len_utf8),
+            (EncodingType::Utf16Le, self.min_len_utf16),
+            (EncodingType::Utf16Be, self.min_len_utf16),
+            (EncodingType::Gbk, self.min_len_gbk),
+        ]
+        .into_iter()
+        .filter_map(|(encoding, min_len)| min_len.map(|min_len| (encoding, self.floored_min_len(min_len))))
+        .collect()
+    }
+
+    /// Get the byte whitelist selected with `--charset`, if any
+    pub fn get_charset(&self) -> Option<Charset> {
+        self.charset.map(|kind| match kind {
+            CharsetKind::Base64 => Charset::base64(),
+            CharsetKind::Hex => Charset::hex(),
+            CharsetKind::Alnum => Charset::alnum(),
+            CharsetKind::Custom => Charset::from_bytes(self.charset_bytes.as_deref().unwrap_or_default().as_bytes()),
+        })
+    }
+
+    /// Deduplication applied to the merged chunk results: `--dedup`, or none with `--no-dedup`
+    pub fn dedup_mode(&self) -> DedupMode {
+        if self.no_dedup {
+            DedupMode::None
+        } else {
+            self.dedup.unwrap_or(DedupMode::Offset)
+        }
+    }
+
+    /// Whether extraction runs in sequential streaming windows
+    pub fn streaming(&self) -> bool {
+        self.stream || self.checkpoint.is_some()
+    }
+
+    /// Memory budget in bytes: `--max-memory`, or the memory currently available
+    pub fn memory_budget(&self) -> u64 {
+        match self.max_memory {
+            Some(mb) => mb.saturating_mul(1024 * 1024),
+            None => available_memory(),
+        }
+    }
+
+    /// Whether `--color` enables highlighting for this run
+    ///
+    /// Only console output is highlighted: files written with `--output` never
+    /// are, as the escape codes would become part of their content. In `auto`
+    /// mode stdout must also be a terminal and the `NO_COLOR` environment
+    /// variable unset or empty.
+    pub fn use_color(&self) -> bool {
+        if self.output.is_some() {
+            return false;
+        }
+        match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::io::stdout().is_terminal()
+                    && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+            }
+        }
+    }
+
+    /// Pattern whose matches are highlighted, when there is a search and color is enabled
+    fn highlight_pattern(&self) -> Option<Regex> {
+        let pattern = self.search.as_ref().filter(|_| self.use_color())?;
+        if self.regex {
+            Regex::new(pattern).ok()
+        } else {
+            Regex::new(&regex::escape(pattern)).ok()
+        }
+    }
+
+    /// Get the output options selected on the command line
+    pub fn output_options(&self) -> OutputOptions {
+        OutputOptions {
+            classify: self.classify,
+            normalize_timestamps: self.normalize_timestamps,
+            decode_base64: self.decode_base64,
+            emit_raw_hex: self.emit_raw_hex,
+            context_view: self.context_view,
+            group_by: self.group_by,
+            consolidate: self.consolidate,
+            wide_offset: self.correlate_wide || self.embedded_ascii,
+            partition: self.partition_table,
+            section: self.scans_sections(),
+            region: self.region_map.is_some(),
+            gap_before: self.emit_gaps,
+            decode_note: self.emit_decode_notes || self.embedded_ascii,
+            confidence: self.emit_confidence,
+            sample_rate: self.sample_rate(),
+            delimiter: self.delimiter,
+            quote_style: self.quote_style,
+            highlight: self.highlight_pattern(),
+        }
+    }
+
+    /// Whether `--sections` limits the scan to sections of an executable
+    pub fn scans_sections(&self) -> bool {
+        #[cfg(feature = "binary-parse")]
+        {
+            !self.sections.is_empty()
+        }
+        #[cfg(not(feature = "binary-parse"))]
+        {
+            false
+        }
+    }
+
+    /// Fraction of chunks to scan with `--sample-rate`, `None` for a full scan
+    pub fn sample_rate(&self) -> Option<f64> {
+        self.sample_rate.filter(|&rate| rate < 1.0)
+    }
+
+    /// Placeholder for invalid bytes in runs degraded to ASCII, `None` with `--drop-invalid`
+    pub fn replacement_char(&self) -> Option<char> {
+        (!self.drop_invalid).then_some(self.replacement_char)
+    }
+
     /// Get the number of threads to use, defaulting to CPU core count
     pub fn get_threads(&self) -> usize {
         self.threads.unwrap_or_else(|| {
@@ -83,3 +1020,122 @@ impl Config {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_conflicting_flags() {
+        let parse = |args: &[&str]| Config::try_parse_from([&["memstrap", "mem.raw"], args].concat()).unwrap();
+        let message = |config: &Config| config.validate().unwrap_err().to_string();
+        assert!(parse(&["-s", "pass", "-r", "--capture", "1"]).validate().is_ok());
+
+        let mut config = parse(&["-r"]);
+        assert!(message(&config).contains("--regex requires a --search"));
+        config.search = Some("(\\w+)".to_string());
+        assert!(config.validate().is_ok());
+
+        let mut config = parse(&["-s", "pass"]);
+        config.capture = Some(1);
+        assert!(message(&config).contains("--capture requires --regex"));
+
+        let mut config = parse(&[]);
+        config.invert_match = true;
+        assert!(message(&config).contains("--invert-match requires"));
+
+        let mut config = parse(&["-s", "pass", "-r", "--capture", "1"]);
+        config.invert_match = true;
+        assert!(message(&config).contains("--capture cannot be combined with --invert-match"));
+
+        let mut config = parse(&["--charset", "custom", "--charset-bytes", "ACGT"]);
+        config.charset_bytes = None;
+        assert!(message(&config).contains("requires --charset-bytes"));
+        let mut config = parse(&["--charset", "hex"]);
+        config.charset_bytes = Some("ACGT".to_string());
+        assert!(message(&config).contains("only applies to --charset custom"));
+
+        assert!(message(&parse(&["-e", "gbk", "--min-len", "64", "--gbk-max-len", "32"])).contains("exceeds --gbk-max-len"));
+        assert!(message(&parse(&["-e", "all", "--min-len-gbk", "64", "--gbk-max-len", "32"])).contains("(64)"));
+        assert!(parse(&["-e", "gbk", "--min-len", "64", "--min-len-gbk", "8", "--gbk-max-len", "32"]).validate().is_ok());
+        // The GBK cap does not matter when GBK is not scanned
+        assert!(parse(&["-e", "ascii", "--min-len", "2000"]).validate().is_ok());
+        assert!(parse(&["--min-len", "2000"]).validate().is_ok());
+    }
+
+    #[test]
+    fn test_tiny_min_len() {
+        let parse = |args: &[&str]| Config::try_parse_from([&["memstrap", "mem.raw"], args].concat()).unwrap();
+
+        let config = parse(&["--min-len", "1"]);
+        assert_eq!(config.effective_min_len(), MIN_LEN_FLOOR);
+        assert_eq!(
+            config.tiny_min_len_warning().unwrap(),
+            "minimum length 1 is below 3; using 3 (pass --allow-tiny to keep it)"
+        );
+
+        let config = parse(&["--min-len", "1", "--allow-tiny"]);
+        assert_eq!(config.effective_min_len(), 1);
+        assert!(config.tiny_min_len_warning().unwrap().contains("exhaust memory"));
+
+        // Per-encoding minimums are raised too
+        let config = parse(&["--min-len-utf16", "2"]);
+        assert_eq!(config.effective_min_len(), 4);
+        assert!(config.min_len_overrides().contains(&(EncodingType::Utf16Le, MIN_LEN_FLOOR)));
+        assert!(config.tiny_min_len_warning().is_some());
+
+        assert_eq!(parse(&["--min-len", "3"]).tiny_min_len_warning(), None);
+    }
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(parse_address("4096"), Ok(4096));
+        assert_eq!(parse_address("0x1000"), Ok(4096));
+        assert_eq!(parse_address("0XfFfF"), Ok(0xFFFF));
+        assert!(parse_address("1000h").is_err());
+        assert!(parse_address("0x").is_err());
+    }
+
+    #[test]
+    fn test_encoding_type_names_round_trip() {
+        // Exhaustive, so a new variant must be added here and to `ALL`
+        let variant = |encoding: EncodingType| match encoding {
+            EncodingType::Ascii | EncodingType::Utf8 | EncodingType::Utf16Le | EncodingType::Utf16Be | EncodingType::Gbk => (),
+        };
+        EncodingType::all().iter().copied().for_each(variant);
+        assert_eq!(EncodingType::all().len(), 5);
+
+        for &encoding in EncodingType::all() {
+            assert_eq!(EncodingType::from_str(encoding.as_str(), false), Ok(encoding));
+        }
+        assert_eq!(EncodingType::from_str("UTF16LE", true), Ok(EncodingType::Utf16Le));
+        assert!(EncodingType::from_str("latin1", true).is_err());
+    }
+
+    #[test]
+    fn test_parse_script() {
+        assert_eq!(parse_script("Cyrillic"), Ok(Script::Cyrillic));
+        assert_eq!(parse_script("Hani"), Ok(Script::Han));
+        assert!(parse_script("Klingon").is_err());
+    }
+
+    #[test]
+    fn test_parse_sample_rate() {
+        assert_eq!(parse_sample_rate("0.5"), Ok(0.5));
+        assert_eq!(parse_sample_rate("1"), Ok(1.0));
+        assert!(parse_sample_rate("1.5").is_err());
+        assert!(parse_sample_rate("-0.1").is_err());
+        assert!(parse_sample_rate("half").is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_bytes() {
+        assert_eq!(parse_hex_bytes("4D5A"), Ok(vec![0x4D, 0x5A]));
+        assert_eq!(parse_hex_bytes("0x7f 45 4c 46"), Ok(b"\x7fELF".to_vec()));
+        assert!(parse_hex_bytes("4D5").is_err());
+        assert!(parse_hex_bytes("").is_err());
+
+        let config = Config::parse_from(["memstrap", "image.raw", "--signature", "4D5A"]);
+        assert_eq!(config.signature, Some(vec![0x4D, 0x5A]));
+    }
+}
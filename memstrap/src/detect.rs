@@ -0,0 +1,209 @@
+//! Heuristic encoding detection for `--auto-encoding`
+
+use crate::config::EncodingType;
+
+/// Bytes sampled from the start of each input
+pub const DETECT_SAMPLE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Shortest run of printable characters counted as text
+const MIN_RUN: usize = 4;
+
+/// Share of the non-null bytes that must belong to text runs of an encoding
+const MIN_TEXT_RATIO: f64 = 0.05;
+
+/// Share of the high (0x80+) bytes that must form sequences of an encoding
+const MIN_HIGH_RATIO: f64 = 0.5;
+
+/// Minimum number of high bytes in valid sequences before UTF-8 or GBK is considered
+const MIN_HIGH_BYTES: usize = 16;
+
+/// Byte statistics of a sample
+#[derive(Debug, Default)]
+struct SampleStats {
+    /// Bytes other than 0x00
+    non_null: usize,
+    /// Bytes in runs of at least `MIN_RUN` printable ASCII characters
+    ascii_run: usize,
+    /// Characters in runs of printable ASCII stored as even-aligned UTF-16LE
+    utf16le_run: usize,
+    /// Characters in runs of printable ASCII stored as even-aligned UTF-16BE
+    utf16be_run: usize,
+    /// Bytes of 0x80 or above
+    high: usize,
+    /// High bytes in valid multi-byte UTF-8 sequences
+    utf8_high: usize,
+    /// High bytes in GBK double-byte characters that are not valid UTF-8
+    gbk_high: usize,
+}
+
+fn is_printable(byte: u8) -> bool {
+    matches!(byte, 0x20..=0x7E | b'\t' | b'\n' | b'\r')
+}
+
+/// Length of the valid multi-byte UTF-8 sequence starting at `data[0]`, if any
+fn utf8_sequence_len(data: &[u8]) -> Option<usize> {
+    let len = match data[0] {
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => return None,
+    };
+    let sequence = data.get(..len)?;
+    std::str::from_utf8(sequence).ok().map(|_| len)
+}
+
+fn is_gbk_pair(lead: u8, trail: u8) -> bool {
+    (0x81..=0xFE).contains(&lead) && (0x40..=0xFE).contains(&trail) && trail != 0x7F
+}
+
+/// Length of the printable run ending just before a break, counted only if long enough
+fn close_run(run: &mut usize) -> usize {
+    let counted = if *run >= MIN_RUN { *run } else { 0 };
+    *run = 0;
+    counted
+}
+
+impl SampleStats {
+    fn collect(sample: &[u8]) -> Self {
+        let mut stats = SampleStats {
+            non_null: sample.iter().filter(|&&byte| byte != 0).count(),
+            ..Default::default()
+        };
+
+        let mut run = 0;
+        for &byte in sample {
+            if is_printable(byte) {
+                run += 1;
+            } else {
+                stats.ascii_run += close_run(&mut run);
+            }
+        }
+        stats.ascii_run += close_run(&mut run);
+
+        let (mut le_run, mut be_run) = (0, 0);
+        for pair in sample.chunks_exact(2) {
+            if is_printable(pair[0]) && pair[1] == 0 {
+                le_run += 1;
+            } else {
+                stats.utf16le_run += close_run(&mut le_run);
+            }
+            if pair[0] == 0 && is_printable(pair[1]) {
+                be_run += 1;
+            } else {
+                stats.utf16be_run += close_run(&mut be_run);
+            }
+        }
+        stats.utf16le_run += close_run(&mut le_run);
+        stats.utf16be_run += close_run(&mut be_run);
+
+        // Try UTF-8 first so that GBK only claims pairs UTF-8 cannot explain
+        let mut i = 0;
+        while i < sample.len() {
+            let byte = sample[i];
+            if byte < 0x80 {
+                i += 1;
+                continue;
+            }
+            if let Some(len) = utf8_sequence_len(&sample[i..]) {
+                stats.high += len;
+                stats.utf8_high += len;
+                i += len;
+            } else if i + 1 < sample.len() && is_gbk_pair(byte, sample[i + 1]) {
+                stats.high += 1;
+                stats.gbk_high += 1;
+                if sample[i + 1] >= 0x80 {
+                    stats.high += 1;
+                    stats.gbk_high += 1;
+                }
+                i += 2;
+            } else {
+                stats.high += 1;
+                i += 1;
+            }
+        }
+
+        stats
+    }
+
+    fn text_share(&self, count: usize) -> bool {
+        self.non_null > 0 && count as f64 / self.non_null as f64 >= MIN_TEXT_RATIO
+    }
+
+    fn high_share(&self, count: usize) -> bool {
+        count >= MIN_HIGH_BYTES && count as f64 / self.high as f64 >= MIN_HIGH_RATIO
+    }
+}
+
+/// Guess which encodings a sample of an input contains
+///
+/// Looks at the share of printable ASCII runs, of ASCII interleaved with
+/// nulls (UTF-16) and at how the high bytes pair up (multi-byte UTF-8
+/// versus GBK double-byte characters). The result is in scan order and
+/// falls back to ASCII when nothing stands out, so it is never empty.
+pub fn detect_encodings(sample: &[u8]) -> Vec<EncodingType> {
+    let stats = SampleStats::collect(sample);
+
+    let detected: Vec<EncodingType> = EncodingType::ALL
+        .into_iter()
+        .filter(|encoding| match encoding {
+            EncodingType::Ascii => stats.text_share(stats.ascii_run),
+            EncodingType::Utf8 => stats.high_share(stats.utf8_high),
+            EncodingType::Utf16Le => stats.text_share(stats.utf16le_run),
+            EncodingType::Utf16Be => stats.text_share(stats.utf16be_run),
+            EncodingType::Gbk => stats.high_share(stats.gbk_high),
+        })
+        .collect();
+
+    if detected.is_empty() {
+        vec![EncodingType::Ascii]
+    } else {
+        detected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    fn utf16be(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_be_bytes).collect()
+    }
+
+    #[test]
+    fn test_detect_encodings() {
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(20);
+        assert_eq!(detect_encodings(text.as_bytes()), vec![EncodingType::Ascii]);
+
+        let mut sample = vec![0u8; 4096];
+        sample.extend(utf16le(&text));
+        assert_eq!(detect_encodings(&sample), vec![EncodingType::Utf16Le]);
+
+        let mut sample = vec![0u8; 4096];
+        sample.extend(utf16be(&text));
+        assert_eq!(detect_encodings(&sample), vec![EncodingType::Utf16Be]);
+
+        let chinese = "内存取证字符串提取工具，支持多种编码。".repeat(10);
+        let mut sample = text.clone().into_bytes();
+        sample.extend(chinese.as_bytes());
+        assert_eq!(detect_encodings(&sample), vec![EncodingType::Ascii, EncodingType::Utf8]);
+
+        // "中文字符" in GBK, which is not valid UTF-8
+        let gbk = [0xD6, 0xD0, 0xCE, 0xC4, 0xD7, 0xD6, 0xB7, 0xFB].repeat(20);
+        assert_eq!(detect_encodings(&gbk), vec![EncodingType::Gbk]);
+
+        let mut mixed = text.into_bytes();
+        mixed.extend(utf16le("C:\\Windows\\System32\\notepad.exe").repeat(20));
+        assert_eq!(detect_encodings(&mixed), vec![EncodingType::Ascii, EncodingType::Utf16Le]);
+    }
+
+    #[test]
+    fn test_detect_encodings_falls_back_to_ascii() {
+        assert_eq!(detect_encodings(&[]), vec![EncodingType::Ascii]);
+        assert_eq!(detect_encodings(&[0u8; 1024]), vec![EncodingType::Ascii]);
+        assert_eq!(detect_encodings(&[0x01, 0x02, 0xFF, 0x00].repeat(100)), vec![EncodingType::Ascii]);
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use thiserror::Error;
 
 /// Custom error types for memstrap
@@ -20,6 +21,51 @@ pub enum MemstrapError {
     
     #[error("Configuration error: {0}")]
     Config(String),
+
+    #[error("File '{}' does not exist", .0.display())]
+    FileNotFound(PathBuf),
+
+    #[error("'{}' is not a regular file", .0.display())]
+    NotRegularFile(PathBuf),
+
+    #[error("Partition table error: {0}")]
+    PartitionTable(String),
+
+    #[error("Region map error: {0}")]
+    RegionMap(String),
+
+    #[cfg(feature = "msgpack")]
+    #[error("MessagePack error: {0}")]
+    Msgpack(#[from] rmp_serde::encode::Error),
+
+    #[cfg(feature = "ndjson")]
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "server")]
+    #[error("Server error: {0}")]
+    Server(String),
+
+    #[cfg(feature = "binary-parse")]
+    #[error("Executable parse error: {0}")]
+    BinaryParse(String),
+}
+
+impl MemstrapError {
+    /// Process exit code for this error: 2 for invalid arguments or inputs, 1 otherwise
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            MemstrapError::InvalidEncoding(_)
+            | MemstrapError::Config(_)
+            | MemstrapError::FileNotFound(_)
+            | MemstrapError::NotRegularFile(_) => 2,
+            _ => 1,
+        }
+    }
 }
 
 /// Result type alias for memstrap operations
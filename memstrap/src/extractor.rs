@@ -1,17 +1,57 @@
-use crate::config::EncodingType;
-use crate::error::Result;
+use crate::charset::Charset;
+use crate::classify;
+use crate::config::{EncodingType, MinLenUnit};
+use crate::error::{MemstrapError, Result};
+use crate::offset::Offset;
+use crate::stream;
+use rayon::prelude::*;
 use regex::Regex;
-use std::collections::HashSet;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::ops::{ControlFlow, Range};
 use encoding_rs::GBK;
+use unicode_script::{Script, UnicodeScript};
+
+/// Target size of the sub-ranges that multibyte scanners split a chunk into
+const MULTIBYTE_SUB_RANGE_SIZE: usize = 1024 * 1024;
+
+/// Default cap on the byte length of a GBK string
+pub const DEFAULT_GBK_MAX_LEN: usize = 1024;
+
+/// Default placeholder for invalid bytes in a run degraded from UTF-8 to ASCII
+pub const DEFAULT_REPLACEMENT_CHAR: char = '?';
 
 /// Represents the encoding of a found string
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Encoding {
     Ascii,
     Utf8,
     Utf16Le,
     Utf16Be,
     Gbk,
+    /// Raw byte signature match (`--signature`); the content is the signature in hex
+    Signature,
+    /// Text in the `--codepage` codec, carrying its encoding_rs name (e.g. `windows-1251`)
+    Custom(#[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_codepage"))] CodepageName),
+}
+
+/// Name of a `--codepage` codec as encoding_rs spells it
+///
+/// An alias rather than a literal `&'static str` in [`Encoding::Custom`]: serde
+/// would otherwise borrow the name from the input, tying deserialization to
+/// `'static` input, while [`deserialize_codepage`] resolves it to encoding_rs's
+/// own copy instead.
+pub type CodepageName = &'static str;
+
+/// Resolve a deserialized code page name to the `&'static str` encoding_rs names it with
+#[cfg(feature = "serde")]
+fn deserialize_codepage<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<CodepageName, D::Error> {
+    let label = <String as serde::Deserialize>::deserialize(deserializer)?;
+    encoding_rs::Encoding::for_label(label.as_bytes())
+        .map(|codec| codec.name())
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown code page {:?}", label)))
 }
 
 impl From<EncodingType> for Encoding {
@@ -34,28 +74,225 @@ impl std::fmt::Display for Encoding {
             Encoding::Utf16Le => write!(f, "UTF-16LE"),
             Encoding::Utf16Be => write!(f, "UTF-16BE"),
             Encoding::Gbk => write!(f, "GBK"),
+            Encoding::Signature => write!(f, "Signature"),
+            Encoding::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl Encoding {
+    /// Every fixed encoding a result can carry; [`Custom`](Encoding::Custom) code pages are not listed
+    pub const ALL: [Encoding; 6] = [
+        Encoding::Ascii,
+        Encoding::Utf8,
+        Encoding::Utf16Le,
+        Encoding::Utf16Be,
+        Encoding::Gbk,
+        Encoding::Signature,
+    ];
+
+    /// [`ALL`](Encoding::ALL) as a slice, for callers listing the encodings
+    pub fn all() -> &'static [Encoding] {
+        &Self::ALL
+    }
+
+    /// Stable lower-case name, e.g. `utf16le`, also used in file names
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Ascii => "ascii",
+            Encoding::Utf8 => "utf8",
+            Encoding::Utf16Le => "utf16le",
+            Encoding::Utf16Be => "utf16be",
+            Encoding::Gbk => "gbk",
+            Encoding::Signature => "signature",
+            Encoding::Custom(name) => name,
+        }
+    }
+}
+
+impl std::str::FromStr for Encoding {
+    type Err = MemstrapError;
+
+    /// Parse a name returned by [`as_str`](Encoding::as_str), ignoring case
+    ///
+    /// Only the encodings in [`ALL`](Encoding::ALL) are recognised.
+    fn from_str(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|encoding| encoding.as_str().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| MemstrapError::InvalidEncoding(name.to_string()))
+    }
+}
+
+/// Why a string's content may not match its bytes exactly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum DecodeNote {
+    /// The run was not valid UTF-8 and was reported as ASCII with invalid bytes replaced or dropped
+    Utf8FallbackAscii,
+    /// Only the valid UTF-8 prefix of the run was kept (`--follow-utf8-continuation`)
+    Utf8Truncated,
+    /// Invalid bytes were skipped or replaced while decoding GBK
+    GbkPartial,
+    /// The ASCII characters of the UTF-16 string at `wide_offset`, reported
+    /// narrow for cross-referencing (`--embedded-ascii`)
+    EmbeddedAscii,
+}
+
+impl std::fmt::Display for DecodeNote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeNote::Utf8FallbackAscii => write!(f, "utf8_fallback_ascii"),
+            DecodeNote::Utf8Truncated => write!(f, "utf8_truncated"),
+            DecodeNote::GbkPartial => write!(f, "gbk_partial"),
+            DecodeNote::EmbeddedAscii => write!(f, "embedded_ascii"),
         }
     }
 }
 
 /// Represents a found string with its metadata
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FoundString {
-    pub offset: u64,
+    pub offset: Offset,
     pub content: String,
     pub encoding: Encoding,
     pub byte_length: usize,
     pub context_before: Option<Vec<u8>>,
     pub context_after: Option<Vec<u8>>,
+    /// Raw matched bytes, retained only when the extractor is configured to keep them
+    pub raw_bytes: Option<Vec<u8>>,
+    /// Offset of the UTF-16 copy of this string, set by wide/narrow correlation
+    pub wide_offset: Option<Offset>,
+    /// Whether a byte order mark of the string's encoding immediately precedes it
+    ///
+    /// The BOM itself is not part of the content or the byte range.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub had_bom: bool,
+    /// Number of the partition the string lies in (`--partition-table`), which its offset is relative to
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub partition: Option<u32>,
+    /// Name of the ELF/PE section the string lies in (`--sections`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub section: Option<String>,
+    /// Offset of the string from the start of its section (`--sections`); `offset` stays absolute
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub section_offset: Option<Offset>,
+    /// Label of the `--region-map` region the string's offset falls in
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub region: Option<String>,
+    /// Bytes between the end of the previous string of the same chunk and the start of this one
+    /// (`--emit-gaps`), 0 when they overlap and `None` for a chunk's first string
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gap_before: Option<u64>,
+    /// How decoding fell short of the reported encoding, if it did
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub decode_note: Option<DecodeNote>,
+    /// Share of a multibyte (GBK) string's multibyte characters that decoded to a
+    /// mapped character, from 0.0 to 1.0; `None` for other encodings
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub confidence: Option<f32>,
 }
 
 /// Configuration for string extraction
 pub struct ExtractionConfig {
     pub min_len: usize,
+    /// Per-encoding minimum lengths overriding `min_len`
+    pub min_len_overrides: HashMap<Encoding, usize>,
+    /// Whether minimum lengths count characters or bytes
+    pub min_len_unit: MinLenUnit,
     pub encodings: HashSet<Encoding>,
     pub search_pattern: Option<String>,
     pub regex_pattern: Option<Regex>,
-    pub context_bytes: Option<usize>,
+    pub context_before: Option<usize>,
+    pub context_after: Option<usize>,
+    pub keep_raw_bytes: bool,
+    pub allow_tab: bool,
+    pub allow_newline: bool,
+    /// Treat DEL (0x7F) as printable instead of ending a string
+    pub del_printable: bool,
+    pub min_words: Option<usize>,
+    /// Minimum number of distinct characters a string must contain
+    pub min_unique_chars: Option<usize>,
+    /// Minimum share of letters and digits among an ASCII string's characters
+    pub min_alnum_ratio: Option<f32>,
+    /// Keep only strings the classifier takes for a Windows or POSIX file path
+    pub paths_only: bool,
+    /// Minimum decode confidence of multibyte strings (see [`FoundString::confidence`])
+    pub min_confidence: Option<f32>,
+    pub drop_whitespace: bool,
+    pub follow_utf8_continuation: bool,
+    pub charset: Option<Charset>,
+    /// Unicode scripts of which a string must contain at least one character; empty keeps all
+    pub scripts: Vec<Script>,
+    pub capture_group: Option<usize>,
+    pub invert_match: bool,
+    pub gbk_max_len: usize,
+    pub signature: Option<Vec<u8>>,
+    /// Raw byte sequence a candidate's matched bytes must contain
+    pub search_bytes: Option<Vec<u8>>,
+    /// Record each string's distance from the previous one in `gap_before`
+    pub emit_gaps: bool,
+    /// Placeholder for bytes that are not printable ASCII when invalid UTF-8 is
+    /// degraded to ASCII; `None` drops them
+    pub replacement_char: Option<char>,
+    /// Stop context at the nearest null or control character
+    pub context_trim: bool,
+    /// Follow whichever of ASCII and UTF-16LE gives the longer run, joining mixed-width strings
+    pub adaptive: bool,
+    /// Extra codec to scan with (`--codepage`), reported as [`Encoding::Custom`]
+    pub codepage: Option<&'static encoding_rs::Encoding>,
+    /// Also report the ASCII characters of each UTF-16 string as a narrow result
+    pub embedded_ascii: bool,
+}
+
+/// Byte order marks (the encoded U+FEFF) recognised in front of strings
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Whether the string at `data[start..]` is preceded by a BOM of its encoding
+///
+/// Returns `None` when the bytes before it are the BOM of the opposite UTF-16
+/// byte order shifted by one: the string is then the misaligned reading of
+/// text in that byte order and is dropped.
+fn bom_before(data: &[u8], start: usize, encoding: Encoding) -> Option<bool> {
+    let preceded_by = |bom: &[u8], gap: usize| {
+        start >= bom.len() + gap && &data[start - bom.len() - gap..start - gap] == bom
+    };
+    match encoding {
+        Encoding::Ascii | Encoding::Utf8 => Some(preceded_by(UTF8_BOM, 0)),
+        Encoding::Utf16Le if preceded_by(UTF16BE_BOM, 1) => None,
+        Encoding::Utf16Le => Some(preceded_by(UTF16LE_BOM, 0)),
+        Encoding::Utf16Be if preceded_by(UTF16LE_BOM, 1) => None,
+        Encoding::Utf16Be => Some(preceded_by(UTF16BE_BOM, 0)),
+        Encoding::Gbk | Encoding::Signature | Encoding::Custom(_) => Some(false),
+    }
+}
+
+/// Decode confidence of the GBK string `content` decoded from `bytes`
+///
+/// The share of multibyte characters that decoded to a mapped character:
+/// `bytes` is walked like the GBK scanner does, counting each lead/trail pair
+/// and each skipped invalid byte as one attempted character, and every
+/// non-ASCII character of `content` other than U+FFFD as a mapped one. A
+/// string without multibyte characters has confidence 1.0.
+pub fn gbk_confidence(bytes: &[u8], content: &str) -> f32 {
+    let mut attempted = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let pair = (0x81..=0xFE).contains(&bytes[i])
+            && bytes.get(i + 1).is_some_and(|trail| (0x40..=0x7E).contains(trail) || (0x80..=0xFE).contains(trail));
+        if bytes[i] >= 0x80 {
+            attempted += 1;
+        }
+        i += if pair { 2 } else { 1 };
+    }
+    if attempted == 0 {
+        return 1.0;
+    }
+    let mapped = content.chars().filter(|&c| !c.is_ascii() && c != char::REPLACEMENT_CHARACTER).count();
+    (mapped as f32 / attempted as f32).min(1.0)
 }
 
 /// Main string extractor
@@ -74,54 +311,639 @@ impl StringExtractor {
     ) -> Result<Self> {
         let encodings: HashSet<Encoding> = encodings.into_iter().map(Encoding::from).collect();
         
-        let regex_pattern = if use_regex && search_pattern.is_some() {
-            Some(Regex::new(search_pattern.as_ref().unwrap())?)
-        } else {
-            None
+        let regex_pattern = match search_pattern {
+            Some(ref pattern) if use_regex => Some(Regex::new(pattern)?),
+            _ => None,
         };
 
         let config = ExtractionConfig {
             min_len,
+            min_len_overrides: HashMap::new(),
+            min_len_unit: MinLenUnit::default(),
             encodings,
             search_pattern,
             regex_pattern,
-            context_bytes,
+            context_before: context_bytes,
+            context_after: context_bytes,
+            keep_raw_bytes: false,
+            allow_tab: true,
+            allow_newline: false,
+            del_printable: false,
+            min_words: None,
+            min_unique_chars: None,
+            min_alnum_ratio: None,
+            paths_only: false,
+            min_confidence: None,
+            drop_whitespace: false,
+            follow_utf8_continuation: false,
+            charset: None,
+            scripts: Vec::new(),
+            capture_group: None,
+            invert_match: false,
+            gbk_max_len: DEFAULT_GBK_MAX_LEN,
+            signature: None,
+            search_bytes: None,
+            context_trim: false,
+            emit_gaps: false,
+            replacement_char: Some(DEFAULT_REPLACEMENT_CHAR),
+            adaptive: false,
+            codepage: None,
+            embedded_ascii: false,
         };
 
         Ok(StringExtractor { config })
     }
 
+    /// Treat tab and/or newline (CR, LF) bytes as part of a string instead of terminating it
+    pub fn with_whitespace(mut self, allow_tab: bool, allow_newline: bool) -> Self {
+        self.config.allow_tab = allow_tab;
+        self.config.allow_newline = allow_newline;
+        self
+    }
+
+    /// Treat DEL (0x7F) as part of a string instead of terminating it
+    ///
+    /// Applies to every scanner, since they all share
+    /// [`is_printable_ascii`](Self::is_printable_ascii).
+    pub fn with_del_printable(mut self, del_printable: bool) -> Self {
+        self.config.del_printable = del_printable;
+        self
+    }
+
+    /// Require at least this many word-like tokens in each string
+    pub fn with_min_words(mut self, min_words: Option<usize>) -> Self {
+        self.config.min_words = min_words;
+        self
+    }
+
+    /// Drop multibyte strings whose decode confidence is below `min_confidence` (0.0 to 1.0)
+    pub fn with_min_confidence(mut self, min_confidence: Option<f32>) -> Self {
+        self.config.min_confidence = min_confidence;
+        self
+    }
+
+    /// Require at least this many distinct characters in each string, dropping runs like "AAAAAAAA"
+    pub fn with_min_unique_chars(mut self, min_unique_chars: Option<usize>) -> Self {
+        self.config.min_unique_chars = min_unique_chars;
+        self
+    }
+
+    /// Drop ASCII strings whose share of letters and digits is below `min_alnum_ratio` (0.0 to 1.0)
+    ///
+    /// Meant for runs of punctuation that happen to be printable, as found in
+    /// compressed data; strings in other encodings are not affected.
+    pub fn with_min_alnum_ratio(mut self, min_alnum_ratio: Option<f32>) -> Self {
+        self.config.min_alnum_ratio = min_alnum_ratio;
+        self
+    }
+
+    /// Keep only strings that look like file paths: Windows drive or UNC paths,
+    /// or `/`-rooted POSIX paths with at least two components
+    ///
+    /// A shortcut for a path regex, using the same rules as `--classify`.
+    pub fn with_paths_only(mut self, paths_only: bool) -> Self {
+        self.config.paths_only = paths_only;
+        self
+    }
+
+    /// Reject strings made up only of whitespace
+    pub fn with_drop_whitespace(mut self, drop: bool) -> Self {
+        self.config.drop_whitespace = drop;
+        self
+    }
+
+    /// On a UTF-8 decode failure, emit the valid prefix and rescan after the bad byte
+    /// instead of degrading the whole run to ASCII with `?` placeholders
+    pub fn with_follow_utf8_continuation(mut self, follow: bool) -> Self {
+        self.config.follow_utf8_continuation = follow;
+        self
+    }
+
+    /// Scan ASCII and UTF-16LE together, joining strings that switch width midway (experimental)
+    ///
+    /// Only takes effect when both ASCII (or UTF-8) and UTF-16LE are selected;
+    /// see [`scan_adaptive`](Self::scan_adaptive).
+    pub fn with_adaptive(mut self, adaptive: bool) -> Self {
+        self.config.adaptive = adaptive;
+        self
+    }
+
+    /// Reject strings containing any character outside `charset`
+    pub fn with_charset(mut self, charset: Option<Charset>) -> Self {
+        self.config.charset = charset;
+        self
+    }
+
+    /// Keep only strings containing at least one character of one of these scripts
+    pub fn with_scripts(mut self, scripts: Vec<Script>) -> Self {
+        self.config.scripts = scripts;
+        self
+    }
+
+    /// Report only the given regex capture group of each match (0 is the whole match)
+    ///
+    /// Fails if no regex search is configured or the pattern has no such group.
+    pub fn with_capture(mut self, group: Option<usize>) -> Result<Self> {
+        if let Some(group) = group {
+            let groups = match self.config.regex_pattern {
+                Some(ref regex) => regex.captures_len(),
+                None => return Err(MemstrapError::Config("--capture requires a regex search".to_string())),
+            };
+            if group >= groups {
+                return Err(MemstrapError::Config(format!(
+                    "capture group {} does not exist (pattern has {} groups)",
+                    group,
+                    groups - 1
+                )));
+            }
+        }
+        self.config.capture_group = group;
+        Ok(self)
+    }
+
+    /// Capture `before` bytes ahead of and `after` bytes behind each string,
+    /// replacing the symmetric size given to `new`
+    pub fn with_context(mut self, before: Option<usize>, after: Option<usize>) -> Self {
+        self.config.context_before = before;
+        self.config.context_after = after;
+        self
+    }
+
+    /// Cut context at the nearest null or control character on each side
+    ///
+    /// Context then stays within the record around the string instead of
+    /// spilling into neighbouring strings; the size limits still apply.
+    pub fn with_context_trim(mut self, trim: bool) -> Self {
+        self.config.context_trim = trim;
+        self
+    }
+
+    /// Keep strings that do NOT match the search pattern (no effect without one)
+    ///
+    /// Capture narrowing does not apply to inverted matches.
+    pub fn with_invert_match(mut self, invert: bool) -> Self {
+        self.config.invert_match = invert;
+        self
+    }
+
+    /// Override the minimum length for individual encodings
+    pub fn with_min_len_overrides(mut self, overrides: impl IntoIterator<Item = (EncodingType, usize)>) -> Self {
+        self.config.min_len_overrides
+            .extend(overrides.into_iter().map(|(encoding, min_len)| (Encoding::from(encoding), min_len)));
+        self
+    }
+
+    /// Minimum length for strings of `encoding`
+    fn min_len(&self, encoding: Encoding) -> usize {
+        self.config.min_len_overrides.get(&encoding).copied().unwrap_or(self.config.min_len)
+    }
+
+    /// Measure minimum lengths in each encoding's own unit (the default), characters or bytes
+    pub fn with_min_len_unit(mut self, unit: MinLenUnit) -> Self {
+        self.config.min_len_unit = unit;
+        self
+    }
+
+    /// Whether a string of `chars` characters spanning `bytes` bytes meets the minimum for `encoding`
+    fn long_enough(&self, encoding: Encoding, chars: usize, bytes: usize) -> bool {
+        let len = match self.config.min_len_unit {
+            MinLenUnit::Native => match encoding {
+                Encoding::Ascii | Encoding::Utf8 | Encoding::Gbk => bytes,
+                Encoding::Utf16Le | Encoding::Utf16Be => bytes / 2,
+                _ => chars,
+            },
+            MinLenUnit::Chars => chars,
+            MinLenUnit::Bytes => bytes,
+        };
+        len >= self.min_len(encoding)
+    }
+
+    /// Cap GBK strings at `max_len` bytes; longer runs are split into several results
+    ///
+    /// Fails if GBK is scanned and the cap is below its minimum string length.
+    pub fn with_gbk_max_len(mut self, max_len: usize) -> Result<Self> {
+        let min_len = self.min_len(Encoding::Gbk);
+        if max_len < min_len && self.config.encodings.contains(&Encoding::Gbk) {
+            return Err(MemstrapError::Config(format!(
+                "--gbk-max-len ({}) must be at least the GBK minimum length ({})",
+                max_len, min_len
+            )));
+        }
+        self.config.gbk_max_len = max_len;
+        Ok(self)
+    }
+
+    /// Also report each UTF-16 string's ASCII characters as an ASCII result at the same bytes
+    ///
+    /// The narrow copy carries the wide string's offset in `wide_offset` and
+    /// [`DecodeNote::EmbeddedAscii`]; non-ASCII characters are replaced like
+    /// invalid bytes in an ASCII fallback, and copies shorter than the ASCII
+    /// minimum length are left out. It is derived from a wide result that
+    /// already passed the filters, so the filters are not applied again.
+    pub fn with_embedded_ascii(mut self, embedded_ascii: bool) -> Self {
+        self.config.embedded_ascii = embedded_ascii;
+        self
+    }
+
+    /// Also scan for strings in the codec named by an encoding_rs `label` (e.g. `windows-1251`, `koi8-r`)
+    ///
+    /// Fails on labels encoding_rs does not know, and on UTF-16 and the
+    /// replacement codec, which cannot be scanned byte by byte like the others
+    /// (`--encoding utf16le`/`utf16be` covers UTF-16).
+    pub fn with_codepage(mut self, label: Option<&str>) -> Result<Self> {
+        let Some(label) = label else {
+            return Ok(self);
+        };
+        let codec = encoding_rs::Encoding::for_label(label.trim().as_bytes())
+            .ok_or_else(|| MemstrapError::InvalidEncoding(format!("unknown code page label '{}'", label)))?;
+        if codec.output_encoding() != codec {
+            return Err(MemstrapError::InvalidEncoding(format!(
+                "code page '{}' ({}) cannot be scanned with --codepage",
+                label,
+                codec.name()
+            )));
+        }
+        self.config.codepage = Some(codec);
+        Ok(self)
+    }
+
+    /// Also report every occurrence of a raw byte `signature` (such as `MZ` magic bytes)
+    pub fn with_signature(mut self, signature: Option<Vec<u8>>) -> Self {
+        self.config.signature = signature.filter(|signature| !signature.is_empty());
+        self
+    }
+
+    /// Keep only strings whose underlying bytes contain `needle`, whatever their encoding
+    ///
+    /// Combines with a text search: both must match.
+    pub fn with_search_bytes(mut self, needle: Option<Vec<u8>>) -> Self {
+        self.config.search_bytes = needle.filter(|needle| !needle.is_empty());
+        self
+    }
+
+    /// Placeholder for invalid bytes when a run that is not valid UTF-8 is
+    /// reported as ASCII, or `None` to drop those bytes from the content
+    pub fn with_replacement_char(mut self, replacement: Option<char>) -> Self {
+        self.config.replacement_char = replacement;
+        self
+    }
+
+    /// Record on each result the bytes since the end of the previous one (`FoundString::gap_before`)
+    pub fn with_gaps(mut self, emit: bool) -> Self {
+        self.config.emit_gaps = emit;
+        self
+    }
+
+    /// Retain the raw matched bytes on each result (`FoundString::raw_bytes`)
+    pub fn with_raw_bytes(mut self, keep: bool) -> Self {
+        self.config.keep_raw_bytes = keep;
+        self
+    }
+
+    /// Extract strings from any reader, such as a network stream or decompressor
+    ///
+    /// The data is buffered `window` bytes at a time plus `overlap` bytes of
+    /// lookahead, so strings crossing a window boundary are reported once and
+    /// whole as long as they fit in the overlap. Offsets are absolute from the
+    /// start of the reader. Results are ordered window by window.
+    pub fn extract_from_reader<R: Read>(&self, reader: R, window: usize, overlap: usize) -> Result<Vec<FoundString>> {
+        let mut results = Vec::new();
+        stream::scan_reader(reader, 0, window, overlap, self, |_, found| {
+            results.extend(found);
+            Ok(ControlFlow::Continue(()))
+        })?;
+        Ok(results)
+    }
+
     /// Extract strings from a byte slice with a given base offset
+    ///
+    /// Results are in ascending offset order; results at the same offset keep
+    /// the scan order (ASCII/UTF-8, UTF-16LE, UTF-16BE, code page, GBK, signature).
     pub fn extract_strings(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
-        let mut results = Vec::with_capacity(1024); // Pre-allocate capacity
+        // Every result takes at least min_len bytes plus a terminator, so small chunks reserve less
+        let mut results = Vec::with_capacity((data.len() / self.config.min_len.saturating_add(1)).min(1024));
+        self.extract_with(data, base_offset, |found| {
+            results.push(found);
+            ControlFlow::Continue(())
+        });
+
+        // Each scanner yields its own results in order; merge them (stable, so ties keep scan order)
+        results.sort_by_key(|s| s.offset);
+        if self.config.emit_gaps {
+            set_gaps(&mut results);
+        }
+        results
+    }
+
+    /// Like [`extract_strings`](Self::extract_strings) for `data[range]`, taking context from all of `data`
+    ///
+    /// Chunked scans pass the whole region and each chunk's range, so a string
+    /// near a chunk edge still gets the context bytes that lie in the
+    /// neighbouring chunk; context stops only at the ends of `data`. Offsets
+    /// are `base_offset` plus the position in `data`.
+    pub fn extract_strings_in(&self, data: &[u8], range: Range<usize>, base_offset: u64) -> Vec<FoundString> {
+        let chunk_start = range.start;
+        let mut results = self.extract_strings(&data[range], base_offset + chunk_start as u64);
+        if self.config.context_before.is_some() || self.config.context_after.is_some() {
+            for found in &mut results {
+                let start = (found.offset.get() - base_offset) as usize;
+                (found.context_before, found.context_after) =
+                    self.extract_context(data, start, start + found.byte_length, found.encoding);
+            }
+        }
+        results
+    }
 
-        // Extract ASCII/UTF-8 strings
-        if self.config.encodings.contains(&Encoding::Ascii)
-            || self.config.encodings.contains(&Encoding::Utf8) {
-            results.extend(self.extract_ascii_utf8(data, base_offset));
+    /// Reconstruct the strings ending right before `data[end]` by walking backward
+    ///
+    /// `end` is the offset of a known terminator (or the end of `data`). The
+    /// run of printable ASCII before it and, with UTF-16LE selected, the run of
+    /// printable UTF-16LE code units before it are each reported when long
+    /// enough and accepted by the filters. Non-ASCII characters end a run.
+    pub fn extract_ending_at(&self, data: &[u8], end: usize, base_offset: u64) -> Vec<FoundString> {
+        let end = end.min(data.len());
+        let mut runs = Vec::new();
+
+        if self.config.encodings.contains(&Encoding::Ascii) || self.config.encodings.contains(&Encoding::Utf8) {
+            let start = data[..end].iter().rposition(|&b| !self.is_printable_ascii(b)).map_or(0, |pos| pos + 1);
+            if self.long_enough(Encoding::Ascii, end - start, end - start) {
+                let content: String = data[start..end].iter().map(|&b| b as char).collect();
+                runs.push((start, content, Encoding::Ascii));
+            }
         }
 
-        // Extract UTF-16LE strings
         if self.config.encodings.contains(&Encoding::Utf16Le) {
-            results.extend(self.extract_utf16le(data, base_offset));
+            let mut start = end;
+            while start >= 2 && data[start - 1] == 0 && self.is_printable_ascii(data[start - 2]) {
+                start -= 2;
+            }
+            if self.long_enough(Encoding::Utf16Le, (end - start) / 2, end - start) {
+                let content: String = data[start..end].iter().step_by(2).map(|&b| b as char).collect();
+                runs.push((start, content, Encoding::Utf16Le));
+            }
+        }
+
+        runs.into_iter()
+            .filter(|(_, content, _)| self.matches_search_criteria(content))
+            .filter_map(|(start, content, encoding)| self.build_result(data, start..end, base_offset, content, encoding, None))
+            .collect()
+    }
+
+    /// Pass each string found in `data` to `callback` instead of collecting them
+    ///
+    /// Results arrive scanner by scanner (ASCII/UTF-8, UTF-16LE, UTF-16BE, code
+    /// page, GBK, signature), each in offset order, so they are not merged by
+    /// offset and `gap_before` is not set. GBK is scanned in parallel, so its results are
+    /// only passed on once the GBK scan is done.
+    ///
+    /// Returning `ControlFlow::Break` from `callback` ends the scan: the
+    /// current scanner stops and the remaining ones are skipped.
+    pub fn extract_with<F: FnMut(FoundString) -> ControlFlow<()>>(&self, data: &[u8], base_offset: u64, mut callback: F) {
+        let stopped = Cell::new(false);
+        let mut on_match = |start, end, content, encoding, note| {
+            if let Some(found) = self.build_result(data, start..end, base_offset, content, encoding, note) {
+                let embedded = self.embedded_ascii(&found);
+                let flow = match callback(found) {
+                    ControlFlow::Continue(()) => embedded.map_or(ControlFlow::Continue(()), &mut callback),
+                    ControlFlow::Break(()) => ControlFlow::Break(()),
+                };
+                stopped.set(flow.is_break());
+                return flow;
+            }
+            ControlFlow::Continue(())
+        };
+
+        // Extract ASCII/UTF-8 and UTF-16LE strings, together when adaptive
+        if self.is_adaptive() {
+            self.scan_adaptive(data, &mut on_match);
+        } else {
+            if self.config.encodings.contains(&Encoding::Ascii)
+                || self.config.encodings.contains(&Encoding::Utf8) {
+                self.scan_ascii_utf8(data, &mut on_match);
+            }
+            if !stopped.get() && self.config.encodings.contains(&Encoding::Utf16Le) {
+                self.scan_utf16le(data, &mut on_match);
+            }
         }
 
         // Extract UTF-16BE strings
-        if self.config.encodings.contains(&Encoding::Utf16Be) {
-            results.extend(self.extract_utf16be(data, base_offset));
+        if !stopped.get() && self.config.encodings.contains(&Encoding::Utf16Be) {
+            self.scan_utf16be(data, &mut on_match);
+        }
+
+        // Extract strings in the custom code page
+        if let Some(codec) = self.config.codepage.filter(|_| !stopped.get()) {
+            self.scan_codepage(data, codec, &mut on_match);
+        }
+        if stopped.get() {
+            return;
         }
 
         // Extract GBK strings
+        if self.config.encodings.contains(&Encoding::Gbk)
+            && self.extract_gbk_only(data, base_offset).into_iter().try_for_each(&mut callback).is_break()
+        {
+            return;
+        }
+
+        // Find raw signature matches
+        if let Some(ref signature) = self.config.signature {
+            let content = hex::encode(signature);
+            self.scan_signature(data, signature, &mut |start| {
+                callback(self.signature_result(data, start, base_offset, &content))
+            });
+        }
+    }
+
+    /// Count the strings [`extract_strings`](Self::extract_strings) would return for `data`
+    ///
+    /// Runs the same scanners and filters but only bumps a counter, so no
+    /// `FoundString`s, context or raw byte copies are allocated. Offsets do not
+    /// affect the count, so no base offset is taken.
+    pub fn count_strings(&self, data: &[u8]) -> usize {
+        self.count_strings_by_encoding(data).iter().map(|(_, count)| count).sum()
+    }
+
+    /// Like [`count_strings`](Self::count_strings), broken down by encoding
+    ///
+    /// Encodings are listed in [`Encoding::ALL`] order, followed by the custom
+    /// code page; those without any string are left out.
+    pub fn count_strings_by_encoding(&self, data: &[u8]) -> Vec<(Encoding, usize)> {
+        self.count_strings_starting_before(data, data.len())
+    }
+
+    /// Like [`count_strings_by_encoding`](Self::count_strings_by_encoding), counting only strings that start before `limit`
+    ///
+    /// Lets overlapping chunks each count the strings starting in their own
+    /// part, so no string is counted twice.
+    pub fn count_strings_starting_before(&self, data: &[u8], limit: usize) -> Vec<(Encoding, usize)> {
+        let slot = |encoding: Encoding| Encoding::ALL.iter().position(|&e| e == encoding).expect("ALL lists every fixed encoding");
+        let mut counts = [0; Encoding::ALL.len()];
+        let mut custom = 0;
+        let mut count_match = |start, end, content, encoding, _| {
+            if start >= limit {
+                return ControlFlow::Continue(());
+            }
+            if let Some((_, _, content, _, _)) = self.finish_match(data, start, end, content, encoding) {
+                match encoding {
+                    Encoding::Custom(_) => custom += 1,
+                    _ => counts[slot(encoding)] += 1,
+                }
+                if self.embedded_ascii_content(&content, encoding).is_some() {
+                    counts[slot(Encoding::Ascii)] += 1;
+                }
+            }
+            ControlFlow::Continue(())
+        };
+
+        if self.is_adaptive() {
+            self.scan_adaptive(data, &mut count_match);
+        } else {
+            if self.config.encodings.contains(&Encoding::Ascii)
+                || self.config.encodings.contains(&Encoding::Utf8) {
+                self.scan_ascii_utf8(data, &mut count_match);
+            }
+            if self.config.encodings.contains(&Encoding::Utf16Le) {
+                self.scan_utf16le(data, &mut count_match);
+            }
+        }
+        if self.config.encodings.contains(&Encoding::Utf16Be) {
+            self.scan_utf16be(data, &mut count_match);
+        }
+        if let Some(codec) = self.config.codepage {
+            self.scan_codepage(data, codec, &mut count_match);
+        }
         if self.config.encodings.contains(&Encoding::Gbk) {
-            results.extend(self.extract_gbk(data, base_offset));
+            // GBK runs holding only ASCII are reported as ASCII
+            let gbk_counts = self.scan_sub_ranges(data, MULTIBYTE_SUB_RANGE_SIZE, |range| {
+                let (mut gbk, mut ascii) = (0, 0);
+                self.scan_gbk(data, range, &mut |start, end, content, encoding, _| {
+                    if start < limit && self.finish_match(data, start, end, content, encoding).is_some() {
+                        match encoding {
+                            Encoding::Gbk => gbk += 1,
+                            _ => ascii += 1,
+                        }
+                    }
+                    ControlFlow::Continue(())
+                });
+                (gbk, ascii)
+            });
+            for (gbk, ascii) in gbk_counts {
+                counts[slot(Encoding::Gbk)] += gbk;
+                counts[slot(Encoding::Ascii)] += ascii;
+            }
+        }
+        if let Some(ref signature) = self.config.signature {
+            self.scan_signature(data, signature, &mut |start| {
+                if start < limit {
+                    counts[slot(Encoding::Signature)] += 1;
+                }
+                ControlFlow::Continue(())
+            });
+        }
+
+        let custom = self.config.codepage.map(|codec| (Encoding::Custom(codec.name()), custom));
+        Encoding::ALL.into_iter().zip(counts).chain(custom).filter(|&(_, count)| count > 0).collect()
+    }
+
+    /// The narrow copy of a UTF-16 result reported with `--embedded-ascii`
+    fn embedded_ascii(&self, wide: &FoundString) -> Option<FoundString> {
+        let content = self.embedded_ascii_content(&wide.content, wide.encoding)?;
+        Some(FoundString {
+            content,
+            encoding: Encoding::Ascii,
+            wide_offset: Some(wide.offset),
+            decode_note: Some(DecodeNote::EmbeddedAscii),
+            confidence: None,
+            ..wide.clone()
+        })
+    }
+
+    /// Content of the `--embedded-ascii` copy of a string, if it gets one
+    fn embedded_ascii_content(&self, content: &str, encoding: Encoding) -> Option<String> {
+        if !self.config.embedded_ascii || !matches!(encoding, Encoding::Utf16Le | Encoding::Utf16Be) {
+            return None;
         }
+        let narrow: String = content
+            .chars()
+            .filter_map(|c| match u8::try_from(c) {
+                Ok(byte) if self.is_printable_ascii(byte) => Some(c),
+                _ => self.config.replacement_char,
+            })
+            .collect();
+        self.long_enough(Encoding::Ascii, narrow.chars().count(), narrow.len()).then_some(narrow)
+    }
+
+    /// Find every (possibly overlapping) occurrence of the configured signature
+    ///
+    /// Matches bypass the string filters (length, search, charset); each one is
+    /// reported with encoding `Signature` and the signature in hex as content.
+    pub fn extract_signature_only(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
+        let Some(ref signature) = self.config.signature else {
+            return Vec::new();
+        };
+        let content = hex::encode(signature);
 
+        let mut results = Vec::new();
+        self.scan_signature(data, signature, &mut |start| {
+            results.push(self.signature_result(data, start, base_offset, &content));
+            ControlFlow::Continue(())
+        });
         results
     }
 
-    /// Extract ASCII and UTF-8 strings
-    fn extract_ascii_utf8(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
+    /// Result for the signature match at `start`, with the signature's hex as content
+    fn signature_result(&self, data: &[u8], start: usize, base_offset: u64, content: &str) -> FoundString {
+        let signature = self.config.signature.as_deref().unwrap_or_default();
+        let end = start + signature.len();
+        let (context_before, context_after) = self.extract_context(data, start, end, Encoding::Signature);
+        FoundString {
+            offset: Offset(base_offset + start as u64),
+            content: content.to_string(),
+            encoding: Encoding::Signature,
+            byte_length: signature.len(),
+            context_before,
+            context_after,
+            raw_bytes: self.config.keep_raw_bytes.then(|| signature.to_vec()),
+            wide_offset: None,
+            had_bom: false,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note: None,
+            confidence: None,
+        }
+    }
+
+    /// Pass the start of every (possibly overlapping) occurrence of `signature` to `on_match`
+    fn scan_signature(&self, data: &[u8], signature: &[u8], on_match: &mut impl FnMut(usize) -> ControlFlow<()>) {
+        let finder = memchr::memmem::Finder::new(signature);
+        let mut pos = 0;
+        while let Some(found) = finder.find(&data[pos..]) {
+            if on_match(pos + found).is_break() {
+                return;
+            }
+            pos += found + 1;
+        }
+    }
+
+    /// Extract ASCII and UTF-8 strings, ignoring the configured encodings
+    ///
+    /// ASCII and UTF-8 share one scanner, so this is what `extract_strings`
+    /// runs when either of them is enabled.
+    pub fn extract_ascii_utf8_only(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
         let mut results = Vec::with_capacity(256);
+        self.scan_ascii_utf8(data, &mut |start, end, content, encoding, note| {
+            results.extend(self.build_result(data, start..end, base_offset, content, encoding, note));
+            ControlFlow::Continue(())
+        });
+        results
+    }
+
+    /// Scan `data` for ASCII and UTF-8 strings, passing each candidate to `on_match`
+    fn scan_ascii_utf8(&self, data: &[u8], on_match: &mut impl FnMut(usize, usize, String, Encoding, Option<DecodeNote>) -> ControlFlow<()>) {
         let mut i = 0;
         let data_len = data.len();
 
@@ -129,36 +951,35 @@ impl StringExtractor {
             // Look for potential string start (printable ASCII)
             if self.is_printable_ascii(data[i]) {
                 let start = i;
+                let mut chars = 0;
                 let mut has_non_ascii = false;
 
                 // Fast path: scan for ASCII printable characters
                 while i < data_len {
                     let byte = data[i];
 
-                    // Stop at null terminator or control characters (except space and tab)
-                    if byte == 0 || (byte < 0x20 && byte != 0x09) {
+                    // Stop at null terminator or control characters (except allowed whitespace)
+                    if self.is_terminator(byte) {
                         break;
                     }
 
                     // For ASCII printable characters, continue
-                    if self.is_printable_ascii(byte) || byte == 0x09 { // Include tab
+                    if self.is_printable_ascii(byte) {
                         i += 1;
+                        chars += 1;
                         continue;
                     }
 
-                    // Mark that we found non-ASCII and break
+                    // Skip a valid UTF-8 sequence; a lead byte starting an invalid one is
+                    // kept alone so the run falls back to ASCII, other bytes end the run
                     if (byte & 0x80) != 0 {
-                        has_non_ascii = true;
-                        // Try to skip this UTF-8 sequence
-                        if byte & 0xE0 == 0xC0 && i + 1 < data_len { // 2-byte sequence
-                            i += 2;
-                        } else if byte & 0xF0 == 0xE0 && i + 2 < data_len { // 3-byte sequence
-                            i += 3;
-                        } else if byte & 0xF8 == 0xF0 && i + 3 < data_len { // 4-byte sequence
-                            i += 4;
-                        } else {
-                            break; // Invalid UTF-8
+                        match utf8_sequence_len(&data[i..]) {
+                            Some(len) => i += len,
+                            None if (0xC0..0xF8).contains(&byte) => i += 1,
+                            None => break,
                         }
+                        chars += 1;
+                        has_non_ascii = true;
                     } else {
                         // Non-printable ASCII, stop
                         break;
@@ -166,24 +987,40 @@ impl StringExtractor {
                 }
 
                 let byte_length = i - start;
-                if byte_length >= self.config.min_len {
+                let encoding = if has_non_ascii { Encoding::Utf8 } else { Encoding::Ascii };
+                if self.long_enough(encoding, chars, byte_length) {
                     let string_bytes = &data[start..i];
 
                     // Only validate UTF-8 if we found non-ASCII bytes
-                    let (content, encoding) = if has_non_ascii {
+                    let mut end = i;
+                    let (content, encoding, note) = if has_non_ascii {
                         match std::str::from_utf8(string_bytes) {
-                            Ok(s) => (s.to_string(), Encoding::Utf8),
+                            Ok(s) => (s.to_string(), Encoding::Utf8, None),
+                            Err(e) if self.config.follow_utf8_continuation => {
+                                // Keep the valid prefix and resume scanning right after the bad byte
+                                end = start + e.valid_up_to();
+                                i = end + e.error_len().unwrap_or(1);
+                                let prefix = String::from_utf8_lossy(&data[start..end]).into_owned();
+                                let encoding = if prefix.is_ascii() { Encoding::Ascii } else { Encoding::Utf8 };
+                                if !self.long_enough(encoding, prefix.chars().count(), end - start) {
+                                    continue;
+                                }
+                                (prefix, encoding, Some(DecodeNote::Utf8Truncated))
+                            }
                             Err(_) => {
-                                // Convert to ASCII, replacing invalid bytes
+                                // Convert to ASCII, replacing (or dropping) invalid bytes
                                 let ascii_string: String = string_bytes
                                     .iter()
-                                    .map(|&b| if b.is_ascii_graphic() || b == b' ' || b == b'\t' {
-                                        b as char
+                                    .filter_map(|&b| if self.is_printable_ascii(b) {
+                                        Some(b as char)
                                     } else {
-                                        '?'
+                                        self.config.replacement_char
                                     })
                                     .collect();
-                                (ascii_string, Encoding::Ascii)
+                                if !self.long_enough(Encoding::Ascii, ascii_string.chars().count(), byte_length) {
+                                    continue;
+                                }
+                                (ascii_string, Encoding::Ascii, Some(DecodeNote::Utf8FallbackAscii))
                             }
                         }
                     } else {
@@ -191,32 +1028,38 @@ impl StringExtractor {
                         let ascii_string = unsafe {
                             std::str::from_utf8_unchecked(string_bytes).to_string()
                         };
-                        (ascii_string, Encoding::Ascii)
+                        (ascii_string, Encoding::Ascii, None)
                     };
 
-                    if self.matches_search_criteria(&content) {
-                        let (context_before, context_after) = self.extract_context(data, start, i);
-                        results.push(FoundString {
-                            offset: base_offset + start as u64,
-                            content,
-                            encoding,
-                            byte_length,
-                            context_before,
-                            context_after,
-                        });
+                    if self.matches_search_criteria(&content) && on_match(start, end, content, encoding, note).is_break() {
+                        return;
                     }
                 }
             } else {
                 i += 1;
             }
         }
+    }
 
+    /// Extract UTF-16LE strings, ignoring the configured encodings
+    pub fn extract_utf16le_only(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
+        let mut results = Vec::new();
+        self.scan_utf16le(data, &mut |start, end, content, encoding, note| {
+            results.extend(self.build_result(data, start..end, base_offset, content, encoding, note));
+            ControlFlow::Continue(())
+        });
         results
     }
 
-    /// Extract UTF-16LE strings
-    fn extract_utf16le(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
-        let mut results = Vec::new();
+    /// Scan `data` for UTF-16LE strings, passing each candidate to `on_match`
+    ///
+    /// Strings may start at any offset, even or odd. When a stray byte sits
+    /// between two wide strings, the following string starts at the other
+    /// alignment. The stray byte and that string's first character would then
+    /// read as one unit. Instead the current string ends at the stray byte,
+    /// and the scan resumes there. Nothing is reported twice, so no extra pass
+    /// or deduplication is needed.
+    fn scan_utf16le(&self, data: &[u8], on_match: &mut impl FnMut(usize, usize, String, Encoding, Option<DecodeNote>) -> ControlFlow<()>) {
         let mut i = 0;
 
         while i + 1 < data.len() {
@@ -236,35 +1079,31 @@ impl StringExtractor {
                     }
                     
                     // Check if it's a valid UTF-16LE character
-                    if high == 0x00 && self.is_printable_ascii(low) {
+                    if high == 0x00 && !self.ends_wide_string(low) {
                         utf16_bytes.push(low as u16);
                         i += 2;
+                    } else if high == 0x00 {
+                        // DEL, C1 controls and disallowed tab/newline end the string
+                        break;
+                    } else if self.is_printable_ascii(high) && data.get(i + 2) == Some(&0x00) {
+                        // A stray byte shifted the next string to the other alignment:
+                        // end here so it is found from its first character
+                        break;
                     } else {
                         // Try to decode as full UTF-16
                         let code_unit = u16::from_le_bytes([low, high]);
                         utf16_bytes.push(code_unit);
                         i += 2;
-                        
-                        // If it's not a simple ASCII pattern, be more conservative
-                        if high != 0x00 {
-                            break;
-                        }
+
+                        // It's not a simple ASCII pattern, be more conservative
+                        break;
                     }
                 }
 
-                let byte_length = i - start;
-                if utf16_bytes.len() >= self.config.min_len {
+                if self.long_enough(Encoding::Utf16Le, utf16_bytes.len(), i - start) {
                     if let Ok(content) = String::from_utf16(&utf16_bytes) {
-                        if self.matches_search_criteria(&content) {
-                            let (context_before, context_after) = self.extract_context(data, start, i);
-                            results.push(FoundString {
-                                offset: base_offset + start as u64,
-                                content,
-                                encoding: Encoding::Utf16Le,
-                                byte_length,
-                                context_before,
-                                context_after,
-                            });
+                        if self.matches_search_criteria(&content) && on_match(start, i, content, Encoding::Utf16Le, None).is_break() {
+                            return;
                         }
                     }
                 }
@@ -272,13 +1111,77 @@ impl StringExtractor {
                 i += 1;
             }
         }
+    }
 
-        results
+    /// Whether the adaptive scanner replaces the separate ASCII/UTF-8 and UTF-16LE scans
+    fn is_adaptive(&self) -> bool {
+        let encodings = &self.config.encodings;
+        self.config.adaptive
+            && (encodings.contains(&Encoding::Ascii) || encodings.contains(&Encoding::Utf8))
+            && encodings.contains(&Encoding::Utf16Le)
+    }
+
+    /// Scan `data` for strings mixing ASCII and UTF-16LE runs (`--adaptive`)
+    ///
+    /// A heuristic: at each position the encoding yielding the longer
+    /// printable run is followed and the scan advances past that run, so
+    /// `"key="` directly followed by `"v\0a\0l\0"` is one string `key=val`
+    /// rather than two fragments. Only printable ASCII characters are
+    /// recognised in either width. A result is labelled with the encoding that
+    /// contributed more of its characters.
+    fn scan_adaptive(&self, data: &[u8], on_match: &mut impl FnMut(usize, usize, String, Encoding, Option<DecodeNote>) -> ControlFlow<()>) {
+        let wide_run = |at: usize| {
+            data[at..].chunks_exact(2).take_while(|unit| unit[1] == 0 && self.is_printable_ascii(unit[0])).count()
+        };
+
+        let mut i = 0;
+        while i < data.len() {
+            if !self.is_printable_ascii(data[i]) {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            let mut content = String::new();
+            let (mut narrow, mut wide) = (0, 0);
+            while i < data.len() && self.is_printable_ascii(data[i]) {
+                // A narrow run stops at the NUL after this byte, so a wide run of two or more units is longer
+                let units = if data.get(i + 1) == Some(&0) { wide_run(i) } else { 0 };
+                if units > 1 {
+                    content.extend(data[i..i + 2 * units].iter().step_by(2).map(|&b| b as char));
+                    wide += units;
+                    i += 2 * units;
+                } else {
+                    content.push(data[i] as char);
+                    narrow += 1;
+                    i += 1;
+                }
+            }
+
+            let encoding = if wide > narrow { Encoding::Utf16Le } else { Encoding::Ascii };
+            // A mixed-width run has no single native unit, so it counts characters
+            let long_enough = match self.config.min_len_unit {
+                MinLenUnit::Native => narrow + wide >= self.min_len(encoding),
+                _ => self.long_enough(encoding, narrow + wide, i - start),
+            };
+            if long_enough && self.matches_search_criteria(&content) && on_match(start, i, content, encoding, None).is_break() {
+                return;
+            }
+        }
     }
 
-    /// Extract UTF-16BE strings
-    fn extract_utf16be(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
+    /// Extract UTF-16BE strings, ignoring the configured encodings
+    pub fn extract_utf16be_only(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
         let mut results = Vec::new();
+        self.scan_utf16be(data, &mut |start, end, content, encoding, note| {
+            results.extend(self.build_result(data, start..end, base_offset, content, encoding, note));
+            ControlFlow::Continue(())
+        });
+        results
+    }
+
+    /// Scan `data` for UTF-16BE strings, passing each candidate to `on_match`
+    fn scan_utf16be(&self, data: &[u8], on_match: &mut impl FnMut(usize, usize, String, Encoding, Option<DecodeNote>) -> ControlFlow<()>) {
         let mut i = 0;
 
         while i + 1 < data.len() {
@@ -298,35 +1201,27 @@ impl StringExtractor {
                     }
                     
                     // Check if it's a valid UTF-16BE character
-                    if high == 0x00 && self.is_printable_ascii(low) {
+                    if high == 0x00 && !self.ends_wide_string(low) {
                         utf16_bytes.push(low as u16);
                         i += 2;
+                    } else if high == 0x00 {
+                        // DEL, C1 controls and disallowed tab/newline end the string
+                        break;
                     } else {
                         // Try to decode as full UTF-16
                         let code_unit = u16::from_be_bytes([high, low]);
                         utf16_bytes.push(code_unit);
                         i += 2;
-                        
-                        // If it's not a simple ASCII pattern, be more conservative
-                        if high != 0x00 {
-                            break;
-                        }
+
+                        // It's not a simple ASCII pattern, be more conservative
+                        break;
                     }
                 }
 
-                let byte_length = i - start;
-                if utf16_bytes.len() >= self.config.min_len {
+                if self.long_enough(Encoding::Utf16Be, utf16_bytes.len(), i - start) {
                     if let Ok(content) = String::from_utf16(&utf16_bytes) {
-                        if self.matches_search_criteria(&content) {
-                            let (context_before, context_after) = self.extract_context(data, start, i);
-                            results.push(FoundString {
-                                offset: base_offset + start as u64,
-                                content,
-                                encoding: Encoding::Utf16Be,
-                                byte_length,
-                                context_before,
-                                context_after,
-                            });
+                        if self.matches_search_criteria(&content) && on_match(start, i, content, Encoding::Utf16Be, None).is_break() {
+                            return;
                         }
                     }
                 }
@@ -334,17 +1229,48 @@ impl StringExtractor {
                 i += 1;
             }
         }
+    }
 
-        results
+    /// Extract GBK strings, ignoring the configured encodings
+    pub fn extract_gbk_only(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
+        self.scan_sub_ranges(data, MULTIBYTE_SUB_RANGE_SIZE, |range| {
+            let mut results = Vec::new();
+            self.scan_gbk(data, range, &mut |start, end, content, encoding, note| {
+                results.extend(self.build_result(data, start..end, base_offset, content, encoding, note));
+                ControlFlow::Continue(())
+            });
+            results
+        })
+        .into_iter()
+        .flatten()
+        .collect()
     }
 
-    /// Extract GBK strings
-    fn extract_gbk(&self, data: &[u8], base_offset: u64) -> Vec<FoundString> {
-        let mut results = Vec::new();
-        let mut i = 0;
+    /// Run a multibyte scanner over independent sub-ranges of `data` in parallel
+    ///
+    /// Sub-ranges only end on terminator bytes, which no scanner consumes, so each
+    /// range can be scanned on its own and the merged outputs (in range order) are
+    /// identical to a single pass over the whole slice. Scanners should read the
+    /// full slice so context extraction is not clipped at sub-range edges.
+    fn scan_sub_ranges<T, F>(&self, data: &[u8], sub_range_size: usize, scan: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(Range<usize>) -> T + Sync,
+    {
+        let ranges = split_at_terminators(data, sub_range_size, |b| self.is_terminator(b));
+        if ranges.len() <= 1 {
+            return vec![scan(0..data.len())];
+        }
+
+        ranges.into_par_iter().map(&scan).collect()
+    }
+
+    /// Scan one sub-range of `data` for GBK strings, passing each candidate to `on_match`
+    fn scan_gbk(&self, data: &[u8], range: Range<usize>, on_match: &mut impl FnMut(usize, usize, String, Encoding, Option<DecodeNote>) -> ControlFlow<()>) {
+        let mut i = range.start;
         let data_len = data.len();
 
-        while i < data_len {
+        while i < range.end {
             // Look for potential GBK string start
             // GBK first byte ranges: 0x81-0xFE
             if data[i] >= 0x81 && data[i] <= 0xFE {
@@ -352,19 +1278,19 @@ impl StringExtractor {
                 let mut gbk_bytes = Vec::new();
                 let mut consecutive_invalid = 0;
                 const MAX_INVALID_BYTES: usize = 3; // Stop after too many invalid bytes
-                const MAX_STRING_LENGTH: usize = 1024; // Prevent extremely long strings
+                let max_len = self.config.gbk_max_len; // Prevent extremely long strings
 
                 // Collect potential GBK bytes with limits
-                while i < data_len && gbk_bytes.len() < MAX_STRING_LENGTH {
+                while i < data_len && gbk_bytes.len() < max_len {
                     let byte = data[i];
 
                     // Check for null terminator or control characters
-                    if byte == 0 || (byte < 0x20 && byte != 0x09) {
+                    if self.is_terminator(byte) {
                         break;
                     }
 
                     // ASCII printable characters are valid in GBK
-                    if byte >= 0x20 && byte <= 0x7E {
+                    if self.is_printable_ascii(byte) {
                         gbk_bytes.push(byte);
                         consecutive_invalid = 0;
                         i += 1;
@@ -372,11 +1298,15 @@ impl StringExtractor {
                     }
 
                     // GBK double-byte character
-                    if byte >= 0x81 && byte <= 0xFE && i + 1 < data_len {
+                    if (0x81..=0xFE).contains(&byte) && i + 1 < data_len {
                         let second_byte = data[i + 1];
                         // GBK second byte ranges: 0x40-0x7E, 0x80-0xFE
-                        if (second_byte >= 0x40 && second_byte <= 0x7E) ||
-                           (second_byte >= 0x80 && second_byte <= 0xFE) {
+                        if (0x40..=0x7E).contains(&second_byte) ||
+                           (0x80..=0xFE).contains(&second_byte) {
+                            // Never split a character across the length cap
+                            if gbk_bytes.len() + 2 > max_len {
+                                break;
+                            }
                             gbk_bytes.push(byte);
                             gbk_bytes.push(second_byte);
                             consecutive_invalid = 0;
@@ -395,23 +1325,35 @@ impl StringExtractor {
                     i += 1;
                 }
 
-                let byte_length = i - start;
-                if gbk_bytes.len() >= self.config.min_len {
+                // No character takes less than a byte, so shorter runs fail either unit
+                let min_len = self.min_len(Encoding::Gbk);
+                if gbk_bytes.len() >= min_len {
                     // Try to decode as GBK - allow some errors for robustness
-                    let (decoded, _encoding, _had_errors) = GBK.decode(&gbk_bytes);
+                    let (decoded, _encoding, had_errors) = GBK.decode(&gbk_bytes);
+                    let chars = decoded.chars().count();
                     // Only reject if the string is mostly errors or empty
-                    if !decoded.trim().is_empty() && decoded.chars().count() >= self.config.min_len / 2 {
+                    if !decoded.trim().is_empty() && chars >= min_len / 2 && self.long_enough(Encoding::Gbk, chars, gbk_bytes.len()) {
                         let content = decoded.into_owned();
-                        if self.matches_search_criteria(&content) {
-                            let (context_before, context_after) = self.extract_context(data, start, i);
-                            results.push(FoundString {
-                                offset: base_offset + start as u64,
-                                content,
-                                encoding: Encoding::Gbk,
-                                byte_length,
-                                context_before,
-                                context_after,
-                            });
+                        // Only ASCII left (say a stray lead byte before plain text): report it as
+                        // ASCII from its first character, unless the ASCII scanner finds it there too
+                        let (start, encoding) = if content.is_ascii() {
+                            if self.config.encodings.contains(&Encoding::Ascii) || self.config.encodings.contains(&Encoding::Utf8) {
+                                continue;
+                            }
+                            let skipped = data[start..i].iter().position(|&b| self.is_printable_ascii(b)).unwrap_or(0);
+                            (start + skipped, Encoding::Ascii)
+                        } else {
+                            (start, Encoding::Gbk)
+                        };
+                        // Skipped invalid bytes or undecodable pairs leave the content incomplete
+                        let partial = had_errors || gbk_bytes.len() < i - start;
+                        // The GBK minimum was checked above; reclassified text needs the ASCII one
+                        let long_enough = encoding == Encoding::Gbk || self.long_enough(Encoding::Ascii, content.len(), content.len());
+                        if long_enough
+                            && self.matches_search_criteria(&content)
+                            && on_match(start, i, content, encoding, partial.then_some(DecodeNote::GbkPartial)).is_break()
+                        {
+                            return;
                         }
                     }
                 }
@@ -419,51 +1361,400 @@ impl StringExtractor {
                 i += 1;
             }
         }
-
-        results
     }
 
-    /// Check if a byte is a printable ASCII character
-    fn is_printable_ascii(&self, byte: u8) -> bool {
-        byte >= 0x20 && byte <= 0x7E
-    }
+    /// Scan `data` for strings in `codec` (`--codepage`), passing each candidate to `on_match`
+    ///
+    /// Each step takes the shortest run of up to four bytes that decodes to a
+    /// single character without errors; a string continues while those
+    /// characters are printable (printable ASCII as configured, otherwise any
+    /// non-control character). Lengths are in characters. Runs with only ASCII
+    /// in an ASCII-compatible codec are left to the ASCII scanner.
+    fn scan_codepage(
+        &self,
+        data: &[u8],
+        codec: &'static encoding_rs::Encoding,
+        on_match: &mut impl FnMut(usize, usize, String, Encoding, Option<DecodeNote>) -> ControlFlow<()>,
+    ) {
+        let is_printable = |c: char| match u8::try_from(c) {
+            Ok(byte) if byte.is_ascii() => self.is_printable_ascii(byte),
+            _ => !c.is_control() && c != char::REPLACEMENT_CHARACTER,
+        };
+        // Single-byte codecs decode each byte alone, so look them up once
+        let single_byte: Option<Vec<Option<char>>> = codec.is_single_byte().then(|| {
+            (0..=u8::MAX)
+                .map(|byte| {
+                    let bytes = [byte];
+                    let decoded = codec.decode_without_bom_handling_and_without_replacement(&bytes)?;
+                    decoded.chars().next().filter(|&c| is_printable(c))
+                })
+                .collect()
+        });
+        let next_char = |i: usize| -> Option<(char, usize)> {
+            if let Some(ref table) = single_byte {
+                return table[data[i] as usize].map(|c| (c, 1));
+            }
+            (1..=4.min(data.len() - i)).find_map(|len| {
+                let decoded = codec.decode_without_bom_handling_and_without_replacement(&data[i..i + len])?;
+                let mut chars = decoded.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if is_printable(c) => Some((c, len)),
+                    _ => None,
+                }
+            })
+        };
 
-    /// Check if a string matches the search criteria
-    fn matches_search_criteria(&self, content: &str) -> bool {
-        if let Some(ref regex) = self.config.regex_pattern {
-            regex.is_match(content)
-        } else if let Some(ref pattern) = self.config.search_pattern {
-            content.contains(pattern)
-        } else {
-            true
+        let mut i = 0;
+        while i < data.len() {
+            let start = i;
+            let mut content = String::new();
+            while let Some((c, len)) = (i < data.len()).then(|| next_char(i)).flatten() {
+                content.push(c);
+                i += len;
+            }
+            if i == start {
+                i += 1;
+                continue;
+            }
+
+            if codec.is_ascii_compatible() && content.is_ascii() {
+                continue;
+            }
+            let encoding = Encoding::Custom(codec.name());
+            if self.long_enough(encoding, content.chars().count(), i - start) && self.matches_search_criteria(&content) && on_match(start, i, content, encoding, None).is_break() {
+                return;
+            }
         }
     }
 
-    /// Extract context bytes around a found string
-    fn extract_context(&self, data: &[u8], start: usize, end: usize) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
-        if let Some(context_size) = self.config.context_bytes {
-            let before_start = start.saturating_sub(context_size);
-            let after_end = std::cmp::min(end + context_size, data.len());
+    /// Build a result for the match at `data[range]`
+    fn build_result(
+        &self,
+        data: &[u8],
+        range: Range<usize>,
+        base_offset: u64,
+        content: String,
+        encoding: Encoding,
+        decode_note: Option<DecodeNote>,
+    ) -> Option<FoundString> {
+        let (start, end, content, had_bom, confidence) = self.finish_match(data, range.start, range.end, content, encoding)?;
+        let (context_before, context_after) = self.extract_context(data, start, end, encoding);
+        Some(FoundString {
+            offset: Offset(base_offset + start as u64),
+            content,
+            encoding,
+            byte_length: end - start,
+            context_before,
+            context_after,
+            raw_bytes: self.config.keep_raw_bytes.then(|| data[start..end].to_vec()),
+            wide_offset: None,
+            had_bom,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note,
+            confidence,
+        })
+    }
 
-            let context_before = if before_start < start {
-                Some(data[before_start..start].to_vec())
-            } else {
-                None
-            };
+    /// Apply the byte-level filters and `--capture` narrowing to the match at `data[start..end]`
+    ///
+    /// Returns the reported range, content, BOM flag and decode confidence, or
+    /// `None` if the match is dropped.
+    fn finish_match(
+        &self,
+        data: &[u8],
+        start: usize,
+        end: usize,
+        content: String,
+        encoding: Encoding,
+    ) -> Option<(usize, usize, String, bool, Option<f32>)> {
+        if let Some(ref needle) = self.config.search_bytes {
+            memchr::memmem::find(&data[start..end], needle)?;
+        }
+        let had_bom = bom_before(data, start, encoding)?;
+
+        // With --capture, narrow the result to the capture group's span
+        let capture_group = self.config.capture_group.filter(|_| !self.config.invert_match);
+        let (start, end, content) = match (capture_group, &self.config.regex_pattern) {
+            (Some(group), Some(regex)) => {
+                let capture = regex.captures(&content)?.get(group)?;
+                let prefix = self.source_len(data, start, &content[..capture.start()], encoding);
+                let len = self.source_len(data, start, &content[..capture.end()], encoding) - prefix;
+                (start + prefix, start + prefix + len, capture.as_str().to_string())
+            }
+            _ => (start, end, content),
+        };
 
-            let context_after = if end < after_end {
-                Some(data[end..after_end].to_vec())
-            } else {
-                None
-            };
+        if let (Encoding::Ascii, Some(min_alnum_ratio)) = (encoding, self.config.min_alnum_ratio) {
+            if alnum_ratio(&content) < min_alnum_ratio {
+                return None;
+            }
+        }
 
-            (context_before, context_after)
+        let confidence = (encoding == Encoding::Gbk).then(|| gbk_confidence(&data[start..end], &content));
+        if let (Some(confidence), Some(min_confidence)) = (confidence, self.config.min_confidence) {
+            if confidence < min_confidence {
+                return None;
+            }
+        }
+        Some((start, end, content, had_bom, confidence))
+    }
+
+    /// Number of bytes from `data[start..]` that decoded to `text`, a prefix of a match's content
+    ///
+    /// ASCII and GBK content may have dropped or replaced invalid bytes, so
+    /// their source is walked the way the scanners decoded it; other
+    /// encodings decode exactly and are re-encoded.
+    fn source_len(&self, data: &[u8], start: usize, text: &str, encoding: Encoding) -> usize {
+        if !matches!(encoding, Encoding::Ascii | Encoding::Gbk) {
+            return encoded_len(text, encoding);
+        }
+        // Source bytes and decoded content bytes of the unit at data[i]
+        let unit_len = |i: usize| -> (usize, usize) {
+            let byte = data[i];
+            match encoding {
+                _ if self.is_printable_ascii(byte) => (1, 1),
+                Encoding::Ascii => (1, self.config.replacement_char.map_or(0, char::len_utf8)),
+                // A lead byte with a valid trail byte, as scan_gbk collects them; anything else was skipped
+                _ => match data.get(i + 1) {
+                    Some(&trail) if (0x81..=0xFE).contains(&byte) && matches!(trail, 0x40..=0x7E | 0x80..=0xFE) => {
+                        (2, GBK.decode_without_bom_handling(&data[i..i + 2]).0.len())
+                    }
+                    _ => (1, 0),
+                },
+            }
+        };
+        let (mut i, mut decoded) = (start, 0);
+        while decoded < text.len() && i < data.len() {
+            let (bytes, chars) = unit_len(i);
+            i += bytes;
+            decoded += chars;
+        }
+        i - start
+    }
+
+    /// Check if a byte is a printable ASCII character, including any whitespace and DEL allowed by the config
+    ///
+    /// This is the single definition of "printable" for single-byte characters;
+    /// every scanner goes through it so the options apply to all of them alike.
+    fn is_printable_ascii(&self, byte: u8) -> bool {
+        match byte {
+            0x20..=0x7E => true,
+            b'\t' => self.config.allow_tab,
+            b'\n' | b'\r' => self.config.allow_newline,
+            0x7F => self.config.del_printable,
+            _ => false,
+        }
+    }
+
+    /// Check whether a UTF-16 code unit below U+0100 ends a wide string
+    ///
+    /// DEL and the C1 controls (U+007F-U+009F) always do, tab and CR/LF only
+    /// when the config does not allow them. Latin-1 letters continue the string.
+    fn ends_wide_string(&self, low: u8) -> bool {
+        match low {
+            b'\t' | b'\n' | b'\r' | 0x7F => !self.is_printable_ascii(low),
+            0x80..=0x9F => true,
+            _ => false,
+        }
+    }
+
+    /// Check whether a byte always ends a string candidate (null or non-allowed control)
+    fn is_terminator(&self, byte: u8) -> bool {
+        byte == 0 || (byte < 0x20 && !self.is_printable_ascii(byte))
+    }
+
+    /// Check if a string matches the search criteria
+    fn matches_search_criteria(&self, content: &str) -> bool {
+        if self.config.drop_whitespace && content.trim().is_empty() {
+            return false;
+        }
+
+        if let Some(ref charset) = self.config.charset {
+            if !charset.accepts(content) {
+                return false;
+            }
+        }
+
+        if !self.config.scripts.is_empty() && !content.chars().any(|c| self.config.scripts.contains(&c.script())) {
+            return false;
+        }
+
+        if let Some(min_words) = self.config.min_words {
+            if count_words(content) < min_words {
+                return false;
+            }
+        }
+
+        if let Some(min_unique_chars) = self.config.min_unique_chars {
+            if !has_unique_chars(content, min_unique_chars) {
+                return false;
+            }
+        }
+
+        if self.config.paths_only && !classify::is_path(content) {
+            return false;
+        }
+
+        if let Some(ref regex) = self.config.regex_pattern {
+            regex.is_match(content) != self.config.invert_match
+        } else if let Some(ref pattern) = self.config.search_pattern {
+            content.contains(pattern) != self.config.invert_match
         } else {
-            (None, None)
+            true
+        }
+    }
+
+    /// Extract context bytes around a found string
+    ///
+    /// With context trimming, each side also stops at the nearest null or
+    /// control character, read in `encoding`'s code units.
+    fn extract_context(
+        &self,
+        data: &[u8],
+        start: usize,
+        end: usize,
+        encoding: Encoding,
+    ) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+        let unit = match encoding {
+            Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            _ => 1,
+        };
+        let is_boundary = |bytes: &[u8]| match encoding {
+            Encoding::Utf16Le => u16::from_le_bytes([bytes[0], bytes[1]]) < 0x20,
+            Encoding::Utf16Be => u16::from_be_bytes([bytes[0], bytes[1]]) < 0x20,
+            _ => bytes[0] < 0x20,
+        };
+
+        let context_before = self.config.context_before.and_then(|size| {
+            let mut before_start = start.saturating_sub(size);
+            if self.config.context_trim {
+                let mut pos = start;
+                while pos >= before_start + unit && !is_boundary(&data[pos - unit..pos]) {
+                    pos -= unit;
+                }
+                before_start = pos;
+            }
+            (before_start < start).then(|| data[before_start..start].to_vec())
+        });
+
+        let context_after = self.config.context_after.and_then(|size| {
+            let mut after_end = std::cmp::min(end + size, data.len());
+            if self.config.context_trim {
+                let mut pos = end;
+                while pos + unit <= after_end && !is_boundary(&data[pos..pos + unit]) {
+                    pos += unit;
+                }
+                after_end = pos;
+            }
+            (end < after_end).then(|| data[end..after_end].to_vec())
+        });
+
+        (context_before, context_after)
+    }
+}
+
+/// Number of bytes `text` occupies in the source `encoding`
+fn encoded_len(text: &str, encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Ascii | Encoding::Utf8 => text.len(),
+        Encoding::Utf16Le | Encoding::Utf16Be => text.encode_utf16().count() * 2,
+        Encoding::Gbk => GBK.encode(text).0.len(),
+        Encoding::Signature => text.len() / 2,
+        Encoding::Custom(name) => {
+            encoding_rs::Encoding::for_label(name.as_bytes()).map_or(text.len(), |codec| codec.encode(text).0.len())
         }
     }
 }
 
+/// Count whitespace-separated tokens made only of letters and digits, ignoring edge punctuation
+fn count_words(content: &str) -> usize {
+    content
+        .split_whitespace()
+        .map(|token| token.trim_matches(|c: char| c.is_ascii_punctuation()))
+        .filter(|token| !token.is_empty() && token.chars().all(char::is_alphanumeric))
+        .count()
+}
+
+/// Set `gap_before` on offset-ordered `results` from the end of each one's predecessor
+fn set_gaps(results: &mut [FoundString]) {
+    let mut previous_end = None;
+    for found_string in results {
+        let start = found_string.offset.get();
+        found_string.gap_before = previous_end.map(|end: u64| start.saturating_sub(end));
+        previous_end = Some(start + found_string.byte_length as u64);
+    }
+}
+
+/// Length of the valid UTF-8 sequence at the start of `bytes`, if there is one
+///
+/// Continuation bytes must be 0x80-0xBF; overlong encodings, surrogates and
+/// code points above U+10FFFF are rejected, as are sequences cut off by the
+/// end of `bytes`.
+fn utf8_sequence_len(bytes: &[u8]) -> Option<usize> {
+    let (len, second) = match *bytes.first()? {
+        0xC2..=0xDF => (2, 0x80..=0xBF),
+        0xE0 => (3, 0xA0..=0xBF),
+        0xE1..=0xEC | 0xEE..=0xEF => (3, 0x80..=0xBF),
+        0xED => (3, 0x80..=0x9F),
+        0xF0 => (4, 0x90..=0xBF),
+        0xF1..=0xF3 => (4, 0x80..=0xBF),
+        0xF4 => (4, 0x80..=0x8F),
+        _ => return None,
+    };
+    let tail = bytes.get(1..len)?;
+    (second.contains(&tail[0]) && tail[1..].iter().all(|b| (0x80..=0xBF).contains(b))).then_some(len)
+}
+
+/// Whether `content` contains at least `min` distinct characters
+fn has_unique_chars(content: &str, min: usize) -> bool {
+    let mut seen = HashSet::new();
+    min == 0 || content.chars().any(|c| seen.insert(c) && seen.len() >= min)
+}
+
+/// Share of `content`'s characters that are ASCII letters or digits, 0.0 for an empty string
+fn alnum_ratio(content: &str) -> f32 {
+    let total = content.chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+    content.chars().filter(char::is_ascii_alphanumeric).count() as f32 / total as f32
+}
+
+/// Split `data` into ranges of roughly `target_size` bytes, each ending on a terminator byte
+fn split_at_terminators<F>(data: &[u8], target_size: usize, is_terminator: F) -> Vec<Range<usize>>
+where
+    F: Fn(u8) -> bool,
+{
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    while start < data.len() {
+        let nominal_end = start.saturating_add(target_size.max(1));
+        if nominal_end >= data.len() {
+            ranges.push(start..data.len());
+            break;
+        }
+
+        match data[nominal_end..].iter().position(|&b| is_terminator(b)) {
+            Some(pos) => {
+                ranges.push(start..nominal_end + pos);
+                start = nominal_end + pos;
+            }
+            None => {
+                // No safe split point left, scan the remainder as one range
+                ranges.push(start..data.len());
+                break;
+            }
+        }
+    }
+
+    ranges
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -542,6 +1833,363 @@ mod tests {
         assert!(results.iter().any(|s| s.encoding == Encoding::Gbk));
     }
 
+    #[test]
+    fn test_search_bytes_matches_raw_gbk() {
+        // "你好世界" in GBK, then "hello world" in ASCII
+        let mut data = vec![0xC4, 0xE3, 0xBA, 0xC3, 0xCA, 0xC0, 0xBD, 0xE7, 0x00];
+        data.extend_from_slice(b"hello world\x00");
+
+        // 0xC3CA straddles the second and third characters
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Gbk], None, false, None)
+            .unwrap()
+            .with_search_bytes(Some(vec![0xC3, 0xCA]));
+        let results = extractor.extract_strings(&data, 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "你好世界");
+        assert_eq!(results[0].encoding, Encoding::Gbk);
+
+        let extractor = extractor.with_search_bytes(Some(b"o w".to_vec()));
+        let found: Vec<String> = extractor.extract_strings(&data, 0).into_iter().map(|s| s.content).collect();
+        assert_eq!(found, ["hello world"]);
+    }
+
+    #[test]
+    fn test_byte_order_marks() {
+        let extractor = StringExtractor::new(
+            4,
+            vec![EncodingType::Utf8, EncodingType::Utf16Le, EncodingType::Utf16Be],
+            None,
+            false,
+            None,
+        )
+        .unwrap();
+        let utf16le = |text: &str| text.encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<u8>>();
+        let utf16be = |text: &str| text.encode_utf16().flat_map(u16::to_be_bytes).collect::<Vec<u8>>();
+        let found = |data: &[u8]| {
+            extractor
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| (s.offset.get(), s.encoding, s.content, s.had_bom))
+                .collect::<Vec<_>>()
+        };
+
+        let data = [b"\x00\xEF\xBB\xBFcaf\xC3\xA9 menu\x00".as_slice(), b"plain text\x00"].concat();
+        assert_eq!(
+            found(&data),
+            [
+                (4, Encoding::Utf8, "café menu".to_string(), true),
+                (15, Encoding::Ascii, "plain text".to_string(), false),
+            ]
+        );
+
+        // The BOM also rules out the shifted big-endian reading of the text
+        let data = [&[0xFF, 0xFE][..], &utf16le("Hello"), &[0, 0]].concat();
+        assert_eq!(found(&data), [(2, Encoding::Utf16Le, "Hello".to_string(), true)]);
+
+        let data = [&[0xFE, 0xFF][..], &utf16be("Hello"), &[0, 0]].concat();
+        assert_eq!(found(&data), [(2, Encoding::Utf16Be, "Hello".to_string(), true)]);
+
+        // Without a BOM both byte orders are reported
+        let data = [&[0x01][..], &utf16le("Hello"), &[0, 0]].concat();
+        let found = found(&data);
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|(_, _, _, had_bom)| !had_bom));
+    }
+
+    #[test]
+    fn test_extract_strings_orders_by_offset() {
+        let mut data = Vec::new();
+        data.extend("wide first".encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend_from_slice(b"\x00\x00narrow second\x00");
+        data.extend("wide third".encode_utf16().flat_map(u16::to_be_bytes));
+        data.extend_from_slice(b"\x00\x00\xC4\xE3\xBA\xC3\xCA\xC0\xBD\xE7\x00MZ\x00ascii last\x00");
+
+        let extractor = StringExtractor::new(4, EncodingType::ALL.to_vec(), None, false, None)
+            .unwrap()
+            .with_signature(Some(b"MZ".to_vec()));
+        let results = extractor.extract_strings(&data, 0x1000);
+
+        let encodings: HashSet<Encoding> = results.iter().map(|s| s.encoding).collect();
+        assert!(encodings.len() >= 5, "{:?}", encodings);
+        assert!(results.windows(2).all(|pair| pair[0].offset <= pair[1].offset));
+        assert_eq!(results.last().unwrap().content, "ascii last");
+    }
+
+    #[test]
+    fn test_extract_with_visits_every_result() {
+        let mut data = b"\x00MZ\x90\x00first string\x00\x00\x00".to_vec();
+        data.extend("wide string".encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend(b"\x00\x00second string\x00\xC4\xE3\xBA\xC3\xCA\xC0\xBD\xE7\x00");
+        let extractor = StringExtractor::new(4, EncodingType::ALL.to_vec(), None, false, Some(4))
+            .unwrap()
+            .with_signature(Some(b"MZ".to_vec()));
+
+        let mut calls = 0;
+        let mut visited = Vec::new();
+        extractor.extract_with(&data, 0x100, |found| {
+            calls += 1;
+            visited.push(found);
+            ControlFlow::Continue(())
+        });
+        let in_order: Vec<_> = visited.iter().map(|s| (s.offset, s.encoding)).collect();
+        visited.sort_by_key(|s| s.offset);
+        let collected = extractor.extract_strings(&data, 0x100);
+        assert_eq!(calls, collected.len());
+        assert!(calls >= 5, "{:?}", collected);
+        let key = |s: &FoundString| (s.offset, s.encoding, s.content.clone(), s.context_before.clone());
+        assert_eq!(visited.iter().map(key).collect::<Vec<_>>(), collected.iter().map(key).collect::<Vec<_>>());
+
+        // The callback can keep state, such as the first result of one encoding
+        let mut first_utf16 = None;
+        extractor.extract_with(&data, 0, |found| {
+            if found.encoding == Encoding::Utf16Le {
+                first_utf16 = Some(found.content);
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        });
+        assert_eq!(first_utf16.as_deref(), Some("wide string"));
+
+        // Breaking after N results stops the scan there, in every scanner
+        for n in 1..=calls {
+            let mut seen = Vec::new();
+            extractor.extract_with(&data, 0x100, |found| {
+                seen.push((found.offset, found.encoding));
+                if seen.len() == n { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+            });
+            assert_eq!(seen, in_order[..n], "stopping after {} results", n);
+        }
+    }
+
+    #[test]
+    fn test_count_strings_matches_extract_strings() {
+        // Deterministic xorshift buffers mixing noise with text in every encoding
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let fragments: Vec<Vec<u8>> = vec![
+            b"password=hunter2".to_vec(),
+            "C:\\Users\\admin".encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            "user=bob".encode_utf16().flat_map(u16::to_be_bytes).collect(),
+            vec![0xC4, 0xE3, 0xBA, 0xC3, 0xCA, 0xC0, 0xBD, 0xE7],
+            "caf\u{e9} user=alice".as_bytes().to_vec(),
+            b"MZ\x90\x00".to_vec(),
+        ];
+
+        let extractors = [
+            StringExtractor::new(4, EncodingType::ALL.to_vec(), None, false, None).unwrap(),
+            StringExtractor::new(6, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, Some(8))
+                .unwrap()
+                .with_signature(Some(b"MZ".to_vec())),
+            StringExtractor::new(4, EncodingType::ALL.to_vec(), Some(r"user=(\w+)".to_string()), true, None)
+                .unwrap()
+                .with_capture(Some(1))
+                .unwrap(),
+            StringExtractor::new(4, EncodingType::ALL.to_vec(), Some("user".to_string()), false, None)
+                .unwrap()
+                .with_invert_match(true)
+                .with_search_bytes(Some(vec![b'a'])),
+        ];
+
+        for round in 0..20 {
+            let mut data = Vec::new();
+            while data.len() < 4096 {
+                match next() % 4 {
+                    0 => data.extend_from_slice(&fragments[next() as usize % fragments.len()]),
+                    1 => data.extend(std::iter::repeat_n(0, next() as usize % 8)),
+                    _ => data.extend((0..next() % 16).map(|_| next() as u8)),
+                }
+            }
+            assert!(extractors[0].count_strings(&data) > 0);
+            for (i, extractor) in extractors.iter().enumerate() {
+                assert_eq!(
+                    extractor.count_strings(&data),
+                    extractor.extract_strings(&data, 0).len(),
+                    "round {}, extractor {}",
+                    round,
+                    i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_gbk_max_len_truncates_at_cap() {
+        // "你好世界" repeated: 40 bytes of GBK text
+        let data = [0xC4, 0xE3, 0xBA, 0xC3, 0xCA, 0xC0, 0xBD, 0xE7].repeat(5);
+        let extract = |max_len| -> Vec<(u64, usize)> {
+            StringExtractor::new(4, vec![EncodingType::Gbk], None, false, None)
+                .unwrap()
+                .with_gbk_max_len(max_len)
+                .unwrap()
+                .extract_strings(&data, 0)
+                .into_iter()
+                .map(|s| (s.offset.get(), s.byte_length))
+                .collect()
+        };
+
+        assert_eq!(extract(1024), [(0, 40)]);
+        assert_eq!(extract(16), [(0, 16), (16, 16), (32, 8)]);
+        // An odd cap never splits a two-byte character
+        assert_eq!(extract(15), [(0, 14), (14, 14), (28, 12)]);
+
+        let extractor = StringExtractor::new(8, vec![EncodingType::Gbk], None, false, None).unwrap();
+        assert!(extractor.with_gbk_max_len(4).is_err());
+        let ascii_only = StringExtractor::new(8, vec![EncodingType::Ascii], None, false, None).unwrap();
+        assert!(ascii_only.with_gbk_max_len(4).is_ok());
+    }
+
+    #[test]
+    fn test_signature_offsets() {
+        let mut data = vec![0u8; 0x300];
+        data[0..2].copy_from_slice(b"MZ");
+        data[0x100..0x102].copy_from_slice(b"MZ");
+        data[0x2FF] = b'M';
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None)
+            .unwrap()
+            .with_signature(Some(vec![0x4D, 0x5A]));
+        let results = extractor.extract_strings(&data, 0x1000);
+        let offsets: Vec<u64> = results.iter().map(|s| s.offset.get()).collect();
+        assert_eq!(offsets, [0x1000, 0x1100]);
+        assert!(results.iter().all(|s| s.encoding == Encoding::Signature && s.content == "4d5a"));
+
+        // Overlapping occurrences are all reported
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None)
+            .unwrap()
+            .with_signature(Some(vec![0xAA, 0xAA]));
+        assert_eq!(extractor.extract_signature_only(&[0xAA; 4], 0).len(), 3);
+    }
+
+    #[test]
+    fn test_asymmetric_context() {
+        let data = b"0123456789\x00needle\x00abcdefghijklmnop";
+        let context = |before, after| {
+            let results = StringExtractor::new(6, vec![EncodingType::Ascii], Some("needle".to_string()), false, None)
+                .unwrap()
+                .with_context(before, after)
+                .extract_strings(data, 0);
+            assert_eq!(results.len(), 1);
+            (results[0].context_before.clone(), results[0].context_after.clone())
+        };
+
+        assert_eq!(context(Some(2), Some(8)), (Some(b"9\x00".to_vec()), Some(b"\x00abcdefg".to_vec())));
+        assert_eq!(context(None, Some(3)), (None, Some(b"\x00ab".to_vec())));
+        // Clipped at the start of the buffer
+        assert_eq!(context(Some(64), None), (Some(b"0123456789\x00".to_vec()), None));
+    }
+
+    #[test]
+    fn test_context_trim_stops_at_null() {
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, Some(16))
+            .unwrap()
+            .with_context_trim(true)
+            .with_search_bytes(Some(b"needle".to_vec()));
+
+        // Non-control bytes on either side of the string are kept up to the nulls
+        let data = b"other\x00\xFF\xFEneedle\x80\x81\x00more";
+        let results = extractor.extract_strings(data, 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].context_before.as_deref(), Some(&b"\xFF\xFE"[..]));
+        assert_eq!(results[0].context_after.as_deref(), Some(&b"\x80\x81"[..]));
+
+        // A string directly next to a null has no context on that side
+        let data = b"other\x00needle\x00more";
+        let results = extractor.extract_strings(data, 0);
+        assert_eq!((results[0].context_before.as_deref(), results[0].context_after.as_deref()), (None, None));
+
+        // UTF-16 context is cut at a null code unit, not at the zero high bytes
+        let mut data: Vec<u8> = "ab".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        data.extend_from_slice(&[0, 0, 0x41, 0x30]);
+        data.extend("needle".encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend_from_slice(&[0, 0, b'x', 0]);
+        let extractor = extractor.with_search_bytes(Some(b"n\x00e\x00".to_vec()));
+        let results = extractor.extract_strings(&data, 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].context_before.as_deref(), Some(&[0x41, 0x30][..]));
+        assert_eq!(results[0].context_after, None);
+    }
+
+    #[test]
+    fn test_invert_match_returns_complement() {
+        let data = b"\x00password=hunter2\x00harmless text\x00user_42\x00Password\x00";
+        let extract = |search: &str, regex, invert| -> Vec<String> {
+            StringExtractor::new(4, vec![EncodingType::Ascii], Some(search.to_string()), regex, None)
+                .unwrap()
+                .with_invert_match(invert)
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| s.content)
+                .collect()
+        };
+
+        assert_eq!(extract("password", false, false), ["password=hunter2"]);
+        assert_eq!(extract("password", false, true), ["harmless text", "user_42", "Password"]);
+
+        assert_eq!(extract(r"\d", true, false), ["password=hunter2", "user_42"]);
+        assert_eq!(extract(r"\d", true, true), ["harmless text", "Password"]);
+    }
+
+    #[test]
+    fn test_per_encoding_min_len() {
+        let mut data = b"\x00short\x00much longer text\x00\x00\x00".to_vec();
+        for c in "wide".encode_utf16() {
+            data.extend_from_slice(&c.to_le_bytes());
+        }
+        data.extend_from_slice(&[0, 0]);
+
+        let extractor = StringExtractor::new(8, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, None)
+            .unwrap()
+            .with_min_len_overrides([(EncodingType::Ascii, 10), (EncodingType::Utf16Le, 3)]);
+        let found: Vec<(String, Encoding)> = extractor
+            .extract_strings(&data, 0)
+            .into_iter()
+            .map(|s| (s.content, s.encoding))
+            .collect();
+
+        // "short" fails the ASCII minimum of 10; "wide" passes the UTF-16 minimum of 3
+        assert_eq!(
+            found,
+            [("much longer text".to_string(), Encoding::Ascii), ("wide".to_string(), Encoding::Utf16Le)]
+        );
+    }
+
+    #[test]
+    fn test_min_len_unit() {
+        let utf16le: Vec<u8> = "wide".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let gbk = GBK.encode("中文字").0.into_owned();
+        // Each sample is 6 bytes or more but fewer than 6 characters, except the ASCII one
+        let samples: [(EncodingType, &[u8], &str); 4] = [
+            (EncodingType::Ascii, b"abcdef", "abcdef"),
+            (EncodingType::Utf8, "café!".as_bytes(), "café!"),
+            (EncodingType::Utf16Le, &utf16le, "wide"),
+            (EncodingType::Gbk, &gbk, "中文字"),
+        ];
+        let found = |unit: MinLenUnit| -> Vec<String> {
+            samples
+                .iter()
+                .flat_map(|&(encoding, bytes, _)| {
+                    let mut data = b"\x00\x00".to_vec();
+                    data.extend_from_slice(bytes);
+                    data.extend_from_slice(b"\x00\x00");
+                    StringExtractor::new(6, vec![encoding], None, false, None)
+                        .unwrap()
+                        .with_min_len_unit(unit)
+                        .extract_strings(&data, 0)
+                })
+                .map(|s| s.content)
+                .collect()
+        };
+
+        assert_eq!(found(MinLenUnit::Native), ["abcdef", "café!", "中文字"]);
+        assert_eq!(found(MinLenUnit::Chars), ["abcdef"]);
+        assert_eq!(found(MinLenUnit::Bytes), samples.map(|(_, _, text)| text));
+    }
+
     #[test]
     fn test_search_functionality() {
         let extractor = StringExtractor::new(
@@ -613,4 +2261,742 @@ mod tests {
         assert!(results.iter().any(|s| s.offset >= base_offset));
         assert!(results.iter().any(|s| s.content.contains("Hello World")));
     }
+
+    #[test]
+    fn test_single_encoding_matches_dispatch() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"plain ascii\x00caf\xC3\xA9 latte\x00");
+        data.extend_from_slice(&[b'W', 0, b'i', 0, b'd', 0, b'e', 0, 0, 0]);
+        data.extend_from_slice(&[0, b'B', 0, b'i', 0, b'g', 0, b'E', 0, 0]);
+        data.extend_from_slice(&[0xC4, 0xE3, 0xBA, 0xC3, 0xCA, 0xC0, 0xBD, 0xE7, 0]);
+
+        let single = |encoding: EncodingType| {
+            StringExtractor::new(4, vec![encoding], None, false, Some(4)).unwrap()
+        };
+
+        for _ in 0..100 {
+            let ascii = single(EncodingType::Ascii);
+            assert_eq!(ascii.extract_ascii_utf8_only(&data, 0x40), ascii.extract_strings(&data, 0x40));
+            let utf16le = single(EncodingType::Utf16Le);
+            assert_eq!(utf16le.extract_utf16le_only(&data, 0x40), utf16le.extract_strings(&data, 0x40));
+            let utf16be = single(EncodingType::Utf16Be);
+            assert_eq!(utf16be.extract_utf16be_only(&data, 0x40), utf16be.extract_strings(&data, 0x40));
+            let gbk = single(EncodingType::Gbk);
+            assert_eq!(gbk.extract_gbk_only(&data, 0x40), gbk.extract_strings(&data, 0x40));
+        }
+
+        let contents: Vec<String> = single(EncodingType::Ascii)
+            .extract_ascii_utf8_only(&data, 0)
+            .into_iter()
+            .map(|s| s.content)
+            .collect();
+        assert_eq!(contents, ["plain ascii", "café latte"]);
+    }
+
+    #[test]
+    fn test_charset_filter() {
+        let data = b"deadbeef\x00hello!\x00CAFE0042\x00GATTACA\x00gattaca\x00";
+        let contents = |charset: Charset| -> Vec<String> {
+            StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None)
+                .unwrap()
+                .with_charset(Some(charset))
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| s.content)
+                .collect()
+        };
+
+        assert_eq!(contents(Charset::hex()), ["deadbeef", "CAFE0042"]);
+        assert_eq!(contents(Charset::from_bytes(b"ACGT")), ["GATTACA"]);
+
+        // The charset is checked before the search pattern
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii], Some("e".to_string()), false, None)
+            .unwrap()
+            .with_charset(Some(Charset::hex()));
+        let found: Vec<String> = extractor.extract_strings(data, 0).into_iter().map(|s| s.content).collect();
+        assert_eq!(found, ["deadbeef"]);
+    }
+
+    #[test]
+    fn test_capture_group() {
+        let mut data = b"GET /login user=alice&pw=x\x00".to_vec();
+        // "id user=bob" in UTF-16LE
+        data.extend("id user=bob".encode_utf16().flat_map(|u| u.to_le_bytes()));
+        data.extend_from_slice(&[0, 0]);
+
+        let extractor = StringExtractor::new(
+            4,
+            vec![EncodingType::Ascii, EncodingType::Utf16Le],
+            Some(r"user=(\w+)".to_string()),
+            true,
+            None,
+        )
+        .unwrap()
+        .with_raw_bytes(true)
+        .with_capture(Some(1))
+        .unwrap();
+
+        let results = extractor.extract_strings(&data, 0x100);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].content, "alice");
+        assert_eq!(results[0].offset, 0x100 + 16);
+        assert_eq!(results[0].raw_bytes.as_deref(), Some(&b"alice"[..]));
+        assert_eq!(results[1].content, "bob");
+        assert_eq!(results[1].offset, 0x100 + 27 + 8 * 2);
+        assert_eq!(results[1].byte_length, 6);
+
+        // Group 0 is the whole match, not the whole string
+        let whole = StringExtractor::new(4, vec![EncodingType::Ascii], Some(r"user=(\w+)".to_string()), true, None)
+            .unwrap()
+            .with_capture(Some(0))
+            .unwrap();
+        assert_eq!(whole.extract_strings(&data, 0)[0].content, "user=alice");
+
+        // Offsets come from the source bytes, not the content, where invalid bytes were replaced or dropped
+        let capture = |encoding: EncodingType, replacement: Option<char>, data: &[u8]| -> (u64, usize, String) {
+            let extractor = StringExtractor::new(4, vec![encoding], Some(r"id=(\d+)".to_string()), true, None)
+                .unwrap()
+                .with_replacement_char(replacement)
+                .with_capture(Some(1))
+                .unwrap();
+            let found = extractor.extract_strings(data, 0).remove(0);
+            (found.offset.get(), found.byte_length, found.content)
+        };
+        let lossy = b"\xFF\xFEx id=42\x00";
+        assert_eq!(capture(EncodingType::Ascii, Some('\u{FFFD}'), lossy), (7, 2, "42".to_string()));
+        assert_eq!(capture(EncodingType::Ascii, None, lossy), (7, 2, "42".to_string()));
+        // GBK 你好, a skipped invalid byte, then ASCII
+        assert_eq!(capture(EncodingType::Gbk, None, b"\xC4\xE3\xBA\xC3\xFF id=42\x00"), (9, 2, "42".to_string()));
+
+        let plain = StringExtractor::new(4, vec![EncodingType::Ascii], Some("user".to_string()), false, None).unwrap();
+        assert!(plain.with_capture(Some(1)).is_err());
+        let regex = StringExtractor::new(4, vec![EncodingType::Ascii], Some(r"user=(\w+)".to_string()), true, None).unwrap();
+        assert!(regex.with_capture(Some(2)).is_err());
+    }
+
+    #[test]
+    fn test_gbk_parallel_matches_serial() {
+        let extractor = StringExtractor::new(
+            4,
+            vec![EncodingType::Gbk],
+            None,
+            false,
+            Some(8),
+        ).unwrap();
+
+        // Mixed ASCII/GBK records: "log 你好世界 end", stray bytes and separators
+        let mut data = Vec::new();
+        for i in 0..200u8 {
+            data.extend_from_slice(b"log ");
+            data.extend_from_slice(&[0xC4, 0xE3, 0xBA, 0xC3, 0xCA, 0xC0, 0xBD, 0xE7]);
+            data.extend_from_slice(b" end ");
+            data.push(0x80 | (i % 0x7F));
+            data.push(if i % 3 == 0 { 0x00 } else { b'x' });
+            data.extend_from_slice(&[0xB2, 0xE2, 0xCA, 0xD4, i % 0x20]);
+        }
+
+        let scan = |range| {
+            let mut results = Vec::new();
+            extractor.scan_gbk(&data, range, &mut |start, end, content, encoding, note| {
+                results.extend(extractor.build_result(&data, start..end, 0x1000, content, encoding, note));
+                ControlFlow::Continue(())
+            });
+            results
+        };
+        let serial = scan(0..data.len());
+        let parallel: Vec<FoundString> = extractor.scan_sub_ranges(&data, 64, scan).into_iter().flatten().collect();
+
+        assert!(split_at_terminators(&data, 64, |b| extractor.is_terminator(b)).len() > 1);
+        assert!(!serial.is_empty());
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn test_whitespace_combinations() {
+        let data = b"alpha\tbeta\ngamma\r\ndelta";
+        let contents = |allow_tab, allow_newline| -> Vec<String> {
+            StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None)
+                .unwrap()
+                .with_whitespace(allow_tab, allow_newline)
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| s.content)
+                .collect()
+        };
+
+        assert_eq!(contents(false, false), ["alpha", "beta", "gamma", "delta"]);
+        assert_eq!(contents(true, false), ["alpha\tbeta", "gamma", "delta"]);
+        assert_eq!(contents(false, true), ["alpha", "beta\ngamma\r\ndelta"]);
+        assert_eq!(contents(true, true), ["alpha\tbeta\ngamma\r\ndelta"]);
+    }
+
+    #[test]
+    fn test_min_words_filter() {
+        let extract = |min_words, data: &[u8]| -> Vec<String> {
+            StringExtractor::new(3, vec![EncodingType::Ascii], None, false, None)
+                .unwrap()
+                .with_min_words(Some(min_words))
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| s.content)
+                .collect()
+        };
+
+        assert_eq!(extract(3, b"a b c"), ["a b c"]);
+        assert!(extract(2, b"xQ9").is_empty());
+        assert_eq!(extract(3, b"Error: disk full, retrying.\x00#$%^&*()"), ["Error: disk full, retrying."]);
+        assert_eq!(count_words("ab#cd ef"), 1);
+    }
+
+    #[test]
+    fn test_context_crosses_chunk_edges() {
+        // "boundary" starts the second chunk; "trailing" is cut off by the end of the first chunk's overlap
+        let mut data = vec![b'.'; 128];
+        data[56..88].copy_from_slice(b"\x00before\x00boundary\x00trailing after.");
+
+        let extractor = StringExtractor::new(6, vec![EncodingType::Ascii], None, false, Some(4)).unwrap();
+        let chunks = crate::plan::plan_chunks(data.len(), 2, 16);
+        assert_eq!(chunks, [(0, 80, 0), (64, 128, 64)]);
+        let context = |found: &FoundString| {
+            (found.context_before.clone().unwrap_or_default(), found.context_after.clone().unwrap_or_default())
+        };
+
+        let second = extractor.extract_strings_in(&data, chunks[1].0..chunks[1].1, 0);
+        let boundary = second.iter().find(|s| s.content == "boundary").unwrap();
+        assert_eq!(boundary.offset.get(), 64);
+        assert_eq!(context(boundary), (b"ore\x00".to_vec(), b"\x00tra".to_vec()));
+
+        // Scanned on its own, the chunk has no bytes before the string
+        let alone = extractor.extract_strings(&data[64..128], 64);
+        assert_eq!(alone[0].context_before, None);
+
+        let first = extractor.extract_strings_in(&data, chunks[0].0..chunks[0].1, 0);
+        let cut = first.iter().find(|s| s.offset.get() == 73).unwrap();
+        assert_eq!(cut.content, "trailin");
+        assert_eq!(context(cut).1, b"g af".to_vec());
+    }
+
+    #[test]
+    fn test_extract_ending_at() {
+        let mut data = b"\xFF\x01junk\x02secret_token\x00\x00".to_vec();
+        let ascii_end = data.len() - 2;
+        data.extend("wide name".encode_utf16().flat_map(u16::to_le_bytes));
+        let wide_end = data.len();
+        data.extend(b"\x00\x00");
+
+        let extractor =
+            StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, None).unwrap();
+        let found = |end| -> Vec<(u64, String, Encoding)> {
+            extractor
+                .extract_ending_at(&data, end, 0x1000)
+                .into_iter()
+                .map(|s| (s.offset.get(), s.content, s.encoding))
+                .collect()
+        };
+
+        // Walking back from the null stops at the control byte before the string
+        assert_eq!(found(ascii_end), [(0x1007, "secret_token".to_string(), Encoding::Ascii)]);
+        assert_eq!(found(wide_end), [(0x1000 + ascii_end as u64 + 2, "wide name".to_string(), Encoding::Utf16Le)]);
+        // Ending inside a string reports its prefix; too short a run is dropped
+        assert_eq!(found(13)[0].1, "secret");
+        assert!(found(5).is_empty());
+    }
+
+    #[test]
+    fn test_encoding_names_round_trip() {
+        // Exhaustive, so a new variant must be added here and to `ALL`
+        let variant = |encoding: Encoding| match encoding {
+            Encoding::Ascii
+            | Encoding::Utf8
+            | Encoding::Utf16Le
+            | Encoding::Utf16Be
+            | Encoding::Gbk
+            | Encoding::Signature => (),
+            // Not a fixed encoding, so deliberately missing from ALL
+            Encoding::Custom(_) => (),
+        };
+        Encoding::all().iter().copied().for_each(variant);
+        assert_eq!(Encoding::all().len(), 6);
+
+        for &encoding in Encoding::all() {
+            assert_eq!(encoding.as_str().parse::<Encoding>().unwrap(), encoding);
+        }
+        for &encoding_type in EncodingType::all() {
+            assert_eq!(Encoding::from(encoding_type).as_str(), encoding_type.as_str());
+        }
+        assert!("utf-32".parse::<Encoding>().is_err());
+    }
+
+    #[test]
+    fn test_script_filter() {
+        let data = "City: Москва\x00plain ascii\x00Server Москва\x00".as_bytes();
+        let extract = |scripts: Vec<Script>| -> Vec<String> {
+            StringExtractor::new(4, vec![EncodingType::Utf8], None, false, None)
+                .unwrap()
+                .with_scripts(scripts)
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| s.content)
+                .collect()
+        };
+
+        assert_eq!(extract(vec![]), ["City: Москва", "plain ascii", "Server Москва"]);
+        assert_eq!(extract(vec![Script::Cyrillic]), ["City: Москва", "Server Москва"]);
+        assert!(extract(vec![Script::Han, Script::Arabic]).is_empty());
+    }
+
+    #[test]
+    fn test_min_unique_chars_filter() {
+        let data = b"AAAAAAAA\x00========\x00Hello123\x00ABABABAB\x00";
+        let extract = |min_unique_chars| -> Vec<String> {
+            StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None)
+                .unwrap()
+                .with_min_unique_chars(min_unique_chars)
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| s.content)
+                .collect()
+        };
+
+        assert_eq!(extract(None), ["AAAAAAAA", "========", "Hello123", "ABABABAB"]);
+        assert_eq!(extract(Some(2)), ["Hello123", "ABABABAB"]);
+        assert_eq!(extract(Some(3)), ["Hello123"]);
+        assert!(extract(Some(8)).is_empty());
+        assert!(has_unique_chars("中文中文", 2));
+        assert!(!has_unique_chars("中中中中", 2));
+    }
+
+    #[test]
+    fn test_paths_only_filter() {
+        let mut data = b"\x00C:\\Windows\\System32\x00The quick brown fox jumps\x00/etc/passwd\x00\x00\x00\x00".to_vec();
+        data.extend("\\\\server\\share\x00".encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend_from_slice(b"\x00\x00see /etc/hosts for names\x00");
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, None)
+            .unwrap()
+            .with_paths_only(true);
+        let found: Vec<String> = extractor.extract_strings(&data, 0).into_iter().map(|s| s.content).collect();
+        assert_eq!(found, ["C:\\Windows\\System32", "/etc/passwd", "\\\\server\\share"]);
+        assert_eq!(extractor.count_strings(&data), 3);
+    }
+
+    #[test]
+    fn test_min_alnum_ratio_filter() {
+        let data = b"!!!!@@@@####\x00Hello World 2024\x00";
+        let extract = |min_alnum_ratio| -> Vec<String> {
+            StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None)
+                .unwrap()
+                .with_min_alnum_ratio(min_alnum_ratio)
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| s.content)
+                .collect()
+        };
+
+        assert_eq!(extract(None), ["!!!!@@@@####", "Hello World 2024"]);
+        // 14 of 16 characters are letters or digits
+        assert_eq!(extract(Some(0.5)), ["Hello World 2024"]);
+        assert_eq!(extract(Some(0.875)), ["Hello World 2024"]);
+        assert!(extract(Some(0.9)).is_empty());
+        assert_eq!(alnum_ratio(""), 0.0);
+    }
+
+    #[test]
+    fn test_gap_before() {
+        let data = b"\x00\x00first\x00\x00\x00\x00\x00\x00\x00second\x00third";
+        let extract = |emit| -> Vec<(String, Option<u64>)> {
+            StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None)
+                .unwrap()
+                .with_gaps(emit)
+                .extract_strings(data, 0x1000)
+                .into_iter()
+                .map(|s| (s.content, s.gap_before))
+                .collect()
+        };
+
+        assert_eq!(
+            extract(true),
+            [("first".to_string(), None), ("second".to_string(), Some(7)), ("third".to_string(), Some(1))]
+        );
+        assert!(extract(false).iter().all(|(_, gap)| gap.is_none()));
+    }
+
+    #[test]
+    fn test_drop_whitespace() {
+        let data = b"\x00        \x00 \t \t \x00  word  \x00";
+        let extract = |drop| -> Vec<String> {
+            StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, None)
+                .unwrap()
+                .with_whitespace(true, false)
+                .with_drop_whitespace(drop)
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| s.content)
+                .collect()
+        };
+
+        assert_eq!(extract(false), ["        ", " \t \t ", "  word  "]);
+        assert_eq!(extract(true), ["  word  "]);
+    }
+
+    #[test]
+    fn test_extract_from_reader_matches_slice() {
+        let mut data = vec![0u8; 1000];
+        data[10..30].copy_from_slice(b"before any boundary!");
+        data[120..140].copy_from_slice(b"straddles the window");
+        for (i, c) in "wide crossing".encode_utf16().enumerate() {
+            data[250 + 2 * i..252 + 2 * i].copy_from_slice(&c.to_le_bytes());
+        }
+        data[990..1000].copy_from_slice(b"at the end");
+
+        let extractor =
+            StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, Some(8)).unwrap();
+        let sorted = |mut results: Vec<FoundString>| {
+            results.sort_by_key(|s| (s.offset, s.encoding.as_str()));
+            results
+        };
+
+        let expected = sorted(extractor.extract_strings(&data, 0));
+        for window in [128, 130, 256, 4096] {
+            let streamed = extractor.extract_from_reader(std::io::Cursor::new(&data), window, 64).unwrap();
+            assert_eq!(sorted(streamed), expected, "window size {}", window);
+        }
+    }
+
+    #[test]
+    fn test_embedded_ascii() {
+        let mut data = b"\x00\x00".to_vec();
+        data.extend("Hello".encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend_from_slice(b"\x00\x00");
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, None)
+            .unwrap()
+            .with_embedded_ascii(true);
+        let mut results = extractor.extract_strings(&data, 0x100);
+        let rows: Vec<_> = results
+            .iter()
+            .map(|s| (s.offset.get(), s.content.as_str(), s.encoding, s.wide_offset.map(|o| o.get()), s.decode_note))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (0x102, "Hello", Encoding::Utf16Le, None, None),
+                (0x102, "Hello", Encoding::Ascii, Some(0x102), Some(DecodeNote::EmbeddedAscii)),
+            ]
+        );
+        assert_eq!(results[1].byte_length, 10);
+        assert_eq!(extractor.count_strings(&data), 2);
+
+        // Deduplication and cross-encoding merging keep both copies
+        crate::postprocess::dedup_results(&mut results, crate::config::DedupMode::Offset);
+        crate::postprocess::merge_cross_encoding(&mut results, &Encoding::ALL);
+        assert_eq!(results.len(), 2);
+
+        // Off by default
+        let plain = StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, None).unwrap();
+        assert_eq!(plain.extract_strings(&data, 0).len(), 1);
+    }
+
+    #[test]
+    fn test_utf16le_at_odd_offset() {
+        let utf16le = |text: &str| text.encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<u8>>();
+
+        // A wide string right after a stray byte at the start...
+        let mut data = vec![0x07];
+        data.extend(utf16le("first wide"));
+        // ...and one shifted to an odd offset by a stray byte after another wide string
+        data.push(b'A');
+        let second = data.len();
+        data.extend(utf16le("Hello odd"));
+        // A NUL stray byte has the same effect
+        data.push(0x00);
+        let third = data.len();
+        data.extend(utf16le("world"));
+        data.extend_from_slice(&[0, 0]);
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Utf16Le], None, false, None).unwrap();
+        let results: Vec<(u64, String)> =
+            extractor.extract_strings(&data, 0).into_iter().map(|s| (s.offset.get(), s.content)).collect();
+        assert_eq!(
+            results,
+            [(1, "first wide".to_string()), (second as u64, "Hello odd".to_string()), (third as u64, "world".to_string())]
+        );
+        assert_eq!(second % 2, 0);
+        assert_eq!(third % 2, 1);
+    }
+
+    #[test]
+    fn test_del_terminates_unless_printable() {
+        let mut data = b"\x00left\x7Fright\x00\x00\x00\x00".to_vec();
+        data.extend("wide\x7Fstr".encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend_from_slice(b"\x00\x00");
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, None).unwrap();
+        let contents = |extractor: &StringExtractor| -> Vec<String> {
+            extractor.extract_strings(&data, 0).into_iter().map(|s| s.content).collect()
+        };
+        assert_eq!(contents(&extractor), ["left", "right", "wide"]);
+
+        let extractor = extractor.with_del_printable(true);
+        assert_eq!(contents(&extractor), ["left\x7Fright", "wide\x7Fstr"]);
+    }
+
+    #[test]
+    fn test_utf16_keeps_latin1_and_allowed_newlines() {
+        let mut data = "caf\u{e9} latte line1\r\nline2".encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<u8>>();
+        data.extend_from_slice(b"\x00\x00");
+        data.extend("ctrl\u{85}next".encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend_from_slice(b"\x00\x00");
+
+        let contents = |allow_newline| -> Vec<String> {
+            StringExtractor::new(4, vec![EncodingType::Utf16Le], None, false, None)
+                .unwrap()
+                .with_whitespace(true, allow_newline)
+                .extract_strings(&data, 0)
+                .into_iter()
+                .map(|s| s.content)
+                .collect()
+        };
+        assert_eq!(contents(true), ["caf\u{e9} latte line1\r\nline2", "ctrl", "next"]);
+        assert_eq!(contents(false), ["caf\u{e9} latte line1", "line2", "ctrl", "next"]);
+    }
+
+    #[test]
+    fn test_adaptive_joins_ascii_and_utf16le_runs() {
+        // "user=" in ASCII directly followed by "administrator" in UTF-16LE
+        let mut data = b"\x00\x00user=".to_vec();
+        data.extend("administrator".encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend_from_slice(b"\x00\x00\x00\x00plain ascii\x00");
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf16Le], None, false, None).unwrap();
+        let separate: Vec<String> = extractor.extract_strings(&data, 0).into_iter().map(|s| s.content).collect();
+        assert!(separate.contains(&"user=a".to_string()), "{:?}", separate);
+
+        let adaptive = extractor.with_adaptive(true);
+        let results = adaptive.extract_strings(&data, 0);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].content, "user=administrator");
+        assert_eq!((results[0].offset.get(), results[0].byte_length), (2, 5 + 26));
+        assert_eq!(results[0].encoding, Encoding::Utf16Le);
+        assert_eq!(results[1].content, "plain ascii");
+        assert_eq!(results[1].encoding, Encoding::Ascii);
+        assert_eq!(adaptive.count_strings(&data), 2);
+    }
+
+    #[test]
+    fn test_follow_utf8_continuation() {
+        // "naïve café" is valid, then a 3-byte lead is cut short by a stray 0xFF
+        let mut data = "naïve café".as_bytes().to_vec();
+        data.extend_from_slice(&[0xE2, 0xFF, b'x']);
+        data.extend_from_slice(b"tail text");
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Utf8], None, false, None).unwrap();
+        let degraded = extractor.extract_strings(&data, 0);
+        assert_eq!(degraded.len(), 2);
+        assert_eq!(degraded[0].encoding, Encoding::Ascii);
+        assert!(degraded[0].content.contains('?'));
+        assert_eq!(degraded[1].content, "xtail text");
+
+        let salvaging = extractor.with_follow_utf8_continuation(true);
+        let results = salvaging.extract_strings(&data, 0);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].content, "naïve café");
+        assert_eq!(results[0].encoding, Encoding::Utf8);
+        assert_eq!(results[0].byte_length, "naïve café".len());
+        assert_eq!(results[1].content, "xtail text");
+        assert_eq!(results[1].offset, ("naïve café".len() + 2) as u64);
+    }
+
+    #[test]
+    fn test_utf8_sequence_validation() {
+        assert_eq!(utf8_sequence_len("é".as_bytes()), Some(2));
+        assert_eq!(utf8_sequence_len("中文".as_bytes()), Some(3));
+        assert_eq!(utf8_sequence_len("😀".as_bytes()), Some(4));
+        // Overlong 2- and 3-byte encodings of '/'
+        assert_eq!(utf8_sequence_len(&[0xC0, 0xAF]), None);
+        assert_eq!(utf8_sequence_len(&[0xE0, 0x80, 0xAF]), None);
+        // A surrogate, a code point above U+10FFFF and a bad continuation byte
+        assert_eq!(utf8_sequence_len(&[0xED, 0xA0, 0x80]), None);
+        assert_eq!(utf8_sequence_len(&[0xF4, 0x90, 0x80, 0x80]), None);
+        assert_eq!(utf8_sequence_len(&[0xC3, b'A']), None);
+        // Truncated
+        assert_eq!(utf8_sequence_len(&[0xE4, 0xB8]), None);
+
+        let extract = |data: &[u8]| -> Vec<(String, Encoding)> {
+            StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf8], None, false, None)
+                .unwrap()
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| (s.content, s.encoding))
+                .collect()
+        };
+        assert_eq!(extract(b"\x00caf\xC3\xA9 ok\x00"), [("café ok".to_string(), Encoding::Utf8)]);
+        // The overlong '/' is not decoded; its lead byte degrades the run to ASCII
+        assert_eq!(
+            extract(b"\x00path\xC0\xAFetc/passwd\x00"),
+            [("path?".to_string(), Encoding::Ascii), ("etc/passwd".to_string(), Encoding::Ascii)]
+        );
+        // A truncated sequence no longer swallows the terminator after it
+        assert_eq!(
+            extract(b"\x00cafe\xC3\x00next\x00"),
+            [("cafe?".to_string(), Encoding::Ascii), ("next".to_string(), Encoding::Ascii)]
+        );
+    }
+
+    #[test]
+    fn test_gbk_ascii_transitions() {
+        let gbk = |text: &str| GBK.encode(text).0.into_owned();
+        // "abc你好", then plain text behind a stray lead byte with an invalid trail byte
+        let mut data = b"\x00abc".to_vec();
+        data.extend(gbk("你好"));
+        data.extend_from_slice(b"\x00\x00\x81\xFFhello world\x00");
+
+        let extractor = StringExtractor::new(3, vec![EncodingType::Ascii, EncodingType::Gbk], None, false, None).unwrap();
+        let results = extractor.extract_strings(&data, 0);
+        let rows: Vec<(u64, &str, Encoding)> =
+            results.iter().map(|s| (s.offset.get(), s.content.as_str(), s.encoding)).collect();
+
+        // The GBK run starts at its first lead byte, leaving "abc" to the ASCII scanner
+        assert!(rows.contains(&(4, "你好", Encoding::Gbk)), "{:?}", rows);
+        assert!(rows.iter().any(|&(offset, content, encoding)| offset == 1 && content.starts_with("abc") && encoding == Encoding::Ascii));
+        // No ASCII-only content is tagged GBK, and the text after the stray lead byte
+        // is reported once, by the ASCII scanner
+        assert!(rows.iter().all(|&(_, content, encoding)| encoding != Encoding::Gbk || !content.is_ascii()));
+        let hello: Vec<_> = rows.iter().filter(|row| row.1.contains("hello")).collect();
+        assert_eq!(hello, [&(12, "hello world", Encoding::Ascii)]);
+
+        // GBK alone still reports the text after the stray lead byte, as ASCII
+        let gbk_only = StringExtractor::new(3, vec![EncodingType::Gbk], None, false, None).unwrap();
+        let rows: Vec<(u64, String, Encoding)> =
+            gbk_only.extract_strings(&data, 0).into_iter().map(|s| (s.offset.get(), s.content, s.encoding)).collect();
+        assert_eq!(rows, [(4, "你好".to_string(), Encoding::Gbk), (12, "hello world".to_string(), Encoding::Ascii)]);
+        assert_eq!(gbk_only.count_strings_by_encoding(&data), [(Encoding::Ascii, 1), (Encoding::Gbk, 1)]);
+        assert_eq!(extractor.count_strings_by_encoding(&data), [(Encoding::Ascii, 2), (Encoding::Gbk, 1)]);
+    }
+
+    #[test]
+    fn test_codepage_windows_1251() {
+        let cp1251 = |text: &str| encoding_rs::WINDOWS_1251.encode(text).0.into_owned();
+        let mut data = b"\x00\x01".to_vec();
+        data.extend(cp1251("Привет, мир"));
+        data.extend_from_slice(b"\x00\x00plain\x00\x98");
+        data.extend(cp1251("Да"));
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None)
+            .unwrap()
+            .with_codepage(Some("windows-1251"))
+            .unwrap();
+        let rows: Vec<(u64, String, Encoding)> =
+            extractor.extract_strings(&data, 0).into_iter().map(|s| (s.offset.get(), s.content, s.encoding)).collect();
+
+        // Cyrillic is tagged with the code page and sized in its single bytes; the
+        // ASCII-only run is reported once, by the ASCII scanner
+        let custom = Encoding::Custom("windows-1251");
+        assert!(rows.contains(&(2, "Привет, мир".to_string(), custom)), "{:?}", rows);
+        assert_eq!(rows.iter().filter(|row| row.1 == "plain").collect::<Vec<_>>(), [&(15, "plain".to_string(), Encoding::Ascii)]);
+        assert!(rows.iter().all(|row| row.1 != "Да"));
+        let found = extractor.extract_strings(&data, 0);
+        assert_eq!(found.iter().find(|s| s.encoding == custom).unwrap().byte_length, 11);
+        assert_eq!(custom.to_string(), "windows-1251");
+        // Counted after the fixed encodings
+        assert_eq!(extractor.count_strings_by_encoding(&data).last(), Some(&(custom, 1)));
+
+        // Without ASCII selected, the code page reports no ASCII
+        let codepage_only = StringExtractor::new(4, vec![], None, false, None).unwrap().with_codepage(Some("windows-1251")).unwrap();
+        let encodings: Vec<Encoding> = codepage_only.extract_strings(&data, 0).into_iter().map(|s| s.encoding).collect();
+        assert_eq!(encodings, [custom]);
+
+        // Labels resolve to encoding_rs names; unknown and UTF-16 labels are refused
+        let koi8 = StringExtractor::new(4, vec![], None, false, None).unwrap().with_codepage(Some("KOI8-R")).unwrap();
+        assert_eq!(koi8.config.codepage, Some(encoding_rs::KOI8_R));
+        for label in ["cp-9999", "utf-16le"] {
+            let result = StringExtractor::new(4, vec![], None, false, None).unwrap().with_codepage(Some(label));
+            assert!(matches!(result, Err(MemstrapError::InvalidEncoding(_))), "{}", label);
+        }
+    }
+
+    #[test]
+    fn test_gbk_confidence() {
+        let gbk = |text: &str| GBK.encode(text).0.into_owned();
+        let mut data = b"\x00\x00".to_vec();
+        let clean = data.len();
+        data.extend(gbk("你好世界"));
+        data.extend_from_slice(b"\x00\x00");
+        // Every other character is a stray byte that is not GBK
+        let garbage = data.len();
+        data.extend(gbk("你"));
+        data.push(0xFF);
+        data.extend(gbk("好"));
+        data.push(0x80);
+        data.extend(gbk("世"));
+        data.push(0xFF);
+        data.extend(gbk("界"));
+        data.extend_from_slice(b"\x00\x00");
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Gbk], None, false, None).unwrap();
+        let results: Vec<(u64, Option<f32>)> =
+            extractor.extract_strings(&data, 0).into_iter().map(|s| (s.offset.get(), s.confidence)).collect();
+        assert_eq!(results, [(clean as u64, Some(1.0)), (garbage as u64, Some(4.0 / 7.0))]);
+
+        let strict = extractor.with_min_confidence(Some(0.8));
+        let results = strict.extract_strings(&data, 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "你好世界");
+        assert_eq!(strict.count_strings(&data), 1);
+
+        // Confidence is only computed for multibyte encodings
+        let ascii = StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None).unwrap();
+        assert_eq!(ascii.extract_strings(b"\x00plain\x00", 0)[0].confidence, None);
+        assert_eq!(gbk_confidence(b"abc", "abc"), 1.0);
+    }
+
+    #[test]
+    fn test_decode_notes() {
+        let data = b"\x00what?\xE2\xE2next\x00plain ascii\x00caf\xC3\xA9 ok\x00";
+        let notes = |follow| -> Vec<(String, Option<DecodeNote>)> {
+            StringExtractor::new(4, vec![EncodingType::Ascii, EncodingType::Utf8], None, false, None)
+                .unwrap()
+                .with_follow_utf8_continuation(follow)
+                .extract_strings(data, 0)
+                .into_iter()
+                .map(|s| (s.content, s.decode_note))
+                .collect()
+        };
+
+        assert_eq!(
+            notes(false),
+            [
+                ("what???next".to_string(), Some(DecodeNote::Utf8FallbackAscii)),
+                ("plain ascii".to_string(), None),
+                ("café ok".to_string(), None),
+            ]
+        );
+        assert_eq!(notes(true)[0], ("what?".to_string(), Some(DecodeNote::Utf8Truncated)));
+
+        // "中文" with a stray byte the GBK scanner skips
+        let gbk = StringExtractor::new(4, vec![EncodingType::Gbk], None, false, None).unwrap();
+        let results = gbk.extract_strings(b"\x00\xD6\xD0\xFF\xCE\xC4\x00\xD6\xD0\xCE\xC4\x00", 0);
+        let notes: Vec<_> = results.iter().map(|s| (s.content.as_str(), s.decode_note)).collect();
+        assert_eq!(notes, [("中文", Some(DecodeNote::GbkPartial)), ("中文", None)]);
+    }
+
+    #[test]
+    fn test_replacement_char() {
+        // A legitimate '?' followed by two truncated 3-byte sequences
+        let data = b"what?\xE2\xE2next";
+        let degrade = |replacement| {
+            let results = StringExtractor::new(4, vec![EncodingType::Utf8], None, false, None)
+                .unwrap()
+                .with_replacement_char(replacement)
+                .extract_strings(data, 0);
+            assert_eq!(results.len(), 1);
+            assert_eq!((results[0].encoding, results[0].byte_length), (Encoding::Ascii, data.len()));
+            results[0].content.clone()
+        };
+
+        assert_eq!(degrade(Some('?')), "what???next");
+        assert_eq!(degrade(Some('\u{FFFD}')), "what?\u{FFFD}\u{FFFD}next");
+        // Dropped, only the question mark that was in the data is left
+        assert_eq!(degrade(None), "what?next");
+    }
 }
@@ -0,0 +1,71 @@
+//! SHA-256 of scanned inputs for `--emit-hash`
+
+use sha2::{Digest, Sha256};
+use std::io::{self, Read};
+
+/// Hex-encoded SHA-256 of `data`
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Reader that feeds every byte it yields into a SHA-256 hasher
+///
+/// Lets streaming mode hash the input as windows are read instead of making a
+/// second pass over the file.
+pub struct HashingReader<R> {
+    inner: R,
+    hasher: Option<Sha256>,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wrap `inner`, hashing its bytes only if `enabled`
+    pub fn new(inner: R, enabled: bool) -> Self {
+        HashingReader {
+            inner,
+            hasher: enabled.then(Sha256::new),
+        }
+    }
+
+    /// Feed bytes read elsewhere (such as a skipped prefix) into the hash
+    pub fn update(&mut self, data: &[u8]) {
+        if let Some(hasher) = &mut self.hasher {
+            hasher.update(data);
+        }
+    }
+
+    /// Hex-encoded hash of everything read so far, or `None` if hashing is off
+    pub fn finish(self) -> Option<String> {
+        self.hasher.map(|hasher| hex::encode(hasher.finalize()))
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn test_sha256_known_vector() {
+        assert_eq!(sha256_hex(b"abc"), ABC_SHA256);
+    }
+
+    #[test]
+    fn test_hashing_reader_matches_one_shot_hash() {
+        let mut reader = HashingReader::new(&b"bc"[..], true);
+        reader.update(b"a");
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer).unwrap();
+        assert_eq!(reader.finish().as_deref(), Some(ABC_SHA256));
+
+        assert_eq!(HashingReader::new(&b"abc"[..], false).finish(), None);
+    }
+}
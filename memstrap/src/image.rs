@@ -0,0 +1,102 @@
+//! An input loaded once and searched with any number of extractors
+
+use crate::baseline::Baseline;
+use crate::config::Config;
+use crate::error::Result;
+use crate::extractor::{FoundString, StringExtractor};
+use crate::input::InputData;
+use crate::region_map::RegionMap;
+use crate::run;
+use std::path::{Path, PathBuf};
+
+/// A memory-mapped (or buffered) input that can be searched repeatedly
+///
+/// Loading maps the file once; each [`search`](ScannedImage::search) then
+/// scans the same mapping, so several extractor configurations can be run
+/// without re-reading the image.
+pub struct ScannedImage {
+    path: PathBuf,
+    data: InputData,
+}
+
+impl ScannedImage {
+    /// Memory-map `path`, or read it into memory if mapping is unavailable
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::load(path, false)
+    }
+
+    /// Load `path`, reading it into a buffer instead of mapping it if `no_mmap` is set
+    pub fn load(path: &Path, no_mmap: bool) -> Result<Self> {
+        Ok(ScannedImage { path: path.to_path_buf(), data: InputData::load(path, no_mmap)? })
+    }
+
+    /// The path the input was loaded from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The loaded bytes
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Size of the input in bytes
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether the input is empty
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Extract the strings `extractor` finds in the whole input, as a command line scan with `config` would
+    ///
+    /// The input goes through the same pipeline as one given on the command
+    /// line: chunks planned from `--threads` and `--overlap`, deduplication and
+    /// the merging options, `--baseline` suppression, `--region-map` labels and
+    /// `--base-offset`.
+    /// Messages are printed unless `config` is `--quiet`.
+    pub fn search(&self, config: &Config, extractor: &StringExtractor) -> Result<Vec<FoundString>> {
+        let baseline = config.baseline.as_deref().map(Baseline::load).transpose()?;
+        let region_map = config.region_map.as_deref().map(RegionMap::load).transpose()?;
+        let (results, _) = run::scan_image(config, self, extractor, baseline.as_ref(), region_map.as_ref(), &|| false)?;
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EncodingType;
+    use clap::Parser;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_one_mapping_serves_several_extractors() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"\x00\x00password=hunter2\x00\x00\x00\x00").unwrap();
+        file.write_all(&"wide secret".encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<_>>()).unwrap();
+        file.write_all(b"\x00\x00\x00\x00short\x00").unwrap();
+
+        let image = ScannedImage::open(file.path()).unwrap();
+        let path = file.path().to_str().unwrap();
+        let contents = |args: &[&str], extractor: StringExtractor| -> Vec<(u64, String)> {
+            let config = Config::parse_from([&["memstrap", path, "--quiet"], args].concat());
+            image.search(&config, &extractor).unwrap().into_iter().map(|s| (s.offset.get(), s.content)).collect()
+        };
+
+        let ascii = || StringExtractor::new(5, vec![EncodingType::Ascii], None, false, None).unwrap();
+        assert_eq!(contents(&[], ascii()), [(2, "password=hunter2".to_string()), (48, "short".to_string())]);
+
+        let wide = StringExtractor::new(4, vec![EncodingType::Utf16Le], Some("secret".into()), false, None).unwrap();
+        assert_eq!(contents(&[], wide), [(22, "wide secret".to_string())]);
+
+        // The command line post-processing applies too
+        let mut baseline = NamedTempFile::new().unwrap();
+        writeln!(baseline, "short").unwrap();
+        let args = ["--base-offset", "0x1000", "--baseline", baseline.path().to_str().unwrap()];
+        assert_eq!(contents(&args, ascii()), [(0x1002, "password=hunter2".to_string())]);
+    }
+}
@@ -0,0 +1,96 @@
+//! Loading the scanned file into memory
+
+use crate::config::is_stdin;
+use crate::error::Result;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::Read;
+use std::ops::Deref;
+use std::path::Path;
+
+/// Bytes of the input file, either memory-mapped or read into a buffer
+pub enum InputData {
+    /// File mapped with `mmap`
+    Mapped(Mmap),
+    /// File read with `std::fs::read` (`--no-mmap`, or after an mmap failure)
+    Buffered(Vec<u8>),
+}
+
+impl InputData {
+    /// Load `path`, memory-mapping it unless `no_mmap` is set
+    ///
+    /// Pseudo-files such as those exposed by a MemProcFS mount may refuse to be
+    /// mapped; in that case a warning is printed and the file is read instead.
+    /// The path `-` reads all of standard input. FIFOs, devices and empty files are
+    /// never mapped.
+    pub fn load(path: &Path, no_mmap: bool) -> Result<Self> {
+        if is_stdin(path) {
+            let mut buffer = Vec::new();
+            std::io::stdin().lock().read_to_end(&mut buffer)?;
+            return Ok(InputData::Buffered(buffer));
+        }
+        if no_mmap {
+            return Ok(InputData::Buffered(std::fs::read(path)?));
+        }
+
+        let mut file = File::open(path)?;
+        let metadata = file.metadata()?;
+        // FIFOs and devices report no length and cannot be mapped
+        if !metadata.is_file() {
+            let mut buffer = Vec::new();
+            file.read_to_end(&mut buffer)?;
+            return Ok(InputData::Buffered(buffer));
+        }
+        // Mapping a zero-length file fails on some platforms
+        if metadata.len() == 0 {
+            return Ok(InputData::Buffered(Vec::new()));
+        }
+        match unsafe { Mmap::map(&file) } {
+            Ok(mmap) => Ok(InputData::Mapped(mmap)),
+            Err(e) => {
+                eprintln!(
+                    "Warning: memory mapping {} failed ({}); reading it into memory instead",
+                    path.display(),
+                    e
+                );
+                Ok(InputData::Buffered(std::fs::read(path)?))
+            }
+        }
+    }
+
+    /// Whether the data is memory-mapped
+    pub fn is_mapped(&self) -> bool {
+        matches!(self, InputData::Mapped(_))
+    }
+}
+
+impl Deref for InputData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            InputData::Mapped(mmap) => mmap,
+            InputData::Buffered(buffer) => buffer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_no_mmap_reads_same_bytes() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"\x00memprocfs\x00pseudo file\x00").unwrap();
+
+        let mapped = InputData::load(file.path(), false).unwrap();
+        let buffered = InputData::load(file.path(), true).unwrap();
+
+        assert!(mapped.is_mapped());
+        assert!(!buffered.is_mapped());
+        assert_eq!(&*mapped, &*buffered);
+    }
+}
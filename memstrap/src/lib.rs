@@ -3,12 +3,50 @@
 //! This library provides functionality for extracting strings from memory images
 //! and large files with support for multiple encodings and parallel processing.
 
+pub mod baseline;
+pub mod charset;
+pub mod classify;
+pub mod compress;
 pub mod config;
+pub mod detect;
 pub mod extractor;
+pub mod hash;
+pub mod image;
+pub mod input;
+pub mod offset;
+pub mod plan;
 pub mod output;
+pub mod partition;
 pub mod error;
+pub mod postprocess;
+pub mod progress;
+pub mod region_map;
+pub mod run;
+#[cfg(feature = "binary-parse")]
+pub mod sections;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod stream;
+#[cfg(feature = "tui")]
+pub mod tui;
 
-pub use config::Config;
-pub use extractor::{StringExtractor, FoundString, Encoding};
-pub use output::CsvOutput;
+pub use baseline::Baseline;
+pub use charset::Charset;
+pub use config::{Config, DedupMode, OutputFormat};
+pub use extractor::{StringExtractor, FoundString, Encoding, DecodeNote};
+pub use image::ScannedImage;
+pub use input::InputData;
+pub use offset::Offset;
+pub use plan::ScanPlan;
+pub use postprocess::merge_results;
+pub use region_map::RegionMap;
+pub use output::{ConsolidatedOutput, CsvOutput, XmlOutput, OutputOptions};
+#[cfg(feature = "msgpack")]
+pub use output::MsgpackOutput;
+#[cfg(feature = "ndjson")]
+pub use output::NdjsonOutput;
+#[cfg(feature = "parquet")]
+pub use output::ParquetOutput;
+pub use classify::{classify, StringKind};
 pub use error::{MemstrapError, Result};
+pub use run::{build_extractor, run, run_until, RunSummary};
@@ -0,0 +1,96 @@
+use std::fmt;
+
+/// Byte offset of a found string within the scanned input
+///
+/// Centralizes offset formatting so every writer renders hex the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct Offset(pub u64);
+
+impl Offset {
+    /// Uppercase hexadecimal form with a `0x` prefix, e.g. `0x1F40`
+    pub fn hex(&self) -> String {
+        format!("0x{:X}", self.0)
+    }
+
+    /// Decimal form, e.g. `8000`
+    pub fn dec(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// Raw offset value
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Displays in the canonical hexadecimal form (same as [`Offset::hex`])
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:X}", self.0)
+    }
+}
+
+impl fmt::UpperHex for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::LowerHex for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl From<u64> for Offset {
+    fn from(offset: u64) -> Self {
+        Offset(offset)
+    }
+}
+
+impl From<Offset> for u64 {
+    fn from(offset: Offset) -> Self {
+        offset.0
+    }
+}
+
+impl PartialEq<u64> for Offset {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialOrd<u64> for Offset {
+    fn partial_cmp(&self, other: &u64) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_formatting() {
+        let offset = Offset::from(0x1F40);
+        assert_eq!(offset.hex(), "0x1F40");
+        assert_eq!(offset.dec(), "8000");
+        assert_eq!(offset.to_string(), "0x1F40");
+        assert_eq!(format!("{:08x}", offset), "00001f40");
+
+        let zero = Offset::default();
+        assert_eq!(zero.hex(), "0x0");
+        assert_eq!(zero.dec(), "0");
+    }
+
+    #[test]
+    fn test_offset_conversions() {
+        let offset: Offset = 42u64.into();
+        let raw: u64 = offset.into();
+        assert_eq!(raw, 42);
+        assert!(offset == 42);
+        assert!(offset > 41 && offset < 43);
+        assert!(Offset(1) < Offset(2));
+    }
+}
@@ -1,9 +1,202 @@
-use crate::extractor::FoundString;
+use crate::classify::{classify, decode_base64, normalize_timestamp, strings_preview};
+use crate::config::{ContextView, GroupBy, OutputFormat, QuoteStyle};
+use crate::extractor::{Encoding, FoundString};
 use crate::error::Result;
-use csv::Writer;
-use std::io::{self, Write};
+use crate::offset::Offset;
+use csv::{Writer, WriterBuilder};
+use regex::Regex;
+use std::collections::HashMap;
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
 
+/// Optional columns and rendering switches for output writers
+#[derive(Debug, Clone)]
+pub struct OutputOptions {
+    /// Append a `Kind` column with the classifier result
+    pub classify: bool,
+    /// Append a `NormalizedTimestamp` column with date/time strings converted to RFC 3339
+    pub normalize_timestamps: bool,
+    /// Append `Base64Hex` and `Base64Preview` columns decoding base64 blobs
+    pub decode_base64: bool,
+    /// Append a `RawBytesHex` column with the matched bytes (requires `raw_bytes`)
+    pub emit_raw_hex: bool,
+    /// How `ContextBefore`/`ContextAfter` bytes are rendered
+    pub context_view: ContextView,
+    /// Split CSV rows into sections (offset order is kept within each section)
+    pub group_by: Option<GroupBy>,
+    /// Write one CSV row per distinct content instead of one per result
+    pub consolidate: bool,
+    /// Append a `WideOffset` column with the offset of the paired UTF-16 copy
+    pub wide_offset: bool,
+    /// Append a `Partition` column with the number of the partition each string lies in
+    pub partition: bool,
+    /// Append `Section` and `SectionOffset` columns with the executable section each string lies in
+    pub section: bool,
+    /// Append a `Region` column with the `--region-map` region each string lies in
+    pub region: bool,
+    /// Append a `GapBefore` column with the distance from the previous string
+    pub gap_before: bool,
+    /// Append a `DecodeNote` column saying how decoding fell short, if it did
+    pub decode_note: bool,
+    /// Append a `Confidence` column with the decode confidence of multibyte strings
+    pub confidence: bool,
+    /// Fraction of chunks scanned (`--sample-rate`), recorded so partial output is recognizable
+    pub sample_rate: Option<f64>,
+    /// CSV field delimiter
+    pub delimiter: u8,
+    /// When CSV fields are quoted
+    pub quote_style: QuoteStyle,
+    /// Highlight matches of this pattern in the CSV `Content` column with ANSI colors
+    pub highlight: Option<Regex>,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        OutputOptions {
+            classify: false,
+            normalize_timestamps: false,
+            decode_base64: false,
+            emit_raw_hex: false,
+            context_view: ContextView::default(),
+            group_by: None,
+            consolidate: false,
+            wide_offset: false,
+            partition: false,
+            section: false,
+            region: false,
+            gap_before: false,
+            decode_note: false,
+            confidence: false,
+            sample_rate: None,
+            delimiter: b',',
+            quote_style: QuoteStyle::default(),
+            highlight: None,
+        }
+    }
+}
+
+impl OutputOptions {
+    /// Comment line text marking sampled output, if the scan was sampled
+    fn sampled_comment(&self) -> Option<String> {
+        self.sample_rate
+            .map(|rate| format!("Sampled scan: {:.0}% of chunks scanned, results are incomplete", rate * 100.0))
+    }
+
+    /// CSV writer over `writer` using the configured delimiter and quoting
+    fn csv_writer<W: Write>(&self, writer: W) -> Writer<W> {
+        WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .quote_style(match self.quote_style {
+                QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+                QuoteStyle::Always => csv::QuoteStyle::Always,
+                QuoteStyle::Never => csv::QuoteStyle::Never,
+            })
+            .from_writer(writer)
+    }
+
+    /// Render the context bytes captured around `found_string`
+    ///
+    /// Returns `(before, after)`, empty when no context was captured.
+    fn render_context(&self, found_string: &FoundString) -> (String, String) {
+        let render = |bytes: &Option<Vec<u8>>, start: u64| {
+            bytes
+                .as_ref()
+                .map(|bytes| match self.context_view {
+                    ContextView::Hex => hex::encode(bytes),
+                    ContextView::Hexdump => hexdump_lines(bytes, start).join("\n"),
+                })
+                .unwrap_or_default()
+        };
+
+        let offset = found_string.offset.get();
+        let before_len = found_string.context_before.as_ref().map_or(0, Vec::len) as u64;
+        (
+            render(&found_string.context_before, offset.saturating_sub(before_len)),
+            render(&found_string.context_after, offset + found_string.byte_length as u64),
+        )
+    }
+}
+
+/// Write results in the given format
+pub fn write_results<W: Write>(
+    format: OutputFormat,
+    writer: W,
+    results: &[FoundString],
+    file_path: &Path,
+    options: &OutputOptions,
+) -> Result<()> {
+    match format {
+        OutputFormat::Csv => CsvOutput::write_results_with(writer, results, file_path, options),
+        OutputFormat::Xml => XmlOutput::write_results_with(writer, results, file_path, options),
+        #[cfg(feature = "msgpack")]
+        OutputFormat::Msgpack => MsgpackOutput::write_results(writer, results),
+        #[cfg(feature = "ndjson")]
+        OutputFormat::Ndjson => NdjsonOutput::write_files(writer, &[FileResults::new(file_path, results)]),
+        #[cfg(feature = "parquet")]
+        OutputFormat::Parquet => {
+            // The Parquet writer needs a `Send` sink, which stdout locks are not
+            let mut writer = writer;
+            let mut buffer = Vec::new();
+            ParquetOutput::write_results(&mut buffer, results)?;
+            writer.write_all(&buffer)?;
+            Ok(())
+        }
+    }
+}
+
+/// Results of one scanned input, for multi-file output
+#[derive(Debug, Clone, Copy)]
+pub struct FileResults<'a> {
+    /// Path of the scanned input
+    pub path: &'a Path,
+    /// Strings found in it
+    pub results: &'a [FoundString],
+    /// Hex-encoded SHA-256 of the input, written as output metadata when set
+    pub sha256: Option<&'a str>,
+}
+
+impl<'a> FileResults<'a> {
+    /// Results of `path` without a hash
+    pub fn new(path: &'a Path, results: &'a [FoundString]) -> Self {
+        FileResults { path, results, sha256: None }
+    }
+}
+
+/// Write the results of several inputs in the given format
+///
+/// CSV rows (consolidated ones too) carry each input's path in the `FilePath` column; XML wraps one
+/// `<strings>` element per input in a `<scan>` root when there is more than one.
+/// NDJSON records carry their input's path, and its hash as `source_sha256`.
+/// MessagePack and Parquet output have no file field and only accept a single
+/// input; they do not record input hashes.
+pub fn write_files<W: Write>(
+    format: OutputFormat,
+    writer: W,
+    files: &[FileResults],
+    options: &OutputOptions,
+) -> Result<()> {
+    match (format, files) {
+        (OutputFormat::Csv, _) if options.consolidate => ConsolidatedOutput::write_files_with(writer, files, options),
+        (OutputFormat::Csv, _) => CsvOutput::write_files_with(writer, files, options),
+        (OutputFormat::Xml, [file]) => XmlOutput::write_file_with(writer, file, options),
+        (OutputFormat::Xml, _) => XmlOutput::write_files_with(writer, files, options),
+        #[cfg(feature = "ndjson")]
+        (OutputFormat::Ndjson, _) => NdjsonOutput::write_files(writer, files),
+        #[cfg(feature = "msgpack")]
+        (OutputFormat::Msgpack, [file]) => write_results(format, writer, file.results, file.path, options),
+        #[cfg(feature = "msgpack")]
+        (OutputFormat::Msgpack, _) => Err(crate::error::MemstrapError::Config(
+            "msgpack output supports a single input file".to_string(),
+        )),
+        #[cfg(feature = "parquet")]
+        (OutputFormat::Parquet, [file]) => write_results(format, writer, file.results, file.path, options),
+        #[cfg(feature = "parquet")]
+        (OutputFormat::Parquet, _) => Err(crate::error::MemstrapError::Config(
+            "parquet output supports a single input file".to_string(),
+        )),
+    }
+}
+
 /// CSV output handler
 pub struct CsvOutput;
 
@@ -14,10 +207,49 @@ impl CsvOutput {
         results: &[FoundString],
         file_path: &Path,
     ) -> Result<()> {
-        let mut csv_writer = Writer::from_writer(writer);
+        Self::write_results_with(writer, results, file_path, &OutputOptions::default())
+    }
+
+    /// Write found strings to CSV format with optional columns enabled
+    pub fn write_results_with<W: Write>(
+        writer: W,
+        results: &[FoundString],
+        file_path: &Path,
+        options: &OutputOptions,
+    ) -> Result<()> {
+        Self::write_files_with(writer, &[FileResults::new(file_path, results)], options)
+    }
+
+    /// Write the results of several inputs to one CSV, tagging rows with their input path
+    ///
+    /// An input's hash, if set, follows its rows as a comment line.
+    pub fn write_files_with<W: Write>(
+        writer: W,
+        files: &[FileResults],
+        options: &OutputOptions,
+    ) -> Result<()> {
+        let first_path = files.first().map_or(Path::new(""), |file| file.path);
+        let mut csv_writer = CsvStreamWriter::new(writer, first_path, options.clone(), true)?;
+        for file in files {
+            csv_writer.set_file_path(file.path);
+            match options.group_by {
+                Some(GroupBy::Encoding) => {
+                    for (encoding, group) in group_by_encoding(file.results) {
+                        csv_writer.write_comment(&format!("Encoding: {} ({} strings)", encoding, group.len()))?;
+                        csv_writer.write_rows(group)?;
+                    }
+                }
+                None => csv_writer.write_rows(file.results.iter())?,
+            }
+            if let Some(sha256) = file.sha256 {
+                csv_writer.write_hash_comment(sha256)?;
+            }
+        }
+        csv_writer.flush()
+    }
 
-        // Write header
-        csv_writer.write_record(&[
+    fn header(options: &OutputOptions) -> Vec<&'static str> {
+        let mut header = vec![
             "FilePath",
             "Offset(Hex)",
             "Offset(Dec)",
@@ -26,34 +258,107 @@ impl CsvOutput {
             "Content",
             "ContextBefore",
             "ContextAfter",
-        ])?;
-
-        // Write data rows
-        for found_string in results {
-            let context_before = found_string.context_before
-                .as_ref()
-                .map(|bytes| hex::encode(bytes))
-                .unwrap_or_default();
+        ];
+        if options.classify {
+            header.push("Kind");
+        }
+        if options.normalize_timestamps {
+            header.push("NormalizedTimestamp");
+        }
+        if options.decode_base64 {
+            header.push("Base64Hex");
+            header.push("Base64Preview");
+        }
+        if options.emit_raw_hex {
+            header.push("RawBytesHex");
+        }
+        if options.wide_offset {
+            header.push("WideOffset");
+        }
+        if options.partition {
+            header.push("Partition");
+        }
+        if options.section {
+            header.push("Section");
+            header.push("SectionOffset");
+        }
+        if options.region {
+            header.push("Region");
+        }
+        if options.gap_before {
+            header.push("GapBefore");
+        }
+        if options.decode_note {
+            header.push("DecodeNote");
+        }
+        if options.confidence {
+            header.push("Confidence");
+        }
+        header
+    }
 
-            let context_after = found_string.context_after
-                .as_ref()
-                .map(|bytes| hex::encode(bytes))
-                .unwrap_or_default();
+    fn record(found_string: &FoundString, file_path: &str, options: &OutputOptions) -> Vec<String> {
+        let (context_before, context_after) = options.render_context(found_string);
 
-            csv_writer.write_record(&[
-                file_path.to_string_lossy().as_ref(),
-                &format!("0x{:X}", found_string.offset),
-                &found_string.offset.to_string(),
-                &found_string.encoding.to_string(),
-                &found_string.byte_length.to_string(),
-                &found_string.content,
-                &context_before,
-                &context_after,
-            ])?;
+        let mut record = vec![
+            file_path.to_string(),
+            found_string.offset.hex(),
+            found_string.offset.dec(),
+            found_string.encoding.to_string(),
+            found_string.byte_length.to_string(),
+            match &options.highlight {
+                Some(pattern) => highlight_matches(&found_string.content, pattern),
+                None => found_string.content.clone(),
+            },
+            context_before,
+            context_after,
+        ];
+        if options.classify {
+            record.push(
+                classify(&found_string.content)
+                    .map(|kind| kind.to_string())
+                    .unwrap_or_default(),
+            );
         }
-
-        csv_writer.flush()?;
-        Ok(())
+        if options.normalize_timestamps {
+            record.push(normalize_timestamp(&found_string.content).unwrap_or_default());
+        }
+        if options.decode_base64 {
+            let decoded = decode_base64(&found_string.content);
+            record.push(decoded.as_ref().map(hex::encode).unwrap_or_default());
+            record.push(decoded.as_deref().map(strings_preview).unwrap_or_default());
+        }
+        if options.emit_raw_hex {
+            record.push(
+                found_string.raw_bytes
+                    .as_ref()
+                    .map(hex::encode)
+                    .unwrap_or_default(),
+            );
+        }
+        if options.wide_offset {
+            record.push(found_string.wide_offset.map(|o| o.hex()).unwrap_or_default());
+        }
+        if options.partition {
+            record.push(found_string.partition.map(|p| p.to_string()).unwrap_or_default());
+        }
+        if options.section {
+            record.push(found_string.section.clone().unwrap_or_default());
+            record.push(found_string.section_offset.map(|o| o.hex()).unwrap_or_default());
+        }
+        if options.region {
+            record.push(found_string.region.clone().unwrap_or_default());
+        }
+        if options.gap_before {
+            record.push(found_string.gap_before.map(|gap| gap.to_string()).unwrap_or_default());
+        }
+        if options.decode_note {
+            record.push(found_string.decode_note.map(|note| note.to_string()).unwrap_or_default());
+        }
+        if options.confidence {
+            record.push(found_string.confidence.map(|c| format!("{:.2}", c)).unwrap_or_default());
+        }
+        record
     }
 
     /// Write results to a file
@@ -73,3 +378,471 @@ impl CsvOutput {
         Self::write_results(handle, results, file_path)
     }
 }
+
+/// ANSI escape sequences that start and end a highlighted match (bold red, as grep uses)
+pub const HIGHLIGHT_START: &str = "\x1b[1;31m";
+pub const HIGHLIGHT_END: &str = "\x1b[0m";
+
+/// Wrap every non-empty match of `pattern` in `text` with the highlight escape sequences
+pub fn highlight_matches(text: &str, pattern: &Regex) -> String {
+    let mut highlighted = String::with_capacity(text.len());
+    let mut last = 0;
+    for found in pattern.find_iter(text).filter(|found| !found.is_empty()) {
+        highlighted.push_str(&text[last..found.start()]);
+        highlighted.push_str(HIGHLIGHT_START);
+        highlighted.push_str(found.as_str());
+        highlighted.push_str(HIGHLIGHT_END);
+        last = found.end();
+    }
+    highlighted.push_str(&text[last..]);
+    highlighted
+}
+
+/// Group results by encoding, in order of each encoding's first appearance
+///
+/// Results keep their relative (offset) order within a group.
+pub fn group_by_encoding(results: &[FoundString]) -> Vec<(Encoding, Vec<&FoundString>)> {
+    let mut groups: Vec<(Encoding, Vec<&FoundString>)> = Vec::new();
+    for found_string in results {
+        match groups.iter_mut().find(|(encoding, _)| *encoding == found_string.encoding) {
+            Some((_, group)) => group.push(found_string),
+            None => groups.push((found_string.encoding, vec![found_string])),
+        }
+    }
+    groups
+}
+
+/// Incremental CSV writer shared by `CsvOutput` and streaming extraction
+///
+/// Rows can be written in batches as windows complete; `new` writes the header
+/// only when asked to, so a resumed scan can append to an existing file. Each
+/// batch goes through its own `csv::Writer`, so `# ...` comment lines can be
+/// interleaved with rows on the underlying writer.
+pub struct CsvStreamWriter<W: Write> {
+    writer: W,
+    file_path: String,
+    options: OutputOptions,
+}
+
+impl<W: Write> CsvStreamWriter<W> {
+    /// Wrap `writer`, writing the header row first if `write_header` is set
+    pub fn new(writer: W, file_path: &Path, options: OutputOptions, write_header: bool) -> Result<Self> {
+        let mut stream = CsvStreamWriter {
+            writer,
+            file_path: file_path.to_string_lossy().into_owned(),
+            options,
+        };
+        if write_header {
+            let mut csv_writer = stream.options.csv_writer(&mut stream.writer);
+            csv_writer.write_record(CsvOutput::header(&stream.options))?;
+            csv_writer.flush()?;
+            drop(csv_writer);
+            if let Some(comment) = stream.options.sampled_comment() {
+                stream.write_comment(&comment)?;
+            }
+        }
+        Ok(stream)
+    }
+
+    /// Path reported in the `FilePath` column of subsequent rows
+    pub fn set_file_path(&mut self, file_path: &Path) {
+        self.file_path = file_path.to_string_lossy().into_owned();
+    }
+
+    /// Write a `# text` section marker line between rows
+    pub fn write_comment(&mut self, text: &str) -> Result<()> {
+        writeln!(self.writer, "# {}", text)?;
+        Ok(())
+    }
+
+    /// Write the current input's hash as a `# SHA256 (path) = hex` comment line
+    pub fn write_hash_comment(&mut self, sha256: &str) -> Result<()> {
+        let text = format!("SHA256 ({}) = {}", self.file_path, sha256);
+        self.write_comment(&text)
+    }
+
+    /// Append one row per result
+    pub fn write_rows<'a>(&mut self, results: impl IntoIterator<Item = &'a FoundString>) -> Result<()> {
+        let mut csv_writer = self.options.csv_writer(&mut self.writer);
+        for found_string in results {
+            csv_writer.write_record(CsvOutput::record(found_string, &self.file_path, &self.options))?;
+        }
+        csv_writer.flush()?;
+        Ok(())
+    }
+
+    /// Flush written rows to the underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Unwrap the underlying writer, for example to finish a compressed stream
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// One distinct string and everywhere it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsolidatedRow<'a> {
+    /// Decoded content shared by all occurrences
+    pub content: &'a str,
+    /// Encodings it was found in, in order of first appearance
+    pub encodings: Vec<Encoding>,
+    /// Offset of every occurrence, in result order
+    pub offsets: Vec<Offset>,
+}
+
+/// CSV output with one row per distinct content (`--consolidate`)
+///
+/// Rows list the set of encodings the content was found in and the offsets of
+/// all its occurrences, in order of the content's first occurrence.
+pub struct ConsolidatedOutput;
+
+impl ConsolidatedOutput {
+    /// Group `results` by content
+    pub fn rows(results: &[FoundString]) -> Vec<ConsolidatedRow<'_>> {
+        let mut rows: Vec<ConsolidatedRow> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for found_string in results {
+            let row = *index.entry(&found_string.content).or_insert_with(|| {
+                rows.push(ConsolidatedRow {
+                    content: &found_string.content,
+                    encodings: Vec::new(),
+                    offsets: Vec::new(),
+                });
+                rows.len() - 1
+            });
+            let row = &mut rows[row];
+            if !row.encodings.contains(&found_string.encoding) {
+                row.encodings.push(found_string.encoding);
+            }
+            row.offsets.push(found_string.offset);
+        }
+        rows
+    }
+
+    /// Write one consolidated section per input, tagging rows with their input path
+    ///
+    /// Multi-valued columns are joined with `;`. An input's hash, if set,
+    /// follows its rows as a comment line.
+    pub fn write_files_with<W: Write>(
+        mut writer: W,
+        files: &[FileResults],
+        options: &OutputOptions,
+    ) -> Result<()> {
+        {
+            let mut csv_writer = options.csv_writer(&mut writer);
+            csv_writer.write_record(["FilePath", "Content", "Count", "Encodings", "Offsets"])?;
+            csv_writer.flush()?;
+        }
+        if let Some(comment) = options.sampled_comment() {
+            writeln!(writer, "# {}", comment)?;
+        }
+        for file in files {
+            let path = file.path.to_string_lossy();
+            let mut csv_writer = options.csv_writer(&mut writer);
+            for row in Self::rows(file.results) {
+                let encodings: Vec<String> = row.encodings.iter().map(Encoding::to_string).collect();
+                let offsets: Vec<String> = row.offsets.iter().map(Offset::hex).collect();
+                csv_writer.write_record([
+                    path.as_ref(),
+                    row.content,
+                    &row.offsets.len().to_string(),
+                    &encodings.join(";"),
+                    &offsets.join(";"),
+                ])?;
+            }
+            csv_writer.flush()?;
+            drop(csv_writer);
+            if let Some(sha256) = file.sha256 {
+                writeln!(writer, "# SHA256 ({}) = {}", path, sha256)?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// XML output handler
+pub struct XmlOutput;
+
+impl XmlOutput {
+    /// Write found strings as XML
+    pub fn write_results<W: Write>(
+        writer: W,
+        results: &[FoundString],
+        file_path: &Path,
+    ) -> Result<()> {
+        Self::write_results_with(writer, results, file_path, &OutputOptions::default())
+    }
+
+    /// Write found strings as XML with optional attributes enabled
+    ///
+    /// Produces a `<strings>` root with one `<string>` element per result; the
+    /// decoded text is the element content and metadata is carried in attributes.
+    pub fn write_results_with<W: Write>(
+        writer: W,
+        results: &[FoundString],
+        file_path: &Path,
+        options: &OutputOptions,
+    ) -> Result<()> {
+        Self::write_file_with(writer, &FileResults::new(file_path, results), options)
+    }
+
+    /// Write the results of one input as a single `<strings>` root, carrying
+    /// its hash in a `sha256` attribute if set
+    pub fn write_file_with<W: Write>(writer: W, file: &FileResults, options: &OutputOptions) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        Self::write_strings_element(&mut writer, file, options, "")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write the results of several inputs as a `<scan>` root holding one
+    /// `<strings>` element per input
+    pub fn write_files_with<W: Write>(
+        writer: W,
+        files: &[FileResults],
+        options: &OutputOptions,
+    ) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(writer, "<scan>")?;
+        for file in files {
+            Self::write_strings_element(&mut writer, file, options, "  ")?;
+        }
+        writeln!(writer, "</scan>")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_strings_element<W: Write>(
+        writer: &mut W,
+        file: &FileResults,
+        options: &OutputOptions,
+        indent: &str,
+    ) -> Result<()> {
+        write!(writer, r#"{}<strings file="{}""#, indent, escape_xml(&file.path.to_string_lossy()))?;
+        if let Some(sha256) = file.sha256 {
+            write!(writer, r#" sha256="{}""#, sha256)?;
+        }
+        if let Some(rate) = options.sample_rate {
+            write!(writer, r#" sample_rate="{}""#, rate)?;
+        }
+        writeln!(writer, ">")?;
+
+        for found_string in file.results {
+            write!(
+                writer,
+                r#"{}  <string offset="{}" encoding="{}" length="{}""#,
+                indent, found_string.offset.hex(), found_string.encoding, found_string.byte_length
+            )?;
+            let (context_before, context_after) = options.render_context(found_string);
+            if found_string.context_before.is_some() {
+                write!(writer, r#" context_before="{}""#, escape_xml(&context_before))?;
+            }
+            if found_string.context_after.is_some() {
+                write!(writer, r#" context_after="{}""#, escape_xml(&context_after))?;
+            }
+            if options.classify {
+                if let Some(kind) = classify(&found_string.content) {
+                    write!(writer, r#" kind="{}""#, kind)?;
+                }
+            }
+            if options.normalize_timestamps {
+                if let Some(timestamp) = normalize_timestamp(&found_string.content) {
+                    write!(writer, r#" timestamp="{}""#, timestamp)?;
+                }
+            }
+            if options.decode_base64 {
+                if let Some(decoded) = decode_base64(&found_string.content) {
+                    write!(writer, r#" base64_hex="{}""#, hex::encode(&decoded))?;
+                    write!(writer, r#" base64_preview="{}""#, escape_xml(&strings_preview(&decoded)))?;
+                }
+            }
+            if options.emit_raw_hex {
+                if let Some(ref bytes) = found_string.raw_bytes {
+                    write!(writer, r#" raw_bytes_hex="{}""#, hex::encode(bytes))?;
+                }
+            }
+            if options.wide_offset {
+                if let Some(wide_offset) = found_string.wide_offset {
+                    write!(writer, r#" wide_offset="{}""#, wide_offset.hex())?;
+                }
+            }
+            if let Some(partition) = found_string.partition {
+                write!(writer, r#" partition="{}""#, partition)?;
+            }
+            if let Some(ref section) = found_string.section {
+                write!(writer, r#" section="{}""#, escape_xml(section))?;
+            }
+            if let Some(section_offset) = found_string.section_offset {
+                write!(writer, r#" section_offset="{}""#, section_offset.hex())?;
+            }
+            if let Some(ref region) = found_string.region {
+                write!(writer, r#" region="{}""#, escape_xml(region))?;
+            }
+            if let Some(gap_before) = found_string.gap_before {
+                write!(writer, r#" gap_before="{}""#, gap_before)?;
+            }
+            if options.decode_note {
+                if let Some(note) = found_string.decode_note {
+                    write!(writer, r#" decode_note="{}""#, note)?;
+                }
+            }
+            if options.confidence {
+                if let Some(confidence) = found_string.confidence {
+                    write!(writer, r#" confidence="{:.2}""#, confidence)?;
+                }
+            }
+            writeln!(writer, ">{}</string>", escape_xml(&found_string.content))?;
+        }
+
+        writeln!(writer, "{}</strings>", indent)?;
+        Ok(())
+    }
+}
+
+/// Format bytes as classic 16-per-line `offset  hex  |ascii|` hexdump rows
+pub fn hexdump_lines(bytes: &[u8], start_offset: u64) -> Vec<String> {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            format!("{:08x}  {:<47}  |{}|", start_offset + (i * 16) as u64, hex.join(" "), ascii)
+        })
+        .collect()
+}
+
+/// MessagePack output handler
+#[cfg(feature = "msgpack")]
+pub struct MsgpackOutput;
+
+#[cfg(feature = "msgpack")]
+impl MsgpackOutput {
+    /// Write found strings as a MessagePack array of maps keyed by field name
+    ///
+    /// Optional output columns do not apply; every `FoundString` field is
+    /// written, so the output deserializes back into `Vec<FoundString>`.
+    pub fn write_results<W: Write>(writer: W, results: &[FoundString]) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        rmp_serde::encode::write_named(&mut writer, results)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Newline-delimited JSON output handler
+#[cfg(feature = "ndjson")]
+pub struct NdjsonOutput;
+
+/// One NDJSON line: a result with the input it came from
+#[cfg(feature = "ndjson")]
+#[derive(serde::Serialize)]
+struct NdjsonRecord<'a> {
+    file_path: &'a Path,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_sha256: Option<&'a str>,
+    #[serde(flatten)]
+    found: &'a FoundString,
+}
+
+#[cfg(feature = "ndjson")]
+impl NdjsonOutput {
+    /// Write one JSON object per line for every result of every input
+    ///
+    /// Each object holds the `FoundString` fields plus `file_path` and, when
+    /// the input was hashed (`--emit-hash`), `source_sha256`, so records stay
+    /// attributable after streams from several scans are merged. The hash is
+    /// computed once per input and repeated on each of its records.
+    pub fn write_files<W: Write>(writer: W, files: &[FileResults]) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        for file in files {
+            for found in file.results {
+                let record = NdjsonRecord { file_path: file.path, source_sha256: file.sha256, found };
+                serde_json::to_writer(&mut writer, &record)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Parquet output handler
+#[cfg(feature = "parquet")]
+pub struct ParquetOutput;
+
+#[cfg(feature = "parquet")]
+impl ParquetOutput {
+    /// Arrow schema of the written file
+    ///
+    /// Context columns hold the raw bytes and are null when no context was captured.
+    pub fn schema() -> arrow_schema::Schema {
+        use arrow_schema::{DataType, Field, Schema};
+
+        Schema::new(vec![
+            Field::new("offset", DataType::Int64, false),
+            Field::new("encoding", DataType::Utf8, false),
+            Field::new("length", DataType::Int64, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new("context_before", DataType::Binary, true),
+            Field::new("context_after", DataType::Binary, true),
+        ])
+    }
+
+    /// Write found strings as a single-row-group Parquet file
+    pub fn write_results<W: Write + Send>(writer: W, results: &[FoundString]) -> Result<()> {
+        use arrow_array::{ArrayRef, BinaryArray, Int64Array, RecordBatch, StringArray};
+        use parquet::arrow::ArrowWriter;
+        use std::sync::Arc;
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from_iter_values(results.iter().map(|s| s.offset.get() as i64))),
+            Arc::new(StringArray::from_iter_values(results.iter().map(|s| s.encoding.to_string()))),
+            Arc::new(Int64Array::from_iter_values(results.iter().map(|s| s.byte_length as i64))),
+            Arc::new(StringArray::from_iter_values(results.iter().map(|s| s.content.as_str()))),
+            Arc::new(BinaryArray::from_iter(results.iter().map(|s| s.context_before.as_deref()))),
+            Arc::new(BinaryArray::from_iter(results.iter().map(|s| s.context_after.as_deref()))),
+        ];
+        let schema = Arc::new(Self::schema());
+        let batch = RecordBatch::try_new(schema.clone(), columns).map_err(parquet::errors::ParquetError::from)?;
+
+        let mut writer = ArrowWriter::try_new(writer, schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+/// Escape text for use in XML content or attribute values
+///
+/// Markup characters become entities, tab/CR/LF become character references so
+/// they survive attribute normalization, and characters XML 1.0 cannot represent
+/// at all are replaced with U+FFFD.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' => escaped.push_str("&#x9;"),
+            '\n' => escaped.push_str("&#xA;"),
+            '\r' => escaped.push_str("&#xD;"),
+            c if c < '\u{20}' || c == '\u{FFFE}' || c == '\u{FFFF}' => escaped.push('\u{FFFD}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
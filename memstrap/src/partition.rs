@@ -0,0 +1,192 @@
+//! MBR/GPT partition table parsing for `--partition-table`
+
+use crate::error::{MemstrapError, Result};
+use std::ops::Range;
+
+/// Sector size assumed for LBA addresses (4Kn disks are not supported)
+pub const SECTOR_SIZE: u64 = 512;
+
+/// Offset of the first of the four MBR partition entries
+const MBR_ENTRIES_OFFSET: usize = 446;
+/// Size of an MBR partition entry
+const MBR_ENTRY_SIZE: usize = 16;
+/// Boot signature stored at bytes 510-511 of the MBR
+const MBR_SIGNATURE: [u8; 2] = [0x55, 0xAA];
+/// MBR partition type of the protective entry covering a GPT disk
+const GPT_PROTECTIVE_TYPE: u8 = 0xEE;
+/// Signature at the start of the GPT header (LBA 1)
+const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+/// Upper bound on GPT entries read, well above the usual 128
+const MAX_GPT_ENTRIES: u32 = 1024;
+
+/// A partition listed in the partition table of a disk image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Partition {
+    /// 1-based partition number (MBR slot or GPT entry index)
+    pub number: u32,
+    /// Byte offset of the partition's first sector
+    pub start: u64,
+    /// Size of the partition in bytes
+    pub len: u64,
+}
+
+impl Partition {
+    /// Byte range of the partition within an image of `image_len` bytes
+    ///
+    /// Truncated at the end of the image; empty when the partition starts past it.
+    pub fn range(&self, image_len: usize) -> Range<usize> {
+        let clamp = |offset: u64| offset.min(image_len as u64) as usize;
+        clamp(self.start)..clamp(self.start.saturating_add(self.len))
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+/// List the partitions of a raw disk image from its MBR, or from its GPT when
+/// the MBR is protective
+///
+/// Partitions are returned in table order. Extended MBR partitions are
+/// reported as a whole; the logical partitions inside them are not followed.
+/// Fails if the image has no valid MBR or GPT.
+pub fn read_partitions(image: &[u8]) -> Result<Vec<Partition>> {
+    let invalid = |reason: &str| MemstrapError::PartitionTable(reason.to_string());
+
+    let mbr = image.get(..SECTOR_SIZE as usize).ok_or_else(|| invalid("image is smaller than one sector"))?;
+    if mbr[510..512] != MBR_SIGNATURE {
+        return Err(invalid("no MBR boot signature (55 AA) at offset 510"));
+    }
+
+    let entries: Vec<&[u8]> = mbr[MBR_ENTRIES_OFFSET..MBR_ENTRIES_OFFSET + 4 * MBR_ENTRY_SIZE]
+        .chunks_exact(MBR_ENTRY_SIZE)
+        .collect();
+    if entries.iter().any(|entry| entry[4] == GPT_PROTECTIVE_TYPE) {
+        return read_gpt(image);
+    }
+
+    Ok(entries
+        .iter()
+        .zip(1..)
+        .filter(|(entry, _)| entry[4] != 0)
+        .filter_map(|(entry, number)| {
+            let first_lba = read_u32(entry, 8)? as u64;
+            let sectors = read_u32(entry, 12)? as u64;
+            (sectors > 0).then_some(Partition {
+                number,
+                start: first_lba * SECTOR_SIZE,
+                len: sectors * SECTOR_SIZE,
+            })
+        })
+        .collect())
+}
+
+/// List the partitions of the GPT whose header is at LBA 1
+fn read_gpt(image: &[u8]) -> Result<Vec<Partition>> {
+    let invalid = |reason: &str| MemstrapError::PartitionTable(reason.to_string());
+
+    let header = image
+        .get(SECTOR_SIZE as usize..2 * SECTOR_SIZE as usize)
+        .filter(|header| header.starts_with(GPT_SIGNATURE))
+        .ok_or_else(|| invalid("protective MBR found, but no GPT header at LBA 1"))?;
+    let entries_lba = read_u64(header, 72).unwrap_or_default();
+    let entry_count = read_u32(header, 80).unwrap_or_default().min(MAX_GPT_ENTRIES);
+    let entry_size = read_u32(header, 84).unwrap_or_default() as usize;
+    if entry_size < 128 {
+        return Err(invalid("GPT header has an invalid partition entry size"));
+    }
+
+    let mut partitions = Vec::new();
+    for number in 1..=entry_count {
+        let offset = entries_lba
+            .checked_mul(SECTOR_SIZE)
+            .and_then(|start| start.checked_add((number as u64 - 1) * entry_size as u64))
+            .and_then(|offset| usize::try_from(offset).ok())
+            .ok_or_else(|| invalid("GPT partition entries lie outside the image"))?;
+        let entry = image
+            .get(offset..offset + entry_size)
+            .ok_or_else(|| invalid("GPT partition entries lie outside the image"))?;
+
+        // An all-zero type GUID marks an unused entry
+        if entry[..16].iter().all(|&byte| byte == 0) {
+            continue;
+        }
+        let (first_lba, last_lba) = (read_u64(entry, 32).unwrap_or_default(), read_u64(entry, 40).unwrap_or_default());
+        if last_lba < first_lba {
+            continue;
+        }
+        partitions.push(Partition {
+            number,
+            start: first_lba.saturating_mul(SECTOR_SIZE),
+            len: (last_lba - first_lba + 1).saturating_mul(SECTOR_SIZE),
+        });
+    }
+    Ok(partitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mbr_entry(image: &mut [u8], slot: usize, kind: u8, first_lba: u32, sectors: u32) {
+        let entry = &mut image[MBR_ENTRIES_OFFSET + slot * MBR_ENTRY_SIZE..][..MBR_ENTRY_SIZE];
+        entry[4] = kind;
+        entry[8..12].copy_from_slice(&first_lba.to_le_bytes());
+        entry[12..16].copy_from_slice(&sectors.to_le_bytes());
+    }
+
+    #[test]
+    fn test_read_mbr_partitions() {
+        let mut image = vec![0u8; 16 * 512];
+        image[510..512].copy_from_slice(&MBR_SIGNATURE);
+        mbr_entry(&mut image, 0, 0x83, 2, 4);
+        mbr_entry(&mut image, 2, 0x07, 8, 100);
+
+        let partitions = read_partitions(&image).unwrap();
+        assert_eq!(
+            partitions,
+            vec![
+                Partition { number: 1, start: 1024, len: 2048 },
+                Partition { number: 3, start: 4096, len: 51200 },
+            ]
+        );
+        // The second partition runs past the end of the image
+        assert_eq!(partitions[1].range(image.len()), 4096..8192);
+    }
+
+    #[test]
+    fn test_read_gpt_partitions() {
+        let mut image = vec![0u8; 64 * 512];
+        image[510..512].copy_from_slice(&MBR_SIGNATURE);
+        mbr_entry(&mut image, 0, GPT_PROTECTIVE_TYPE, 1, 63);
+
+        let header = &mut image[512..1024];
+        header[..8].copy_from_slice(GPT_SIGNATURE);
+        header[72..80].copy_from_slice(&2u64.to_le_bytes());
+        header[80..84].copy_from_slice(&4u32.to_le_bytes());
+        header[84..88].copy_from_slice(&128u32.to_le_bytes());
+
+        // Entry 2 is used, entries 1, 3 and 4 are empty
+        let entry = &mut image[1024 + 128..1024 + 256];
+        entry[..16].fill(0xAB);
+        entry[32..40].copy_from_slice(&34u64.to_le_bytes());
+        entry[40..48].copy_from_slice(&40u64.to_le_bytes());
+
+        assert_eq!(read_partitions(&image).unwrap(), vec![Partition { number: 2, start: 34 * 512, len: 7 * 512 }]);
+    }
+
+    #[test]
+    fn test_read_partitions_rejects_images_without_a_table() {
+        assert!(read_partitions(&[0u8; 100]).is_err());
+        assert!(read_partitions(&[0u8; 4096]).is_err());
+
+        let mut image = vec![0u8; 4096];
+        image[510..512].copy_from_slice(&MBR_SIGNATURE);
+        mbr_entry(&mut image, 0, GPT_PROTECTIVE_TYPE, 1, 7);
+        assert!(read_partitions(&image).is_err());
+    }
+}
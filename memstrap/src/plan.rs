@@ -0,0 +1,332 @@
+//! Scan planning: thread count, chunking and memory estimates
+
+use crate::error::{MemstrapError, Result};
+use crate::extractor::FoundString;
+
+/// Files at least this large are scanned with at most `LARGE_FILE_MAX_THREADS` threads
+const LARGE_FILE_THRESHOLD: usize = 100 * 1024 * 1024;
+/// Thread cap for large files, to avoid excessive overhead
+const LARGE_FILE_MAX_THREADS: usize = 8;
+/// Smallest chunk worth handing to its own thread
+const MIN_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+/// Size of the blocks `--sample-rate` picks from, so inputs scanned as a single chunk can be sampled too
+pub const SAMPLE_BLOCK_SIZE: usize = 1024 * 1024;
+/// Rough input density of reported strings, per encoding, used for memory estimates
+const BYTES_PER_RESULT: u64 = 256;
+/// Rough heap footprint of one result's content beyond the `FoundString` itself
+const RESULT_HEAP_BYTES: u64 = 32;
+
+/// How an input will be divided up and scanned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanPlan {
+    /// Size of the input in bytes
+    pub file_size: u64,
+    /// Number of threads scanning in parallel
+    pub num_threads: usize,
+    /// Bytes per chunk (per window in streaming mode), excluding overlap
+    pub chunk_size: usize,
+    /// Number of chunks (windows in streaming mode)
+    pub chunk_count: usize,
+    /// Bytes each chunk extends into its successor
+    pub overlap: usize,
+    /// Whether the input is read window by window (`--stream`)
+    pub streaming: bool,
+}
+
+impl ScanPlan {
+    /// Plan a parallel scan of `file_size` bytes with up to `max_threads` threads
+    ///
+    /// Fails if the overlap is not smaller than the resulting chunk size.
+    pub fn parallel(file_size: usize, max_threads: usize, overlap: usize) -> Result<Self> {
+        let optimal_threads = if file_size > LARGE_FILE_THRESHOLD {
+            std::cmp::min(max_threads, LARGE_FILE_MAX_THREADS)
+        } else {
+            max_threads
+        };
+        Self::parallel_uncapped(file_size, optimal_threads, overlap)
+    }
+
+    /// Like [`parallel`](Self::parallel), but without the thread cap for large
+    /// files, for a thread count given explicitly with `--threads`
+    ///
+    /// Chunks are still at least 16 MiB, so small inputs use fewer threads.
+    pub fn parallel_uncapped(file_size: usize, max_threads: usize, overlap: usize) -> Result<Self> {
+        let num_threads = if file_size < MIN_CHUNK_SIZE {
+            1
+        } else {
+            std::cmp::min(max_threads, file_size / MIN_CHUNK_SIZE).max(1)
+        };
+
+        let chunk_size = if num_threads == 1 { file_size } else { file_size / num_threads };
+        if num_threads > 1 && overlap >= chunk_size {
+            return Err(MemstrapError::Config(format!(
+                "overlap ({} bytes) must be smaller than the chunk size ({} bytes)",
+                overlap, chunk_size
+            )));
+        }
+
+        Ok(ScanPlan {
+            file_size: file_size as u64,
+            num_threads,
+            chunk_size,
+            chunk_count: num_threads,
+            overlap,
+            streaming: false,
+        })
+    }
+
+    /// Plan a parallel scan with an explicit `threads` count, which is not capped
+    /// for large inputs, or else up to `default_threads` threads
+    pub fn with_threads(file_size: usize, threads: Option<usize>, default_threads: usize, overlap: usize) -> Result<Self> {
+        match threads {
+            Some(threads) => Self::parallel_uncapped(file_size, threads, overlap),
+            None => Self::parallel(file_size, default_threads, overlap),
+        }
+    }
+
+    /// Plan a streaming scan of `file_size` bytes in windows of `window_size`
+    pub fn streaming(file_size: u64, window_size: usize, overlap: usize) -> Self {
+        let window_size = window_size.max(1);
+        ScanPlan {
+            file_size,
+            num_threads: 1,
+            chunk_size: window_size,
+            chunk_count: file_size.div_ceil(window_size as u64) as usize,
+            overlap,
+            streaming: true,
+        }
+    }
+
+    /// Plan a scan of `file_size` bytes
+    ///
+    /// Streams when `streaming` is requested or the input exceeds
+    /// `memory_budget` bytes (`None` when the scan cannot switch to streaming);
+    /// otherwise plans a parallel scan as [`with_threads`](Self::with_threads) does.
+    pub fn choose(
+        file_size: u64,
+        streaming: bool,
+        memory_budget: Option<u64>,
+        threads: Option<usize>,
+        default_threads: usize,
+        window_size: usize,
+        overlap: usize,
+    ) -> Result<Self> {
+        if streaming || memory_budget.is_some_and(|budget| file_size > budget) {
+            Ok(Self::streaming(file_size, window_size, overlap))
+        } else {
+            Self::with_threads(file_size as usize, threads, default_threads, overlap)
+        }
+    }
+
+    /// The parallel chunks as `(start, end, base_offset)`
+    pub fn chunks(&self) -> Vec<(usize, usize, u64)> {
+        plan_chunks(self.file_size as usize, self.num_threads, self.overlap)
+    }
+
+    /// The input split into blocks of at most `SAMPLE_BLOCK_SIZE` for `--sample-rate`, as `(start, end, base_offset)`
+    ///
+    /// Blocks overlap like chunks do; scanning them all finds what scanning
+    /// the chunks would.
+    pub fn sample_blocks(&self) -> Vec<(usize, usize, u64)> {
+        let file_size = self.file_size as usize;
+        plan_chunks(file_size, file_size.div_ceil(SAMPLE_BLOCK_SIZE), self.overlap)
+    }
+
+    /// Rough peak memory use of the scan, in bytes
+    ///
+    /// Counts the input buffer (the whole file when `buffered`, one window
+    /// when streaming, nothing for a memory map) plus the result records held
+    /// at once for `encodings` encodings. Real usage depends heavily on how
+    /// many strings the data contains.
+    pub fn estimated_peak_memory(&self, buffered: bool, encodings: usize) -> u64 {
+        let (input, held) = if self.streaming {
+            let window = self.chunk_size as u64 + self.overlap as u64;
+            (window, window.min(self.file_size))
+        } else if buffered {
+            (self.file_size, self.file_size)
+        } else {
+            (0, self.file_size)
+        };
+        let result_size = std::mem::size_of::<FoundString>() as u64 + RESULT_HEAP_BYTES;
+        input + held / BYTES_PER_RESULT * encodings as u64 * result_size
+    }
+}
+
+/// Memory currently available to the process, in bytes (`u64::MAX` if unknown)
+pub fn available_memory() -> u64 {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    match system.available_memory() {
+        0 => u64::MAX,
+        available => available,
+    }
+}
+
+/// Whether chunk (or window) `index` is scanned when sampling a `rate` fraction of them (`--sample-rate`)
+///
+/// Picks evenly spread chunks, always including the first for any rate above
+/// zero, so the first `n` chunks contain `ceil(n * rate)` sampled ones.
+pub fn is_sampled(index: usize, rate: f64) -> bool {
+    let picked = |count: usize| (count as f64 * rate).ceil();
+    picked(index + 1) > picked(index)
+}
+
+/// Split `data_len` bytes into `num_threads` chunks of `(start, end, base_offset)`
+///
+/// Every chunk but the last extends `overlap_size` bytes into its successor so
+/// strings crossing a boundary are still seen whole by one of the two chunks.
+/// At least one and at most `data_len` chunks are made, so none is empty
+/// unless the input is. Chunk ends are clamped to `data_len` rather than
+/// overflowing, whatever the sizes.
+pub fn plan_chunks(data_len: usize, num_threads: usize, overlap_size: usize) -> Vec<(usize, usize, u64)> {
+    let num_threads = num_threads.clamp(1, data_len.max(1));
+    let chunk_size = data_len / num_threads;
+
+    (0..num_threads)
+        .map(|i| {
+            // i < num_threads, so these stay within data_len
+            let start = i * chunk_size;
+            let end = if i == num_threads - 1 {
+                data_len
+            } else {
+                ((i + 1) * chunk_size).checked_add(overlap_size).map_or(data_len, |end| end.min(data_len))
+            };
+            (start, end, start as u64)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: usize = 1024 * 1024;
+
+    #[test]
+    fn test_plan_chunks_overlap() {
+        let chunks = plan_chunks(128, 2, 16);
+        assert_eq!(chunks, vec![(0, 80, 0), (64, 128, 64)]);
+
+        let chunks = plan_chunks(100, 1, 16);
+        assert_eq!(chunks, vec![(0, 100, 0)]);
+    }
+
+    #[test]
+    fn test_plan_chunks_extreme_sizes() {
+        // Each chunk starts where the previous one's own bytes end and stays in bounds
+        let check = |data_len: usize, num_threads: usize, overlap: usize| {
+            let chunks = plan_chunks(data_len, num_threads, overlap);
+            assert!(!chunks.is_empty());
+            assert_eq!(chunks[0].0, 0);
+            assert_eq!(chunks.last().unwrap().1, data_len);
+            for (start, end, base) in &chunks {
+                assert!(start <= end && *end <= data_len);
+                assert_eq!(*base, *start as u64);
+            }
+            for pair in chunks.windows(2) {
+                assert!(pair[0].0 < pair[1].0, "{:?}", pair);
+                assert!(pair[0].1 >= pair[1].0, "{:?}", pair);
+            }
+            chunks
+        };
+
+        // Chunk ends near usize::MAX must not overflow when the overlap is added
+        check(usize::MAX, 8, 4096);
+        check(usize::MAX - 1, 3, usize::MAX);
+        check(usize::MAX / 2 + 1, 2, usize::MAX / 2);
+
+        // More threads than bytes: one chunk per byte, none empty
+        assert_eq!(check(3, 8, 0), vec![(0, 1, 0), (1, 2, 1), (2, 3, 2)]);
+        assert_eq!(check(3, 8, 16), vec![(0, 3, 0), (1, 3, 1), (2, 3, 2)]);
+
+        // Zero threads or an empty input still yield a single chunk
+        assert_eq!(check(100, 0, 16), vec![(0, 100, 0)]);
+        assert_eq!(check(0, 4, 16), vec![(0, 0, 0)]);
+    }
+
+    #[test]
+    fn test_parallel_plan_threads_and_chunks() {
+        // Small inputs use a single thread
+        let plan = ScanPlan::parallel(10 * MB, 16, 4096).unwrap();
+        assert_eq!((plan.num_threads, plan.chunk_size), (1, 10 * MB));
+
+        // One thread per 16 MiB, up to the requested count
+        let plan = ScanPlan::parallel(64 * MB, 16, 4096).unwrap();
+        assert_eq!((plan.num_threads, plan.chunk_size), (4, 16 * MB));
+        let plan = ScanPlan::parallel(64 * MB, 2, 4096).unwrap();
+        assert_eq!((plan.num_threads, plan.chunk_size), (2, 32 * MB));
+
+        // Large inputs are capped at 8 threads
+        let plan = ScanPlan::parallel(1024 * MB, 32, 4096).unwrap();
+        assert_eq!((plan.num_threads, plan.chunk_size), (8, 128 * MB));
+        assert_eq!(plan.chunks().len(), 8);
+    }
+
+    #[test]
+    fn test_is_sampled_spreads_chunks() {
+        let sampled = |rate| (0..10).filter(|&i| is_sampled(i, rate)).collect::<Vec<_>>();
+        assert_eq!(sampled(0.5), [0, 2, 4, 6, 8]);
+        assert_eq!(sampled(0.25), [0, 4, 8]);
+        assert_eq!(sampled(1.0).len(), 10);
+        assert!(sampled(0.0).is_empty());
+        assert_eq!((0..1000).filter(|&i| is_sampled(i, 0.1)).count(), 100);
+    }
+
+    #[test]
+    fn test_sample_blocks() {
+        // A single chunk still yields several blocks to sample from
+        let plan = ScanPlan::parallel(5 * MB / 2, 8, 4096).unwrap();
+        assert_eq!(plan.chunks().len(), 1);
+        let blocks = plan.sample_blocks();
+        assert_eq!(blocks.len(), 3);
+        assert!(blocks.iter().all(|(start, end, _)| end - start <= MB + 4096));
+        assert_eq!(blocks.last().unwrap().1, 5 * MB / 2);
+    }
+
+    #[test]
+    fn test_explicit_thread_count_is_not_capped() {
+        let plan = ScanPlan::parallel_uncapped(1024 * MB, 32, 4096).unwrap();
+        assert_eq!((plan.num_threads, plan.chunk_size), (32, 32 * MB));
+        assert_eq!(plan.chunks().len(), 32);
+
+        // Below the large-file threshold both plans agree
+        assert_eq!(ScanPlan::parallel_uncapped(64 * MB, 16, 4096).unwrap(), ScanPlan::parallel(64 * MB, 16, 4096).unwrap());
+        // Chunks stay at least 16 MiB
+        assert_eq!(ScanPlan::parallel_uncapped(10 * MB, 32, 4096).unwrap().num_threads, 1);
+    }
+
+    #[test]
+    fn test_parallel_plan_rejects_oversized_overlap() {
+        assert!(ScanPlan::parallel(64 * MB, 4, 16 * MB).is_err());
+        // A single chunk has no neighbour, so any overlap is fine
+        assert!(ScanPlan::parallel(MB, 4, 16 * MB).is_ok());
+    }
+
+    #[test]
+    fn test_plan_streams_over_memory_budget() {
+        let choose = |streaming, budget: Option<usize>, threads| {
+            ScanPlan::choose(1024 * MB as u64, streaming, budget.map(|mb| (mb * MB) as u64), threads, 16, 256 * MB, 4096).unwrap()
+        };
+
+        let plan = choose(false, Some(512), None);
+        assert!(plan.streaming);
+        assert_eq!(plan.chunk_count, 4);
+        assert!(choose(true, None, None).streaming);
+
+        // Within the budget, or when streaming is ruled out, the scan is parallel;
+        // only an explicit thread count lifts the large-input cap
+        assert_eq!(choose(false, Some(1024), None).num_threads, 8);
+        assert_eq!(choose(false, None, Some(32)).num_threads, 32);
+    }
+
+    #[test]
+    fn test_streaming_plan_and_memory_estimate() {
+        let plan = ScanPlan::streaming(100 * MB as u64, 64 * MB, 4096);
+        assert_eq!((plan.num_threads, plan.chunk_count), (1, 2));
+
+        let plan = ScanPlan::parallel(64 * MB, 4, 4096).unwrap();
+        let mapped = plan.estimated_peak_memory(false, 1);
+        assert_eq!(plan.estimated_peak_memory(true, 1), mapped + 64 * MB as u64);
+        assert_eq!(plan.estimated_peak_memory(false, 2), 2 * mapped);
+    }
+}
@@ -0,0 +1,567 @@
+//! Post-processing steps applied to the merged results of all chunks
+
+use crate::config::DedupMode;
+use crate::error::{MemstrapError, Result};
+use crate::extractor::{DecodeNote, Encoding, FoundString};
+use crate::offset::Offset;
+use std::collections::{HashMap, HashSet};
+
+/// Sort results by offset and drop duplicates according to `mode`
+///
+/// Returns the number of results removed.
+pub fn dedup_results(results: &mut Vec<FoundString>, mode: DedupMode) -> usize {
+    let before = results.len();
+    results.sort_by_key(|s| s.offset);
+
+    match mode {
+        DedupMode::None => {}
+        // Signature matches and --embedded-ascii copies never displace a string starting at the same offset
+        DedupMode::Offset => {
+            results.sort_by_key(|s| (s.offset, s.encoding == Encoding::Signature, is_embedded_ascii(s)));
+            results.dedup_by_key(|s| (s.offset, s.encoding == Encoding::Signature, is_embedded_ascii(s)));
+        }
+        DedupMode::Content => {
+            // Results are in offset order, so the first occurrence wins
+            let mut seen = HashSet::new();
+            results.retain(|s| seen.insert(s.content.clone()));
+        }
+        DedupMode::OffsetContent => {
+            let mut seen = HashSet::new();
+            results.retain(|s| seen.insert((s.offset, s.content.clone())));
+        }
+    }
+
+    before - results.len()
+}
+
+/// Whether `s` is the narrow copy of a UTF-16 string made by `--embedded-ascii`
+///
+/// Such copies duplicate their wide string on purpose, so the steps that
+/// collapse duplicates leave them alone.
+fn is_embedded_ascii(s: &FoundString) -> bool {
+    s.decode_note == Some(DecodeNote::EmbeddedAscii)
+}
+
+/// Combine the results of several extractors run over the same data
+///
+/// The result sets are concatenated and sorted by offset. Strings found by more
+/// than one extractor at the same offset are reported once. Then the
+/// cross-encoding merge runs with the encodings ranked in [`Encoding::ALL`]
+/// order.
+pub fn merge_results(results: Vec<Vec<FoundString>>) -> Vec<FoundString> {
+    let mut merged: Vec<FoundString> = results.into_iter().flatten().collect();
+    dedup_results(&mut merged, DedupMode::OffsetContent);
+    merge_cross_encoding(&mut merged, &Encoding::ALL);
+    merged
+}
+
+/// Collapse near-duplicates reported in more than one encoding
+///
+/// When two results carry the same decoded content and their byte ranges
+/// overlap by at least half of the shorter one, only the result whose encoding
+/// comes first in `priority` is kept (encodings not listed rank last, ties keep
+/// the lower offset). `results` must be sorted by offset. Returns the number of
+/// results removed.
+pub fn merge_cross_encoding(results: &mut Vec<FoundString>, priority: &[Encoding]) -> usize {
+    let rank = |encoding: Encoding| {
+        priority
+            .iter()
+            .position(|&p| p == encoding)
+            .unwrap_or(priority.len())
+    };
+
+    let mut removed = vec![false; results.len()];
+    for i in 0..results.len() {
+        if removed[i] {
+            continue;
+        }
+        let (a_start, a_end) = byte_range(&results[i]);
+        for j in i + 1..results.len() {
+            let (b_start, b_end) = byte_range(&results[j]);
+            if b_start >= a_end {
+                break;
+            }
+            if removed[j]
+                || is_embedded_ascii(&results[i])
+                || is_embedded_ascii(&results[j])
+                || results[i].encoding == results[j].encoding
+                || results[i].content != results[j].content
+            {
+                continue;
+            }
+
+            let overlap = a_end.min(b_end) - b_start;
+            let shorter = (a_end - a_start).min(b_end - b_start);
+            if overlap * 2 < shorter {
+                continue;
+            }
+
+            if rank(results[j].encoding) < rank(results[i].encoding) {
+                removed[i] = true;
+                break;
+            }
+            removed[j] = true;
+        }
+    }
+
+    let before = results.len();
+    let mut flags = removed.into_iter();
+    results.retain(|_| !flags.next().unwrap_or(false));
+    before - results.len()
+}
+
+/// Join results of one encoding separated by at most `max_gap` bytes
+///
+/// Each result is appended to the previous result of its encoding when it
+/// starts no more than `max_gap` bytes after that one ends: the contents are
+/// concatenated and the range spans both, so text broken by a stray byte is
+/// reported once. The bytes of the gap are not kept, so a merged result
+/// loses its raw bytes unless the fragments touch. `results` must be sorted
+/// by offset. Returns the number of results merged away.
+pub fn merge_adjacent(results: &mut Vec<FoundString>, max_gap: u64) -> usize {
+    let mut last_of: HashMap<Encoding, usize> = HashMap::new();
+    let mut removed = vec![false; results.len()];
+    for i in 0..results.len() {
+        let encoding = results[i].encoding;
+        let (start, end) = byte_range(&results[i]);
+        let target = last_of.get(&encoding).copied().filter(|&last| {
+            let (_, last_end) = byte_range(&results[last]);
+            start >= last_end && start - last_end <= max_gap
+        });
+        let Some(last) = target else {
+            last_of.insert(encoding, i);
+            continue;
+        };
+
+        let (head, tail) = results.split_at_mut(i);
+        let (merged, next) = (&mut head[last], &mut tail[0]);
+        let touching = byte_range(merged).1 == start;
+        merged.content.push_str(&next.content);
+        merged.byte_length = (end - merged.offset.get()) as usize;
+        merged.raw_bytes = match (merged.raw_bytes.take(), next.raw_bytes.take()) {
+            (Some(mut raw), Some(next_raw)) if touching => {
+                raw.extend(next_raw);
+                Some(raw)
+            }
+            _ => None,
+        };
+        merged.context_after = next.context_after.take();
+        merged.decode_note = merged.decode_note.or(next.decode_note);
+        merged.confidence = match (merged.confidence, next.confidence) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        removed[i] = true;
+    }
+
+    let before = results.len();
+    let mut flags = removed.into_iter();
+    results.retain(|_| !flags.next().unwrap_or(false));
+    before - results.len()
+}
+
+/// Pair ASCII/UTF-8 results with a UTF-16 copy of the same text
+///
+/// Each narrow result is matched with the nearest unpaired UTF-16LE/BE result of
+/// identical content whose offset is at most `max_distance` bytes away. The wide
+/// result is removed and its offset stored in the narrow one's `wide_offset`.
+/// Returns the number of pairs formed.
+pub fn correlate_wide(results: &mut Vec<FoundString>, max_distance: u64) -> usize {
+    let is_wide = |s: &FoundString| matches!(s.encoding, Encoding::Utf16Le | Encoding::Utf16Be);
+    let is_narrow = |s: &FoundString| !is_wide(s) && s.encoding != Encoding::Signature && !is_embedded_ascii(s);
+
+    let mut wide_by_content: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, s) in results.iter().enumerate().filter(|(_, s)| is_wide(s)) {
+        wide_by_content.entry(s.content.as_str()).or_default().push(idx);
+    }
+
+    let mut pairs = Vec::new();
+    let mut paired = vec![false; results.len()];
+    for (idx, narrow) in results.iter().enumerate().filter(|(_, s)| is_narrow(s)) {
+        let Some(candidates) = wide_by_content.get(narrow.content.as_str()) else {
+            continue;
+        };
+        let nearest = candidates
+            .iter()
+            .copied()
+            .filter(|&w| !paired[w])
+            .map(|w| (results[w].offset.get().abs_diff(narrow.offset.get()), w))
+            .filter(|&(distance, _)| distance <= max_distance)
+            .min();
+        if let Some((_, w)) = nearest {
+            paired[w] = true;
+            pairs.push((idx, results[w].offset));
+        }
+    }
+
+    for &(idx, wide_offset) in &pairs {
+        results[idx].wide_offset = Some(wide_offset);
+    }
+    let mut flags = paired.into_iter();
+    results.retain(|_| !flags.next().unwrap_or(false));
+    pairs.len()
+}
+
+/// Keep only the first result in each `bucket_size`-byte bucket of offsets (`--bucket`)
+///
+/// A result survives when no earlier result fell into the same
+/// `offset / bucket_size` bucket, which summarizes repetitive tables with one
+/// string per bucket. `results` must be sorted by offset, so the survivor is
+/// the lowest-offset string of its bucket. Returns the number of results
+/// dropped.
+pub fn keep_first_per_bucket(results: &mut Vec<FoundString>, bucket_size: u64) -> usize {
+    let before = results.len();
+    let mut seen = HashSet::new();
+    results.retain(|s| seen.insert(s.offset.get() / bucket_size));
+    before - results.len()
+}
+
+/// Fewest closely-spaced strings [`detect_tables`] reports as a table
+pub const MIN_TABLE_STRINGS: usize = 5;
+
+/// A run of closely-spaced strings of one encoding, such as a resource string table
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringTable {
+    /// Offset of the first string
+    pub offset: Offset,
+    /// Encoding shared by the strings
+    pub encoding: Encoding,
+    /// Partition the table lies in (`--partition-table`), which its offset is relative to
+    pub partition: Option<u32>,
+    /// Number of strings in the table
+    pub count: usize,
+    /// Bytes from the start of the first string to the end of the last
+    pub byte_length: u64,
+    /// Mean string length in characters
+    pub avg_len: f64,
+}
+
+impl std::fmt::Display for StringTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "table of {} {} strings starting at offset {}",
+            self.count, self.encoding, self.offset
+        )?;
+        if let Some(partition) = self.partition {
+            write!(f, " in partition {}", partition)?;
+        }
+        write!(f, " ({} bytes), avg length {:.1}", self.byte_length, self.avg_len)
+    }
+}
+
+/// Find runs of at least [`MIN_TABLE_STRINGS`] strings packed closely together (`--detect-tables`)
+///
+/// A string extends the run of its encoding (and partition) when it starts no
+/// more than `max_gap` bytes after the previous string of the run ends, as
+/// the entries of a string table do. The results themselves are left
+/// untouched. `results` must be sorted by offset. Tables are returned in
+/// offset order.
+pub fn detect_tables(results: &[FoundString], max_gap: u64) -> Vec<StringTable> {
+    let mut runs: HashMap<(Encoding, Option<u32>), Vec<&FoundString>> = HashMap::new();
+    let mut tables = Vec::new();
+    for s in results.iter().filter(|s| !is_embedded_ascii(s)) {
+        let run = runs.entry((s.encoding, s.partition)).or_default();
+        let start = s.offset.get();
+        let continues = run.last().is_some_and(|last| {
+            let (_, last_end) = byte_range(last);
+            start >= last_end && start - last_end <= max_gap
+        });
+        if !continues {
+            tables.extend(string_table(&std::mem::take(run)));
+        }
+        run.push(s);
+    }
+    tables.extend(runs.into_values().filter_map(|run| string_table(&run)));
+    tables.sort_by_key(|table| (table.partition, table.offset));
+    tables
+}
+
+/// Summary of `run` as a table, if it holds enough strings
+fn string_table(run: &[&FoundString]) -> Option<StringTable> {
+    if run.len() < MIN_TABLE_STRINGS {
+        return None;
+    }
+    let (first, last) = (run[0], run[run.len() - 1]);
+    let total_len: usize = run.iter().map(|s| s.content.chars().count()).sum();
+    Some(StringTable {
+        offset: first.offset,
+        encoding: first.encoding,
+        partition: first.partition,
+        count: run.len(),
+        byte_length: byte_range(last).1 - first.offset.get(),
+        avg_len: total_len as f64 / run.len() as f64,
+    })
+}
+
+/// Shift every offset by `base` so it matches the address space the dump came from
+///
+/// Fails if an offset would overflow.
+pub fn rebase_offsets(results: &mut [FoundString], base: u64) -> Result<()> {
+    if base == 0 {
+        return Ok(());
+    }
+    let rebase = |offset: Offset| {
+        offset.get().checked_add(base).map(Offset).ok_or_else(|| {
+            MemstrapError::Config(format!("--base-offset 0x{:X} overflows the offset {}", base, offset))
+        })
+    };
+    for s in results {
+        s.offset = rebase(s.offset)?;
+        if let Some(wide_offset) = s.wide_offset {
+            s.wide_offset = Some(rebase(wide_offset)?);
+        }
+    }
+    Ok(())
+}
+
+fn byte_range(s: &FoundString) -> (u64, u64) {
+    let start = s.offset.get();
+    (start, start + s.byte_length as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::Encoding;
+
+    fn found(offset: u64, content: &str, encoding: Encoding) -> FoundString {
+        FoundString {
+            offset: offset.into(),
+            content: content.to_string(),
+            encoding,
+            byte_length: content.len(),
+            context_before: None,
+            context_after: None,
+            raw_bytes: None,
+            wide_offset: None,
+            had_bom: false,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note: None,
+            confidence: None,
+        }
+    }
+
+    fn crafted() -> Vec<FoundString> {
+        vec![
+            found(40, "alpha", Encoding::Ascii),
+            found(0, "alpha", Encoding::Ascii),
+            found(0, "alpha", Encoding::Ascii),
+            found(0, "alp", Encoding::Utf16Le),
+            found(20, "beta", Encoding::Ascii),
+        ]
+    }
+
+    fn summary(results: &[FoundString]) -> Vec<(u64, &str)> {
+        results.iter().map(|s| (s.offset.get(), s.content.as_str())).collect()
+    }
+
+    fn wide(offset: u64, content: &str, encoding: Encoding) -> FoundString {
+        FoundString {
+            byte_length: content.len() * 2,
+            ..found(offset, content, encoding)
+        }
+    }
+
+    #[test]
+    fn test_merge_cross_encoding() {
+        let priority = [Encoding::Utf16Le, Encoding::Utf8, Encoding::Ascii];
+        let mut results = vec![
+            // Same run seen as ASCII and, one byte later, as UTF-16LE
+            found(0x10, "Password", Encoding::Ascii),
+            wide(0x11, "Password", Encoding::Utf16Le),
+            // Overlapping but different content is left alone
+            found(0x40, "token=abc", Encoding::Ascii),
+            wide(0x42, "token=ab", Encoding::Utf16Le),
+            // Equal content that barely touches is not a duplicate
+            found(0x80, "AAAAAAAA", Encoding::Ascii),
+            wide(0x87, "AAAAAAAA", Encoding::Utf16Le),
+        ];
+
+        assert_eq!(merge_cross_encoding(&mut results, &priority), 1);
+        let kept: Vec<(u64, Encoding)> = results.iter().map(|s| (s.offset.get(), s.encoding)).collect();
+        assert_eq!(
+            kept,
+            [
+                (0x11, Encoding::Utf16Le),
+                (0x40, Encoding::Ascii),
+                (0x42, Encoding::Utf16Le),
+                (0x80, Encoding::Ascii),
+                (0x87, Encoding::Utf16Le),
+            ]
+        );
+
+        // Reversing the priority keeps the ASCII detection instead
+        let mut results = vec![
+            found(0x10, "Password", Encoding::Ascii),
+            wide(0x11, "Password", Encoding::Utf16Le),
+        ];
+        assert_eq!(merge_cross_encoding(&mut results, &[Encoding::Ascii, Encoding::Utf16Le]), 1);
+        assert_eq!(results[0].encoding, Encoding::Ascii);
+    }
+
+    #[test]
+    fn test_merge_results() {
+        let ascii_scan = vec![
+            found(0x30, "passkey", Encoding::Ascii),
+            found(0x10, "Password", Encoding::Ascii),
+        ];
+        let wide_scan = vec![
+            // Found by both extractors
+            found(0x30, "passkey", Encoding::Ascii),
+            // The same run seen as UTF-16LE, merged into the ASCII result
+            wide(0x11, "Password", Encoding::Utf16Le),
+            wide(0x80, "only wide", Encoding::Utf16Le),
+        ];
+
+        let merged = merge_results(vec![ascii_scan, wide_scan]);
+        let rows: Vec<(u64, &str, Encoding)> =
+            merged.iter().map(|s| (s.offset.get(), s.content.as_str(), s.encoding)).collect();
+        assert_eq!(
+            rows,
+            [
+                (0x10, "Password", Encoding::Ascii),
+                (0x30, "passkey", Encoding::Ascii),
+                (0x80, "only wide", Encoding::Utf16Le),
+            ]
+        );
+        assert!(merge_results(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_merge_adjacent() {
+        let mut results = vec![
+            // "Hello" and "World" separated by one stray byte
+            found(0x10, "Hello", Encoding::Ascii),
+            wide(0x13, "Hel", Encoding::Utf16Le),
+            found(0x16, "World", Encoding::Ascii),
+            // Too far from "World"
+            found(0x30, "again", Encoding::Ascii),
+        ];
+
+        assert_eq!(merge_adjacent(&mut results, 1), 1);
+        let rows: Vec<(u64, &str, usize)> =
+            results.iter().map(|s| (s.offset.get(), s.content.as_str(), s.byte_length)).collect();
+        assert_eq!(rows, [(0x10, "HelloWorld", 11), (0x13, "Hel", 6), (0x30, "again", 5)]);
+
+        // A gap of 0 only joins fragments that touch, keeping their raw bytes
+        let mut results = vec![found(0, "abc", Encoding::Ascii), found(3, "def", Encoding::Ascii)];
+        for s in &mut results {
+            s.raw_bytes = Some(s.content.as_bytes().to_vec());
+        }
+        assert_eq!(merge_adjacent(&mut results, 0), 1);
+        assert_eq!(results[0].content, "abcdef");
+        assert_eq!(results[0].raw_bytes.as_deref(), Some(&b"abcdef"[..]));
+    }
+
+    #[test]
+    fn test_keep_first_per_bucket() {
+        let mut results = vec![
+            found(0x00, "header", Encoding::Ascii),
+            found(0x08, "row one", Encoding::Ascii),
+            found(0x0F, "row two", Encoding::Ascii),
+            found(0x10, "second", Encoding::Ascii),
+            found(0x1F, "more", Encoding::Ascii),
+            found(0x40, "later", Encoding::Ascii),
+        ];
+
+        assert_eq!(keep_first_per_bucket(&mut results, 0x10), 3);
+        let survivors: Vec<&str> = results.iter().map(|s| s.content.as_str()).collect();
+        assert_eq!(survivors, ["header", "second", "later"]);
+
+        // A bucket of one byte keeps every distinct offset
+        assert_eq!(keep_first_per_bucket(&mut results, 1), 0);
+    }
+
+    #[test]
+    fn test_correlate_wide() {
+        let mut results = vec![
+            found(0x100, "Kernel32", Encoding::Ascii),
+            wide(0x180, "Kernel32", Encoding::Utf16Le),
+            found(0x200, "ntdll.dll", Encoding::Ascii),
+            // Too far away from the ASCII copy
+            wide(0x9000, "ntdll.dll", Encoding::Utf16Le),
+            wide(0x9100, "user32", Encoding::Utf16Le),
+        ];
+
+        assert_eq!(correlate_wide(&mut results, 0x1000), 1);
+        let rows: Vec<(u64, &str, Option<u64>)> = results
+            .iter()
+            .map(|s| (s.offset.get(), s.content.as_str(), s.wide_offset.map(u64::from)))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (0x100, "Kernel32", Some(0x180)),
+                (0x200, "ntdll.dll", None),
+                (0x9000, "ntdll.dll", None),
+                (0x9100, "user32", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_tables() {
+        // Ten NUL-terminated strings back to back, then a lone string far away
+        let mut results: Vec<FoundString> =
+            (0..10).map(|i| found(0x100 + i * 7, &format!("entry{}", i), Encoding::Ascii)).collect();
+        results.push(found(0x1000, "lonely", Encoding::Ascii));
+        // Too few UTF-16 strings for a table of their own, interleaved with the ASCII ones
+        results.insert(3, wide(0x108, "abc", Encoding::Utf16Le));
+
+        let tables = detect_tables(&results, 8);
+        assert_eq!(
+            tables,
+            [StringTable {
+                offset: 0x100.into(),
+                encoding: Encoding::Ascii,
+                partition: None,
+                count: 10,
+                byte_length: 69,
+                avg_len: 6.0,
+            }]
+        );
+        assert_eq!(tables[0].to_string(), "table of 10 ASCII strings starting at offset 0x100 (69 bytes), avg length 6.0");
+
+        // A gap wider than allowed splits the run into two short ones
+        assert!(detect_tables(&results, 0).is_empty());
+    }
+
+    #[test]
+    fn test_dedup_none() {
+        let mut results = crafted();
+        assert_eq!(dedup_results(&mut results, DedupMode::None), 0);
+        assert_eq!(
+            summary(&results),
+            [(0, "alpha"), (0, "alpha"), (0, "alp"), (20, "beta"), (40, "alpha")]
+        );
+    }
+
+    #[test]
+    fn test_dedup_offset() {
+        let mut results = crafted();
+        assert_eq!(dedup_results(&mut results, DedupMode::Offset), 2);
+        assert_eq!(summary(&results), [(0, "alpha"), (20, "beta"), (40, "alpha")]);
+    }
+
+    #[test]
+    fn test_dedup_content() {
+        let mut results = crafted();
+        assert_eq!(dedup_results(&mut results, DedupMode::Content), 2);
+        assert_eq!(summary(&results), [(0, "alpha"), (0, "alp"), (20, "beta")]);
+    }
+
+    #[test]
+    fn test_dedup_offset_content() {
+        let mut results = crafted();
+        assert_eq!(dedup_results(&mut results, DedupMode::OffsetContent), 1);
+        assert_eq!(
+            summary(&results),
+            [(0, "alpha"), (0, "alp"), (20, "beta"), (40, "alpha")]
+        );
+    }
+}
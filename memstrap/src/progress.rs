@@ -0,0 +1,267 @@
+//! Byte-based progress reporting with a smoothed throughput ETA
+
+use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// Number of recent samples the throughput estimate is averaged over
+const THROUGHPUT_SAMPLES: usize = 5;
+
+/// Minimum time between two `--progress-json` lines
+pub const JSON_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Rolling throughput estimate over the last few progress updates
+///
+/// The rate is taken between the oldest and newest retained samples, which
+/// smooths out jitter from uneven chunk completion times while still tracking
+/// changes in speed over a long scan.
+#[derive(Debug, Clone)]
+pub struct ThroughputEstimator {
+    samples: VecDeque<(Duration, u64)>,
+    capacity: usize,
+}
+
+impl ThroughputEstimator {
+    /// Estimator averaging over the last `capacity` samples (at least 2)
+    pub fn new(capacity: usize) -> Self {
+        ThroughputEstimator {
+            samples: VecDeque::with_capacity(capacity.max(2)),
+            capacity: capacity.max(2),
+        }
+    }
+
+    /// Record that `bytes_done` bytes were complete `elapsed` after the start
+    pub fn record(&mut self, elapsed: Duration, bytes_done: u64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((elapsed, bytes_done));
+    }
+
+    /// Current throughput in bytes per second, once two distinct samples exist
+    pub fn bytes_per_sec(&self) -> Option<f64> {
+        let &(t0, b0) = self.samples.front()?;
+        let &(t1, b1) = self.samples.back()?;
+        let secs = t1.checked_sub(t0)?.as_secs_f64();
+        if secs <= 0.0 || b1 <= b0 {
+            return None;
+        }
+        Some((b1 - b0) as f64 / secs)
+    }
+
+    /// Estimated time to process `remaining` more bytes at the current throughput
+    pub fn eta(&self, remaining: u64) -> Option<Duration> {
+        self.bytes_per_sec()
+            .map(|rate| Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
+
+/// Where [`ByteProgress`] reports to
+enum ProgressSink {
+    /// Terminal progress bar
+    Bar(ProgressBar),
+    /// Newline-delimited JSON objects, at most one per `interval` besides the final one
+    Json {
+        writer: Box<dyn Write + Send>,
+        interval: Duration,
+        /// When the last line was written, and its bytes done and strings
+        last_line: Option<(Instant, u64, u64)>,
+    },
+}
+
+/// Progress measured in bytes: a bar showing the smoothed throughput and ETA,
+/// or machine-readable JSON lines (`--progress-json`)
+pub struct ByteProgress {
+    sink: ProgressSink,
+    estimator: ThroughputEstimator,
+    started: Instant,
+    total_bytes: u64,
+    bytes_done: u64,
+    strings: u64,
+}
+
+impl ByteProgress {
+    /// Progress bar over `total_bytes`
+    pub fn new(total_bytes: u64) -> Self {
+        let bar = ProgressBar::new(total_bytes);
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} {msg}")
+                .unwrap()
+                .progress_chars("█▉▊▋▌▍▎▏ "),
+        );
+        bar.set_message("Extracting strings...");
+
+        Self::with_sink(ProgressSink::Bar(bar), total_bytes)
+    }
+
+    /// Progress over `total_bytes` written to `writer` as JSON lines like
+    /// `{"bytes_done":1024,"bytes_total":4096,"strings":12}`
+    ///
+    /// A line is written at most once per `interval`, and by
+    /// [`finish`](Self::finish) when the counts changed since the last one. Write errors are ignored, like a bar that
+    /// cannot be drawn.
+    pub fn json(total_bytes: u64, writer: impl Write + Send + 'static, interval: Duration) -> Self {
+        Self::with_sink(ProgressSink::Json { writer: Box::new(writer), interval, last_line: None }, total_bytes)
+    }
+
+    fn with_sink(sink: ProgressSink, total_bytes: u64) -> Self {
+        let mut estimator = ThroughputEstimator::new(THROUGHPUT_SAMPLES);
+        estimator.record(Duration::ZERO, 0);
+        ByteProgress {
+            sink,
+            estimator,
+            started: Instant::now(),
+            total_bytes,
+            bytes_done: 0,
+            strings: 0,
+        }
+    }
+
+    /// Mark `bytes` more bytes as processed, having found `strings` more strings, and refresh the ETA
+    pub fn advance(&mut self, bytes: u64, strings: usize) {
+        self.bytes_done += bytes;
+        self.strings += strings as u64;
+        let done = self.bytes_done;
+        self.estimator.record(self.started.elapsed(), done);
+
+        match self.sink {
+            ProgressSink::Bar(ref bar) => {
+                bar.inc(bytes);
+                let remaining = self.total_bytes.saturating_sub(done);
+                if let (Some(rate), Some(eta)) = (self.estimator.bytes_per_sec(), self.estimator.eta(remaining)) {
+                    bar.set_message(format!(
+                        "{:.1} MB/s, ETA {}",
+                        rate / 1024.0 / 1024.0,
+                        format_eta(eta)
+                    ));
+                }
+            }
+            ProgressSink::Json { interval, last_line, .. } => {
+                if last_line.is_none_or(|(last, _, _)| last.elapsed() >= interval) {
+                    self.write_json_line();
+                }
+            }
+        }
+    }
+
+    /// Finish the bar with a completion message, or write the final JSON line
+    /// unless the last one already holds the final counts
+    pub fn finish(&mut self) {
+        match self.sink {
+            ProgressSink::Bar(ref bar) => bar.finish_with_message("Processing complete!"),
+            ProgressSink::Json { last_line, .. } => {
+                if last_line.is_none_or(|(_, bytes_done, strings)| (bytes_done, strings) != (self.bytes_done, self.strings)) {
+                    self.write_json_line();
+                }
+            }
+        }
+    }
+
+    fn write_json_line(&mut self) {
+        if let ProgressSink::Json { ref mut writer, ref mut last_line, .. } = self.sink {
+            let _ = writeln!(
+                writer,
+                r#"{{"bytes_done":{},"bytes_total":{},"strings":{}}}"#,
+                self.bytes_done, self.total_bytes, self.strings
+            );
+            let _ = writer.flush();
+            *last_line = Some((Instant::now(), self.bytes_done, self.strings));
+        }
+    }
+}
+
+/// Format a duration as `HH:MM:SS`
+fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
+
+    #[test]
+    fn test_throughput_needs_two_samples() {
+        let mut estimator = ThroughputEstimator::new(5);
+        assert_eq!(estimator.bytes_per_sec(), None);
+        estimator.record(at(0.0), 0);
+        assert_eq!(estimator.bytes_per_sec(), None);
+        estimator.record(at(2.0), 200);
+        assert_eq!(estimator.bytes_per_sec(), Some(100.0));
+        assert_eq!(estimator.eta(1000), Some(at(10.0)));
+    }
+
+    #[test]
+    fn test_throughput_is_smoothed_over_recent_samples() {
+        let mut estimator = ThroughputEstimator::new(3);
+        // Slow start at 10 B/s, then a steady 100 B/s
+        estimator.record(at(0.0), 0);
+        estimator.record(at(10.0), 100);
+        estimator.record(at(11.0), 200);
+        // Window still includes the slow start: (200 - 0) / 11s
+        assert!((estimator.bytes_per_sec().unwrap() - 200.0 / 11.0).abs() < 1e-9);
+
+        // A burst within the window is averaged rather than taken at face value
+        estimator.record(at(12.0), 300);
+        estimator.record(at(12.5), 450);
+        assert_eq!(estimator.bytes_per_sec(), Some(250.0 / 1.5));
+
+        estimator.record(at(13.5), 550);
+        estimator.record(at(14.5), 650);
+        assert_eq!(estimator.bytes_per_sec(), Some(100.0));
+        assert_eq!(estimator.eta(350), Some(at(3.5)));
+    }
+
+    /// Writer appending to a buffer the test keeps a handle on
+    #[derive(Clone, Default)]
+    struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_progress_lines() {
+        let buffer = SharedBuffer::default();
+        let mut progress = ByteProgress::json(300, buffer.clone(), Duration::ZERO);
+        progress.advance(100, 2);
+        progress.advance(150, 0);
+        progress.advance(50, 5);
+        progress.finish();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> =
+            output.lines().map(|line| serde_json::from_str(line).expect("valid JSON")).collect();
+        // The final counts were already written, so finishing adds no line
+        let done: Vec<u64> = lines.iter().map(|line| line["bytes_done"].as_u64().unwrap()).collect();
+        assert_eq!(done, [100, 250, 300]);
+        assert!(lines.iter().all(|line| line["bytes_total"] == 300));
+        assert_eq!(lines[2]["strings"], 7);
+
+        // Throttled: only the first update and the final line within the interval
+        let buffer = SharedBuffer::default();
+        let mut progress = ByteProgress::json(300, buffer.clone(), Duration::from_secs(3600));
+        progress.advance(100, 1);
+        progress.advance(200, 1);
+        progress.finish();
+        assert_eq!(buffer.0.lock().unwrap().iter().filter(|&&b| b == b'\n').count(), 2);
+    }
+
+    #[test]
+    fn test_format_eta() {
+        assert_eq!(format_eta(at(0.4)), "00:00:00");
+        assert_eq!(format_eta(at(3725.0)), "01:02:05");
+    }
+}
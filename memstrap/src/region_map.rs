@@ -0,0 +1,125 @@
+//! Offset-to-region lookup for `--region-map`
+
+use crate::config::parse_address;
+use crate::error::{MemstrapError, Result};
+use crate::extractor::FoundString;
+use std::io::Read;
+use std::path::Path;
+
+/// A labelled byte range of a memory image, `start` inclusive and `end` exclusive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedRegion {
+    pub start: u64,
+    pub end: u64,
+    pub label: String,
+}
+
+/// Known layout of a memory image, such as a region map exported by MemProcFS
+///
+/// Regions are kept sorted by start and never overlap, so the region holding
+/// an offset is found with a binary search.
+#[derive(Debug, Default)]
+pub struct RegionMap {
+    regions: Vec<MappedRegion>,
+}
+
+impl RegionMap {
+    /// Load a CSV of `start,end,label` rows
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_reader(std::fs::File::open(path)?)
+    }
+
+    /// Read `start,end,label` rows, with `start` and `end` in decimal or 0x-prefixed hex
+    ///
+    /// A leading `start,end,label` header and lines starting with `#` are
+    /// skipped. Fails on malformed rows, on regions that end before they start
+    /// and on overlapping regions.
+    pub fn from_reader(reader: impl Read) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .comment(Some(b'#'))
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+
+        let mut regions = Vec::new();
+        for (index, record) in reader.records().enumerate() {
+            let record = record?;
+            let line = record.position().map_or(index as u64 + 1, |position| position.line());
+            let invalid = |reason: String| MemstrapError::RegionMap(format!("line {}: {}", line, reason));
+            if index == 0 && record.get(0).is_some_and(|field| field.eq_ignore_ascii_case("start")) {
+                continue;
+            }
+            if record.len() != 3 {
+                return Err(invalid(format!("expected start,end,label but found {} fields", record.len())));
+            }
+            let (start, end) = (parse_address(&record[0]).map_err(invalid)?, parse_address(&record[1]).map_err(invalid)?);
+            if end < start {
+                return Err(invalid(format!("region ends (0x{:X}) before it starts (0x{:X})", end, start)));
+            }
+            regions.push(MappedRegion { start, end, label: record[2].to_string() });
+        }
+
+        regions.sort_by_key(|region| region.start);
+        if let Some(pair) = regions.windows(2).find(|pair| pair[1].start < pair[0].end) {
+            return Err(MemstrapError::RegionMap(format!("regions {:?} and {:?} overlap", pair[0].label, pair[1].label)));
+        }
+        Ok(RegionMap { regions })
+    }
+
+    /// Number of regions
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Whether the map has no regions
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Label of the region holding `offset`, if any
+    pub fn label_at(&self, offset: u64) -> Option<&str> {
+        let after = self.regions.partition_point(|region| region.start <= offset);
+        let region = self.regions[..after].last()?;
+        (offset < region.end).then_some(region.label.as_str())
+    }
+
+    /// Record on each result the region its offset falls in
+    ///
+    /// Offsets are taken relative to `base` within the image, such as the
+    /// start of a partition, and must not yet be rebased with `--base-offset`.
+    pub fn annotate(&self, results: &mut [FoundString], base: u64) {
+        for found_string in results {
+            found_string.region = self.label_at(base + found_string.offset.get()).map(str::to_string);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_at() {
+        let map = RegionMap::from_reader(
+            "start,end,label\n# heap first\n0x2000,0x3000,heap\n4096, 0x1800, ntdll.dll\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.label_at(0xFFF), None);
+        assert_eq!(map.label_at(0x1000), Some("ntdll.dll"));
+        assert_eq!(map.label_at(0x17FF), Some("ntdll.dll"));
+        assert_eq!(map.label_at(0x1800), None);
+        assert_eq!(map.label_at(0x2000), Some("heap"));
+        assert_eq!(map.label_at(0x3000), None);
+    }
+
+    #[test]
+    fn test_rejects_malformed_maps() {
+        let error = |text: &str| RegionMap::from_reader(text.as_bytes()).unwrap_err().to_string();
+        assert_eq!(error("0x10,0x20\n"), "Region map error: line 1: expected start,end,label but found 2 fields");
+        assert!(error("0x10,0x20,a\nten,0x40,b\n").starts_with("Region map error: line 2: invalid address \"ten\""));
+        assert!(error("0x20,0x10,a\n").contains("ends (0x10) before it starts (0x20)"));
+        assert!(error("0x10,0x30,a\n0x20,0x40,b\n").contains("\"a\" and \"b\" overlap"));
+    }
+}
@@ -0,0 +1,1287 @@
+use crate::baseline::Baseline;
+use crate::compress::{compressed_path, CompressedWriter};
+use crate::config::{is_special_file, is_stdin, Config, EncodingSelection, OutputFormat};
+use crate::detect::{self, DETECT_SAMPLE_SIZE};
+use crate::error::{MemstrapError, Result};
+use crate::extractor::{Encoding, FoundString, StringExtractor};
+use crate::hash::{self, HashingReader};
+use crate::image::ScannedImage;
+use crate::output::{self, CsvStreamWriter, FileResults, OutputOptions};
+use crate::partition::{self, Partition};
+use crate::plan::{is_sampled, ScanPlan};
+use crate::progress::{ByteProgress, JSON_PROGRESS_INTERVAL};
+use crate::region_map::RegionMap;
+#[cfg(feature = "binary-parse")]
+use crate::sections::{self, Section};
+use crate::postprocess::{correlate_wide, dedup_results, detect_tables, keep_first_per_bucket, merge_adjacent, merge_cross_encoding, rebase_offsets, StringTable};
+use crate::stream::{self, Checkpoint};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::{ControlFlow, Range};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Print an informational line unless `--quiet` was given
+macro_rules! info {
+    ($config:expr, $($arg:tt)*) => {
+        if !$config.quiet {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Metrics collected over a single scan
+#[derive(Debug, Clone, Default)]
+pub struct RunSummary {
+    /// Wall-clock time spent in the parallel extraction, in milliseconds
+    pub elapsed_ms: u64,
+    /// Number of input bytes scanned
+    pub bytes_scanned: u64,
+    /// Number of strings reported after deduplication
+    pub strings_found: usize,
+    /// Number of overlap duplicates that were dropped
+    pub duplicates_removed: usize,
+    /// Number of fragments joined onto a neighbouring string (`--merge-adjacent`)
+    pub fragments_merged: usize,
+    /// Number of cross-encoding near-duplicates merged away (`--encoding-priority`)
+    pub encodings_merged: usize,
+    /// Number of ASCII/UTF-16 pairs joined by `--correlate-wide`
+    pub wide_pairs: usize,
+    /// Number of strings dropped because they appear in the baseline
+    pub baseline_suppressed: usize,
+    /// Number of strings dropped as not the first in their `--bucket`
+    pub bucketed: usize,
+    /// Hex-encoded SHA-256 of the input (`--emit-hash`, per-input summaries only)
+    pub sha256: Option<String>,
+    /// String tables found by `--detect-tables` (per-input summaries only)
+    pub tables: Vec<StringTable>,
+    /// Per-input summaries, in scan order (empty on the per-input summaries themselves)
+    pub per_file: Vec<(PathBuf, RunSummary)>,
+    /// Whether the scan was stopped early, so the output holds partial results
+    pub interrupted: bool,
+}
+
+impl RunSummary {
+    /// Extraction throughput in MB/s
+    pub fn throughput_mbps(&self) -> f64 {
+        let secs = self.elapsed_ms as f64 / 1000.0;
+        if secs > 0.0 {
+            self.bytes_scanned as f64 / 1024.0 / 1024.0 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Fold the summary of one scanned input into these totals
+    pub fn add(&mut self, path: &Path, file: RunSummary) {
+        self.elapsed_ms += file.elapsed_ms;
+        self.bytes_scanned += file.bytes_scanned;
+        self.strings_found += file.strings_found;
+        self.duplicates_removed += file.duplicates_removed;
+        self.fragments_merged += file.fragments_merged;
+        self.encodings_merged += file.encodings_merged;
+        self.wide_pairs += file.wide_pairs;
+        self.baseline_suppressed += file.baseline_suppressed;
+        self.bucketed += file.bucketed;
+        self.interrupted |= file.interrupted;
+        self.per_file.push((path.to_path_buf(), file));
+    }
+
+    /// Single machine-readable line for `--timing`
+    pub fn timing_line(&self) -> String {
+        format!(
+            "elapsed_ms={} bytes={} strings={} throughput_mbps={:.2}",
+            self.elapsed_ms,
+            self.bytes_scanned,
+            self.strings_found,
+            self.throughput_mbps()
+        )
+    }
+}
+
+/// Count strings long enough (at least 3/4 of the overlap) to risk being split at a chunk boundary
+///
+/// Without any overlap every string is at risk, so none are counted; the
+/// caller warns about `--overlap 0` itself.
+pub fn count_overlap_risks(results: &[FoundString], overlap_size: usize) -> usize {
+    if overlap_size == 0 {
+        return 0;
+    }
+    let threshold = overlap_size - overlap_size / 4;
+    results.iter().filter(|s| s.byte_length >= threshold).count()
+}
+
+/// Apply `--merge-adjacent` joining, if requested
+fn merge_fragments(config: &Config, results: &mut Vec<FoundString>) -> usize {
+    config.merge_adjacent.map_or(0, |gap| merge_adjacent(results, gap))
+}
+
+/// Apply `--encoding-priority` merging, if requested
+fn merge_encodings(config: &Config, results: &mut Vec<FoundString>) -> usize {
+    if config.encoding_priority.is_empty() {
+        return 0;
+    }
+    let priority: Vec<Encoding> = config.encoding_priority.iter().map(|&e| Encoding::from(e)).collect();
+    merge_cross_encoding(results, &priority)
+}
+
+/// Apply `--correlate-wide` pairing, if requested
+fn pair_wide(config: &Config, results: &mut Vec<FoundString>) -> usize {
+    if config.correlate_wide {
+        correlate_wide(results, config.correlate_distance)
+    } else {
+        0
+    }
+}
+
+/// Byte-based progress over `total_bytes`, or `None` when progress output is disabled
+///
+/// `--progress-json` reports to stderr even with `--quiet`, as it is meant for
+/// programs rather than people.
+fn byte_progress(config: &Config, total_bytes: u64) -> Option<ByteProgress> {
+    if config.progress_json {
+        Some(ByteProgress::json(total_bytes, io::stderr(), JSON_PROGRESS_INTERVAL))
+    } else if config.no_progress || config.quiet {
+        None
+    } else {
+        Some(ByteProgress::new(total_bytes))
+    }
+}
+
+/// Build the string extractor configured by the extraction options of `config`
+pub fn build_extractor(config: &Config) -> Result<StringExtractor> {
+    let (context_before, context_after) = config.context_sizes();
+    Ok(StringExtractor::new(
+        config.effective_min_len(),
+        config.get_encodings(),
+        config.search.clone(),
+        config.regex,
+        config.context_bytes,
+    )?
+    .with_context(context_before, context_after)
+    .with_context_trim(config.context_trim)
+    .with_raw_bytes(config.emit_raw_hex)
+    .with_gaps(config.emit_gaps)
+    .with_whitespace(!config.exclude_tab, config.allow_newline)
+    .with_del_printable(config.treat_del_as_printable)
+    .with_min_words(config.min_words)
+    .with_min_unique_chars(config.min_unique_chars)
+    .with_min_alnum_ratio(config.min_alnum_ratio)
+    .with_paths_only(config.paths_only)
+    .with_min_confidence(config.min_confidence)
+    .with_drop_whitespace(config.drop_whitespace)
+    .with_follow_utf8_continuation(config.follow_utf8_continuation)
+    .with_adaptive(config.adaptive)
+    .with_embedded_ascii(config.embedded_ascii)
+    .with_replacement_char(config.replacement_char())
+    .with_charset(config.get_charset())
+    .with_scripts(config.scripts.clone())
+    .with_invert_match(config.invert_match)
+    .with_capture(config.capture)?
+    .with_min_len_overrides(config.min_len_overrides())
+    .with_min_len_unit(config.min_len_unit)
+    .with_gbk_max_len(config.gbk_max_len)?
+    .with_codepage(config.codepage.as_deref())?
+    .with_signature(config.signature.clone())
+    .with_search_bytes(config.search_hex.clone()))
+}
+
+/// Scan the configured files and write results to the configured output
+///
+/// The returned summary holds the combined totals; per-input figures are in
+/// `RunSummary::per_file`.
+pub fn run(config: &Config) -> Result<RunSummary> {
+    run_until(config, &|| false)
+}
+
+/// Like [`run`], but stop early once `should_stop` returns true
+///
+/// `should_stop` is polled before each chunk and after each streaming window,
+/// typically reading a flag set by a Ctrl-C handler. Chunks and windows that
+/// already finished are still written to the output, and the summary is
+/// marked `interrupted`.
+pub fn run_until(config: &Config, should_stop: &(dyn Fn() -> bool + Sync)) -> Result<RunSummary> {
+    #[cfg(feature = "server")]
+    if let Some(addr) = &config.serve {
+        crate::server::serve(addr)?;
+        return Ok(RunSummary::default());
+    }
+
+    config.validate()?;
+    config.check_encodings()?;
+    config.check_inputs()?;
+    if let Some(warning) = config.tiny_min_len_warning() {
+        eprintln!("Warning: {}", warning);
+    }
+    if config.no_dedup {
+        eprintln!("Warning: deduplication is disabled; strings in the overlap between chunks may appear twice");
+    }
+    let config = auto_encodings(config)?;
+    let config: &Config = &config;
+
+    let baseline = match &config.baseline {
+        Some(path) => {
+            let baseline = Baseline::load(path)?;
+            info!(config, "Baseline: {} known strings from {}", baseline.len(), path.display());
+            Some(baseline)
+        }
+        None => None,
+    };
+    let region_map = match &config.region_map {
+        Some(path) => {
+            let region_map = RegionMap::load(path)?;
+            info!(config, "Region map: {} regions from {}", region_map.len(), path.display());
+            Some(region_map)
+        }
+        None => None,
+    };
+
+    let extractor = build_extractor(config)?;
+
+    if config.dry_run {
+        return dry_run(config);
+    }
+    if config.count_only {
+        return count_only(config, &extractor);
+    }
+
+    if config.streaming() || over_memory_budget(config)? {
+        return run_streaming(config, &extractor, baseline.as_ref(), should_stop);
+    }
+
+    let mut summary = RunSummary::default();
+    let mut scanned = Vec::with_capacity(config.file_path.len());
+    for path in &config.file_path {
+        let (results, file_summary) = scan_file(config, path, &extractor, baseline.as_ref(), region_map.as_ref(), should_stop)?;
+        summary.add(path, file_summary);
+        scanned.push((path.as_path(), results));
+        if summary.interrupted {
+            break;
+        }
+    }
+    print_totals(config, &summary);
+    warn_if_interrupted(&summary);
+
+    // Output results (the interactive browser replaces stdout output)
+    #[cfg(feature = "tui")]
+    let browsing = config.tui;
+    #[cfg(not(feature = "tui"))]
+    let browsing = false;
+
+    let output_options = config.output_options();
+    let files: Vec<FileResults> = scanned
+        .iter()
+        .zip(&summary.per_file)
+        .map(|((path, results), (_, file_summary))| FileResults {
+            path,
+            results,
+            sha256: file_summary.sha256.as_deref(),
+        })
+        .collect();
+    if let Some(output_path) = &config.output {
+        for &format in &config.format {
+            let output_path = format_output_path(config, output_path, format);
+            if config.split_by_encoding {
+                write_split_by_encoding(config, format, &output_path, &files, &output_options)?;
+            } else {
+                let path = write_output_file(config, format, &output_path, &files, &output_options)?;
+                info!(config, "  Results written to: {}", path.display());
+            }
+        }
+    } else if !browsing {
+        // Several formats require --output, so stdout gets the only one
+        output::write_files(config.format[0], io::stdout().lock(), &files, &output_options)?;
+    }
+
+    #[cfg(feature = "tui")]
+    if config.tui {
+        crate::tui::browse(scanned.into_iter().flat_map(|(_, results)| results).collect())?;
+    }
+
+    Ok(summary)
+}
+
+/// Write one output per encoding with `--split-by-encoding`, each holding only that encoding's strings
+///
+/// Every selected encoding gets a file, with its header even when it found
+/// nothing; other encodings in the results (signature matches) get one too.
+fn write_split_by_encoding(
+    config: &Config,
+    format: OutputFormat,
+    output_path: &Path,
+    files: &[FileResults],
+    options: &OutputOptions,
+) -> Result<()> {
+    let mut encodings: Vec<Encoding> = config.get_encodings().into_iter().map(Encoding::from).collect();
+    for found_string in files.iter().flat_map(|file| file.results) {
+        if !encodings.contains(&found_string.encoding) {
+            encodings.push(found_string.encoding);
+        }
+    }
+
+    for encoding in encodings {
+        let split: Vec<Vec<FoundString>> = files
+            .iter()
+            .map(|file| file.results.iter().filter(|s| s.encoding == encoding).cloned().collect())
+            .collect();
+        let split_files: Vec<FileResults> = files
+            .iter()
+            .zip(&split)
+            .map(|(file, results)| FileResults { results, ..*file })
+            .collect();
+        let path = write_output_file(config, format, &split_output_path(output_path, encoding), &split_files, options)?;
+        info!(config, "  {} results written to: {}", encoding, path.display());
+    }
+    Ok(())
+}
+
+/// Write `files` as `format` to `path`, compressed and renamed as `--compress` asks, returning the path written
+fn write_output_file(
+    config: &Config,
+    format: OutputFormat,
+    path: &Path,
+    files: &[FileResults],
+    options: &OutputOptions,
+) -> Result<PathBuf> {
+    let path = compressed_path(path, config.compress);
+    let mut writer = CompressedWriter::new(File::create(&path)?, config.compress)?;
+    output::write_files(format, &mut writer, files, options)?;
+    writer.finish()?;
+    Ok(path)
+}
+
+/// Where `format` is written: `--output` itself for a single format, otherwise
+/// `--output` with the format's extension, e.g. `out.csv` and `out.xml`
+fn format_output_path(config: &Config, output_path: &Path, format: OutputFormat) -> PathBuf {
+    if config.format.len() > 1 {
+        output_path.with_extension(format.extension())
+    } else {
+        output_path.to_path_buf()
+    }
+}
+
+/// `out.csv` with the encoding's tag before the extension, e.g. `out.utf16le.csv`
+fn split_output_path(output_path: &Path, encoding: Encoding) -> PathBuf {
+    let mut name = output_path.file_stem().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(encoding.as_str());
+    if let Some(extension) = output_path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    output_path.with_file_name(name)
+}
+
+/// Warn that an interrupted scan's output is partial
+fn warn_if_interrupted(summary: &RunSummary) {
+    if summary.interrupted {
+        eprintln!("Warning: scan interrupted; the output holds partial results");
+    }
+}
+
+/// Reason streaming mode cannot be used with the selected options, if any
+fn streaming_unsupported(config: &Config) -> Option<&'static str> {
+    if config.format != [OutputFormat::Csv] {
+        return Some("streaming mode only supports CSV output");
+    }
+    if config.group_by.is_some() {
+        return Some("--group-by cannot be combined with streaming mode");
+    }
+    if config.consolidate {
+        return Some("--consolidate cannot be combined with streaming mode");
+    }
+    if config.partition_table {
+        return Some("--partition-table cannot be combined with streaming mode");
+    }
+    if config.scans_sections() {
+        return Some("--sections cannot be combined with streaming mode");
+    }
+    if config.split_by_encoding {
+        return Some("--split-by-encoding cannot be combined with streaming mode");
+    }
+    if !config.reverse.is_empty() {
+        return Some("--reverse cannot be combined with streaming mode");
+    }
+    if config.bucket.is_some() {
+        return Some("--bucket cannot be combined with streaming mode");
+    }
+    if config.detect_tables {
+        return Some("--detect-tables cannot be combined with streaming mode");
+    }
+    if config.no_dedup {
+        return Some("--no-dedup cannot be combined with streaming mode, which never reports a string twice");
+    }
+    if config.region_map.is_some() {
+        return Some("--region-map cannot be combined with streaming mode");
+    }
+    #[cfg(feature = "tui")]
+    if config.tui {
+        return Some("--tui cannot be combined with streaming mode");
+    }
+    None
+}
+
+/// Plan the scan of one input, switching to streaming when it exceeds the memory budget
+fn plan_input(config: &Config, file_size: u64) -> Result<ScanPlan> {
+    let memory_budget = streaming_unsupported(config).is_none().then(|| config.memory_budget());
+    ScanPlan::choose(
+        file_size,
+        config.streaming(),
+        memory_budget,
+        config.threads,
+        config.get_threads(),
+        config.window_size,
+        config.overlap,
+    )
+}
+
+/// Plan a parallel scan; an explicit `--threads` count is not capped for large inputs
+fn parallel_plan(config: &Config, file_size: usize) -> Result<ScanPlan> {
+    ScanPlan::with_threads(file_size, config.threads, config.get_threads(), config.overlap)
+}
+
+/// Whether an input is larger than the memory budget, so the whole run should stream
+///
+/// Prints a note when switching, or a warning when the selected options rule
+/// streaming out and the input is scanned in memory regardless.
+fn over_memory_budget(config: &Config) -> Result<bool> {
+    if config.file_path.iter().any(|path| is_stdin(path)) {
+        return Ok(false);
+    }
+
+    let budget = config.memory_budget();
+    let mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+    for path in &config.file_path {
+        let file_size = std::fs::metadata(path)?.len();
+        // The planner's own decision, as if the selected options allowed streaming
+        let plan = ScanPlan::choose(file_size, false, Some(budget), config.threads, config.get_threads(), config.window_size, config.overlap)?;
+        if !plan.streaming {
+            continue;
+        }
+        match streaming_unsupported(config) {
+            Some(reason) => eprintln!(
+                "Warning: {} ({:.2} MB) exceeds the {:.2} MB memory budget, but {}; scanning it in memory",
+                path.display(), mb(file_size), mb(budget), reason
+            ),
+            None => {
+                info!(
+                    config,
+                    "Note: {} ({:.2} MB) exceeds the {:.2} MB memory budget; switching to streaming mode",
+                    path.display(), mb(file_size), mb(budget)
+                );
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// With `--auto-encoding`, a copy of `config` selecting the encodings detected in the inputs
+///
+/// Each input's first `DETECT_SAMPLE_SIZE` bytes are sampled and the detected
+/// encodings of all inputs are combined.
+fn auto_encodings(config: &Config) -> Result<Cow<'_, Config>> {
+    if !config.auto_encoding {
+        return Ok(Cow::Borrowed(config));
+    }
+
+    let mut encodings = Vec::new();
+    for path in &config.file_path {
+        let mut sample = Vec::new();
+        File::open(path)?.take(DETECT_SAMPLE_SIZE as u64).read_to_end(&mut sample)?;
+        let detected = detect::detect_encodings(&sample);
+        info!(config, "Auto-detected encodings for {}: {:?}", path.display(), detected);
+        for encoding in detected {
+            if !encodings.contains(&encoding) {
+                encodings.push(encoding);
+            }
+        }
+    }
+
+    let mut config = config.clone();
+    config.encodings = vec![EncodingSelection::Only(encodings)];
+    Ok(Cow::Owned(config))
+}
+
+/// Print the scan plan for each input without scanning anything
+fn dry_run(config: &Config) -> Result<RunSummary> {
+    let encodings = config.get_encodings();
+    for path in &config.file_path {
+        println!("Scan plan for: {}", path.display());
+        if is_stdin(path) {
+            println!("  File size: unknown (stdin is read into memory before scanning)");
+            println!("  Encodings: {:?}", encodings);
+            continue;
+        }
+        if is_special_file(path) {
+            println!("  File size: unknown (FIFOs and devices are read into memory before scanning)");
+            println!("  Encodings: {:?}", encodings);
+            continue;
+        }
+
+        let file_size = std::fs::metadata(path)?.len();
+        let plan = plan_input(config, file_size)?;
+        let mode = match (plan.streaming, config.streaming()) {
+            (true, true) => "streaming",
+            (true, false) => "streaming (over the memory budget)",
+            (false, _) if config.no_mmap => "parallel, buffered",
+            (false, _) => "parallel, memory-mapped",
+        };
+        let mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+
+        println!("  File size: {} bytes ({:.2} MB)", plan.file_size, mb(plan.file_size));
+        println!("  Mode: {}", mode);
+        println!("  Threads: {}", plan.num_threads);
+        println!(
+            "  {} size: {:.2} MB ({} {})",
+            if plan.streaming { "Window" } else { "Chunk" },
+            mb(plan.chunk_size as u64),
+            plan.chunk_count,
+            if plan.streaming { "windows" } else { "chunks" }
+        );
+        println!("  Overlap: {} bytes", plan.overlap);
+        println!("  Encodings: {:?}", encodings);
+        println!(
+            "  Estimated peak memory: {:.2} MB",
+            mb(plan.estimated_peak_memory(config.no_mmap, encodings.len()))
+        );
+    }
+    Ok(RunSummary::default())
+}
+
+/// Print how many strings each input holds, per encoding, without building any results (`--count-only`)
+///
+/// Each scan region is counted in parallel over the chunks `scan_file` would
+/// use; a chunk counts only the strings starting before the next chunk, so the
+/// overlap is not counted twice. The post-processing options (merging,
+/// baseline, buckets) do not apply.
+fn count_only(config: &Config, extractor: &StringExtractor) -> Result<RunSummary> {
+    let mut summary = RunSummary::default();
+    for path in &config.file_path {
+        let started = Instant::now();
+        let image = ScannedImage::load(path, config.no_mmap)?;
+        let mut file_summary = RunSummary::default();
+        let mut counts: Vec<(Encoding, usize)> = Vec::new();
+        for (_, range) in scan_regions(config, image.data())? {
+            file_summary.bytes_scanned += range.len() as u64;
+            let region_data = &image.data()[range.clone()];
+            let chunks = parallel_plan(config, range.len())?.chunks();
+            let chunk_counts: Vec<Vec<(Encoding, usize)>> = chunks
+                .par_iter()
+                .enumerate()
+                .map(|(i, &(start, end, _))| {
+                    // The chunk owns everything up to where the next one starts
+                    let owned_end = chunks.get(i + 1).map_or(end, |next| next.0);
+                    extractor.count_strings_starting_before(&region_data[start..end], owned_end - start)
+                })
+                .collect();
+            for (encoding, count) in chunk_counts.into_iter().flatten() {
+                match counts.iter_mut().find(|(e, _)| *e == encoding) {
+                    Some((_, total)) => *total += count,
+                    None => counts.push((encoding, count)),
+                }
+            }
+        }
+        // Regions list only the encodings they hold, so restore ALL order (code page last)
+        counts.sort_by_key(|&(encoding, _)| Encoding::ALL.iter().position(|&e| e == encoding).unwrap_or(Encoding::ALL.len()));
+        file_summary.strings_found = counts.iter().map(|(_, count)| count).sum();
+        file_summary.elapsed_ms = started.elapsed().as_millis() as u64;
+
+        println!("Counts for: {}", path.display());
+        for (encoding, count) in counts {
+            println!("  {}: {}", encoding, count);
+        }
+        println!("  Total: {}", file_summary.strings_found);
+        summary.add(path, file_summary);
+    }
+    Ok(summary)
+}
+
+/// Extract, deduplicate and filter the strings of one input
+fn scan_file(
+    config: &Config,
+    path: &Path,
+    extractor: &StringExtractor,
+    baseline: Option<&Baseline>,
+    region_map: Option<&RegionMap>,
+    should_stop: &(dyn Fn() -> bool + Sync),
+) -> Result<(Vec<FoundString>, RunSummary)> {
+    info!(config, "Processing file: {}", path.display());
+    if is_special_file(path) {
+        info!(config, "Note: {} is a FIFO or device; reading it into memory", path.display());
+    }
+
+    // Memory-map the file, or read it into a buffer when mapping is unavailable
+    let image = ScannedImage::load(path, config.no_mmap)?;
+    scan_image(config, &image, extractor, baseline, region_map, should_stop)
+}
+
+/// Extract, deduplicate and filter the strings of one loaded input
+///
+/// The pipeline behind both [`scan_file`] and [`ScannedImage::search`].
+pub(crate) fn scan_image(
+    config: &Config,
+    image: &ScannedImage,
+    extractor: &StringExtractor,
+    baseline: Option<&Baseline>,
+    region_map: Option<&RegionMap>,
+    should_stop: &(dyn Fn() -> bool + Sync),
+) -> Result<(Vec<FoundString>, RunSummary)> {
+    let (path, data) = (image.path(), image.data());
+    info!(config, "File size: {} bytes ({:.2} MB)", data.len(), data.len() as f64 / 1024.0 / 1024.0);
+    let sha256 = config.emit_hash.then(|| hash::sha256_hex(data));
+    if data.is_empty() {
+        info!(config, "File is empty, nothing to scan");
+        let summary = RunSummary { sha256, ..Default::default() };
+        print_results(config, "Results", &summary, baseline.is_some());
+        return Ok((Vec::new(), summary));
+    }
+    if !config.reverse.is_empty() {
+        return reverse_scan(config, data, extractor, baseline, region_map, sha256);
+    }
+
+    // Calculate chunks for parallel processing
+    let regions = scan_regions(config, data)?;
+    let plans = regions
+        .iter()
+        .map(|(_, range)| parallel_plan(config, range.len()))
+        .collect::<Result<Vec<_>>>()?;
+
+    match (regions.as_slice(), plans.as_slice()) {
+        ([(Region::Input, _)], [plan]) => {
+            info!(config, "Using {} threads", plan.num_threads);
+            info!(config, "Chunk size: {:.2} MB", plan.chunk_size as f64 / 1024.0 / 1024.0);
+        }
+        _ => {
+            for ((region, range), plan) in regions.iter().zip(&plans) {
+                info!(
+                    config,
+                    "{}: 0x{:X}-0x{:X} ({:.2} MB, {} threads)",
+                    region, range.start, range.end,
+                    range.len() as f64 / 1024.0 / 1024.0, plan.num_threads
+                );
+            }
+        }
+    }
+    info!(config, "Minimum string length: {}", config.effective_min_len());
+    if let Some(ref pattern) = config.search {
+        info!(
+            config,
+            "Search pattern: {} ({}{})",
+            pattern,
+            if config.regex { "regex" } else { "plain text" },
+            if config.invert_match { ", inverted" } else { "" }
+        );
+    }
+    if let Some(ref needle) = config.search_hex {
+        info!(config, "Search bytes: {}", hex::encode(needle));
+    }
+    info!(config, "Encodings: {:?}", config.get_encodings());
+
+    // Create chunks with overlap
+    let mut chunks: Vec<Vec<(usize, usize, u64)>> = plans.iter().map(ScanPlan::chunks).collect();
+
+    // Keep an evenly spread subset of smaller blocks, numbered across all regions
+    if let Some(rate) = config.sample_rate() {
+        chunks = plans.iter().map(ScanPlan::sample_blocks).collect();
+        let total: usize = chunks.iter().map(Vec::len).sum();
+        let mut index = 0;
+        for region_chunks in &mut chunks {
+            region_chunks.retain(|_| {
+                index += 1;
+                is_sampled(index - 1, rate)
+            });
+        }
+        let kept: usize = chunks.iter().map(Vec::len).sum();
+        info!(config, "Sampling {} of {} blocks ({:.0}%)", kept, total, rate * 100.0);
+        if kept < total {
+            eprintln!("Note: sampled scan; results cover only part of {}", path.display());
+        }
+    }
+
+    // Create progress bar, measured in bytes scanned (overlap included)
+    let scan_bytes = chunks.iter().flatten().map(|(start, end, _)| (end - start) as u64).sum();
+    let progress = byte_progress(config, scan_bytes).map(Mutex::new);
+
+    let interrupted = AtomicBool::new(false);
+    let mut elapsed = Duration::ZERO;
+    let bytes_scanned = match config.sample_rate() {
+        Some(_) => chunks.iter().flatten().map(|(start, end, _)| (end - start) as u64).sum(),
+        None => regions.iter().map(|(_, range)| range.len() as u64).sum(),
+    };
+    let mut summary = RunSummary { bytes_scanned, sha256, ..Default::default() };
+    let mut unique_results = Vec::new();
+    let mut at_risk = 0;
+
+    // Each region is scanned and deduplicated on its own, as partition offsets are relative to its start
+    for ((region, range), region_chunks) in regions.iter().zip(&chunks) {
+        let region_data = &data[range.clone()];
+        // Partition offsets are relative to the partition, others to the input
+        let region_base = if matches!(region, Region::Partition(_)) { 0 } else { range.start as u64 };
+
+        // Process chunks in parallel
+        let started = Instant::now();
+        let mut results: Vec<FoundString> = region_chunks
+            .par_iter()
+            .flat_map(|(start, end, _)| {
+                if should_stop() {
+                    interrupted.store(true, Ordering::Relaxed);
+                    return Vec::new();
+                }
+                // Context may reach into the neighbouring chunks of the region
+                let chunk_results = extractor.extract_strings_in(region_data, *start..*end, region_base);
+
+                if let Some(progress) = &progress {
+                    progress.lock().unwrap().advance((end - start) as u64, chunk_results.len());
+                }
+
+                chunk_results
+            })
+            .collect();
+        elapsed += started.elapsed();
+
+        // Remove duplicates (can happen due to overlap)
+        summary.duplicates_removed += dedup_results(&mut results, config.dedup_mode());
+        summary.fragments_merged += merge_fragments(config, &mut results);
+        summary.encodings_merged += merge_encodings(config, &mut results);
+        summary.wide_pairs += pair_wide(config, &mut results);
+        if region_chunks.len() > 1 {
+            at_risk += count_overlap_risks(&results, config.overlap);
+        }
+        // The map describes the whole image, so look partition offsets up from the partition start
+        if let Some(region_map) = region_map {
+            region_map.annotate(&mut results, range.start as u64 - region_base);
+        }
+        region.tag(&mut results);
+        unique_results.append(&mut results);
+    }
+
+    if let Some(progress) = &progress {
+        progress.lock().unwrap().finish();
+    }
+
+    if config.overlap == 0 && chunks.iter().any(|region_chunks| region_chunks.len() > 1) {
+        eprintln!("Warning: --overlap 0 leaves no overlap between chunks; strings crossing a chunk boundary are split");
+    } else if at_risk > 0 {
+        eprintln!(
+            "Warning: {} strings are close to the {} byte chunk overlap and may be split at chunk boundaries; consider a larger --overlap",
+            at_risk, config.overlap
+        );
+    }
+
+    summary.baseline_suppressed = baseline
+        .map(|baseline| baseline.suppress(&mut unique_results))
+        .unwrap_or(0);
+    rebase_offsets(&mut unique_results, config.base_offset)?;
+    if config.detect_tables {
+        summary.tables = detect_tables(&unique_results, config.table_gap);
+    }
+    if let Some(bucket) = config.bucket {
+        summary.bucketed = keep_first_per_bucket(&mut unique_results, bucket);
+    }
+
+    summary.elapsed_ms = elapsed.as_millis() as u64;
+    summary.strings_found = unique_results.len();
+    summary.interrupted = interrupted.into_inner();
+    print_results(config, "Results", &summary, baseline.is_some());
+    if config.detect_tables {
+        print_tables(config, &summary.tables);
+    }
+
+    Ok((unique_results, summary))
+}
+
+/// What a scan region of an input covers
+enum Region {
+    /// The whole input, after any `--skip-header` bytes
+    Input,
+    /// A partition of a disk image (`--partition-table`); offsets are relative to its start
+    Partition(Partition),
+    /// A section of an executable (`--sections`); offsets stay absolute
+    #[cfg(feature = "binary-parse")]
+    Section(Section),
+}
+
+impl Region {
+    /// Record on the results of this region where they lie
+    fn tag(&self, results: &mut [FoundString]) {
+        match self {
+            Region::Input => {}
+            Region::Partition(partition) => {
+                for found_string in results {
+                    found_string.partition = Some(partition.number);
+                }
+            }
+            #[cfg(feature = "binary-parse")]
+            Region::Section(section) => {
+                for found_string in results {
+                    found_string.section = Some(section.name.clone());
+                    found_string.section_offset = Some(crate::offset::Offset(found_string.offset.get() - section.start));
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Region::Input => write!(f, "Input"),
+            Region::Partition(partition) => write!(f, "Partition {}", partition.number),
+            #[cfg(feature = "binary-parse")]
+            Region::Section(section) => write!(f, "Section {}", section.name),
+        }
+    }
+}
+
+/// Byte ranges of `data` to scan, each with what it covers
+///
+/// With `--partition-table` these are the partitions listed in the image's
+/// MBR or GPT (clipped to the image, empty ones skipped), and with
+/// `--sections` the selected sections of an executable; otherwise the whole
+/// input after any `--skip-header` bytes is a single region.
+fn scan_regions(config: &Config, data: &[u8]) -> Result<Vec<(Region, Range<usize>)>> {
+    #[cfg(feature = "binary-parse")]
+    if let Some(regions) = section_regions(config, data) {
+        return Ok(regions);
+    }
+    if !config.partition_table {
+        let start = usize::try_from(config.skip_header).unwrap_or(usize::MAX).min(data.len());
+        return Ok(vec![(Region::Input, start..data.len())]);
+    }
+
+    let partitions = partition::read_partitions(data)?;
+    info!(config, "Partition table: {} partitions", partitions.len());
+    Ok(partitions
+        .into_iter()
+        .map(|partition| (partition, partition.range(data.len())))
+        .filter(|(partition, range)| {
+            if range.is_empty() {
+                eprintln!("Warning: partition {} lies beyond the end of the image; skipping it", partition.number);
+            }
+            !range.is_empty()
+        })
+        .map(|(partition, range)| (Region::Partition(partition), range))
+        .collect())
+}
+
+/// Regions for the `--sections` of an executable, or `None` to scan the input whole
+///
+/// Inputs that are not a parseable ELF or PE file, or that have none of the
+/// selected sections, fall back to a flat scan with a warning.
+#[cfg(feature = "binary-parse")]
+fn section_regions(config: &Config, data: &[u8]) -> Option<Vec<(Region, Range<usize>)>> {
+    if config.sections.is_empty() {
+        return None;
+    }
+    let found = match sections::read_sections(data, &config.sections) {
+        Ok(found) => found,
+        Err(e) => {
+            eprintln!("Warning: {}; scanning the whole input", e);
+            return None;
+        }
+    };
+    for name in config.sections.iter().filter(|name| !found.iter().any(|section| &section.name == *name)) {
+        eprintln!("Warning: section {} not found", name);
+    }
+    if found.is_empty() {
+        eprintln!("Warning: none of the selected sections were found; scanning the whole input");
+        return None;
+    }
+
+    info!(config, "Sections: {}", found.len());
+    Some(
+        found
+            .into_iter()
+            .map(|section| (section.range(data.len()), section))
+            .filter(|(range, section)| {
+                if range.is_empty() {
+                    eprintln!("Warning: section {} lies beyond the end of the input; skipping it", section.name);
+                }
+                !range.is_empty()
+            })
+            .map(|(range, section)| (Region::Section(section), range))
+            .collect(),
+    )
+}
+
+/// Reconstruct the strings ending at each `--reverse` offset of one input, walking backward
+fn reverse_scan(
+    config: &Config,
+    data: &[u8],
+    extractor: &StringExtractor,
+    baseline: Option<&Baseline>,
+    region_map: Option<&RegionMap>,
+    sha256: Option<String>,
+) -> Result<(Vec<FoundString>, RunSummary)> {
+    let started = Instant::now();
+    let mut results = Vec::new();
+    for &end in &config.reverse {
+        match usize::try_from(end).ok().filter(|&end| end <= data.len()) {
+            Some(end) => results.extend(extractor.extract_ending_at(data, end, 0)),
+            None => eprintln!("Warning: end offset 0x{:X} lies beyond the end of the input; skipping it", end),
+        }
+    }
+
+    let mut summary = RunSummary { sha256, ..Default::default() };
+    summary.duplicates_removed = dedup_results(&mut results, config.dedup_mode());
+    summary.baseline_suppressed = baseline.map_or(0, |baseline| baseline.suppress(&mut results));
+    if let Some(region_map) = region_map {
+        region_map.annotate(&mut results, 0);
+    }
+    rebase_offsets(&mut results, config.base_offset)?;
+    summary.elapsed_ms = started.elapsed().as_millis() as u64;
+    summary.strings_found = results.len();
+    print_results(config, "Results", &summary, baseline.is_some());
+    Ok((results, summary))
+}
+
+/// Print the result counters of one input or of the whole run
+fn print_results(config: &Config, title: &str, summary: &RunSummary, with_baseline: bool) {
+    info!(config, "\n{}:", title);
+    info!(config, "  Total strings found: {}", summary.strings_found);
+    if summary.duplicates_removed > 0 {
+        info!(config, "  Duplicates removed: {}", summary.duplicates_removed);
+    }
+    if summary.fragments_merged > 0 {
+        info!(config, "  Adjacent fragments merged: {}", summary.fragments_merged);
+    }
+    if summary.encodings_merged > 0 {
+        info!(config, "  Merged across encodings: {}", summary.encodings_merged);
+    }
+    if config.correlate_wide {
+        info!(config, "  ASCII/UTF-16 pairs: {}", summary.wide_pairs);
+    }
+    if with_baseline {
+        info!(config, "  Suppressed by baseline: {}", summary.baseline_suppressed);
+    }
+    if summary.bucketed > 0 {
+        info!(config, "  Dropped by --bucket: {}", summary.bucketed);
+    }
+}
+
+/// Print the `--detect-tables` report of one input
+fn print_tables(config: &Config, tables: &[StringTable]) {
+    info!(config, "  String tables: {}", tables.len());
+    for table in tables {
+        info!(config, "    {}", table);
+    }
+}
+
+/// Print combined totals when more than one input was scanned
+fn print_totals(config: &Config, summary: &RunSummary) {
+    if summary.per_file.len() > 1 {
+        let title = format!("Total over {} files", summary.per_file.len());
+        print_results(config, &title, summary, config.baseline.is_some());
+    }
+}
+
+/// Streaming variant of [`run`]: scan sequential windows and write each one's
+/// results before moving on, recording a checkpoint after every window
+///
+/// Resuming relies on deterministic chunking: windows are laid out from the
+/// checkpoint in steps of `--window-size`, so the same flags must be used when
+/// restarting. Deduplication applies within each window.
+fn run_streaming(
+    config: &Config,
+    extractor: &StringExtractor,
+    baseline: Option<&Baseline>,
+    should_stop: &(dyn Fn() -> bool + Sync),
+) -> Result<RunSummary> {
+    if let Some(reason) = streaming_unsupported(config) {
+        return Err(MemstrapError::Config(reason.to_string()));
+    }
+
+    let resume_from = match &config.checkpoint {
+        Some(path) => {
+            if config.output.is_none() {
+                return Err(MemstrapError::Config("--checkpoint requires --output".to_string()));
+            }
+            stream::load_checkpoint(path)?.unwrap_or_default()
+        }
+        None => Checkpoint::default(),
+    };
+
+    // A resumed scan appends to the output it already started
+    let output_options = config.output_options();
+    let (output, write_header): (Box<dyn Write>, bool) = match &config.output {
+        Some(path) if resume_from.offset > 0 && path.exists() => {
+            (Box::new(OpenOptions::new().append(true).open(path)?), false)
+        }
+        Some(path) => (Box::new(File::create(compressed_path(path, config.compress))?), true),
+        None => (Box::new(io::stdout().lock()), true),
+    };
+    let output = CompressedWriter::new(output, config.compress)?;
+    // Buffer rows between the flushes at window boundaries
+    let output = BufWriter::with_capacity(config.output_buffer.saturating_mul(1024), output);
+    let mut writer = CsvStreamWriter::new(output, &config.file_path[0], output_options, write_header)?;
+
+    let mut summary = RunSummary::default();
+    for path in &config.file_path {
+        writer.set_file_path(path);
+        let file_summary = stream_file(config, path, extractor, baseline, &mut writer, &resume_from, should_stop)?;
+        summary.add(path, file_summary);
+        if summary.interrupted {
+            break;
+        }
+    }
+    writer.into_inner().into_inner().map_err(io::IntoInnerError::into_error)?.finish()?;
+    print_totals(config, &summary);
+    warn_if_interrupted(&summary);
+    if let Some(output_path) = &config.output {
+        info!(config, "  Results written to: {}", compressed_path(output_path, config.compress).display());
+    }
+
+    Ok(summary)
+}
+
+/// Stream one input from `resume_from` to its end into `writer`
+fn stream_file<W: Write>(
+    config: &Config,
+    path: &Path,
+    extractor: &StringExtractor,
+    baseline: Option<&Baseline>,
+    writer: &mut CsvStreamWriter<W>,
+    resume: &Checkpoint,
+    should_stop: &(dyn Fn() -> bool + Sync),
+) -> Result<RunSummary> {
+    info!(config, "Processing file: {}", path.display());
+
+    let mut file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    info!(config, "File size: {} bytes ({:.2} MB)", file_len, file_len as f64 / 1024.0 / 1024.0);
+    if file_len == 0 {
+        info!(config, "File is empty, nothing to scan");
+    }
+
+    let resume_from = resume.offset;
+    if resume_from > file_len {
+        return Err(MemstrapError::Config(format!(
+            "checkpoint offset {} is beyond the end of the file ({} bytes)",
+            resume_from, file_len
+        )));
+    }
+
+    // Resume from the checkpoint, but never inside the skipped header
+    let scan_from = resume_from.max(config.skip_header.min(file_len));
+    let windows = stream::plan_windows(scan_from..file_len, config.window_size);
+    if resume_from > 0 {
+        info!(config, "Resuming from checkpoint offset: 0x{:X}", resume_from);
+    }
+    info!(config, "Streaming {} windows of {:.2} MB", windows.len(), config.window_size as f64 / 1024.0 / 1024.0);
+
+    let mut progress = byte_progress(config, file_len - scan_from);
+    let mut window_start = scan_from;
+    let mut summary = RunSummary {
+        bytes_scanned: file_len - scan_from,
+        ..Default::default()
+    };
+    let mut at_risk = 0;
+    let started = Instant::now();
+
+    // Hash as windows are read; the prefix skipped by a resume or --skip-header is hashed first
+    let hash_prefix = config.emit_hash && scan_from > 0;
+    let read_from = if hash_prefix { 0 } else { scan_from };
+    file.seek(SeekFrom::Start(read_from))?;
+    let mut reader = HashingReader::new(file.take(file_len - read_from), config.emit_hash);
+    if hash_prefix {
+        io::copy(&mut (&mut reader).take(scan_from), &mut io::sink())?;
+    }
+
+    if let Some(rate) = config.sample_rate() {
+        info!(config, "Sampling {:.0}% of windows", rate * 100.0);
+        eprintln!("Note: sampled scan; results cover only part of {}", path.display());
+    }
+
+    // Strings the interrupted scan already reported may reach into the first window
+    let start = Checkpoint { offset: scan_from, covered: resume.covered.clone() };
+    stream::scan_reader_sampled(
+        &mut reader,
+        start,
+        config.window_size,
+        config.overlap,
+        extractor,
+        config.sample_rate().unwrap_or(1.0),
+        |checkpoint, mut results| {
+            let window_end = checkpoint.offset;
+            summary.duplicates_removed += dedup_results(&mut results, config.dedup_mode());
+            summary.fragments_merged += merge_fragments(config, &mut results);
+            summary.encodings_merged += merge_encodings(config, &mut results);
+            summary.wide_pairs += pair_wide(config, &mut results);
+            if let Some(baseline) = baseline {
+                summary.baseline_suppressed += baseline.suppress(&mut results);
+            }
+            at_risk += count_overlap_risks(&results, config.overlap);
+            summary.strings_found += results.len();
+            rebase_offsets(&mut results, config.base_offset)?;
+
+            // Output must be durable before the checkpoint moves past it
+            writer.write_rows(&results)?;
+            writer.flush()?;
+            if let Some(path) = &config.checkpoint {
+                stream::save_checkpoint(path, checkpoint)?;
+            }
+            if let Some(progress) = &mut progress {
+                progress.advance(window_end - window_start, results.len());
+            }
+            window_start = window_end;
+
+            if window_end < file_len && should_stop() {
+                summary.interrupted = true;
+                return Ok(ControlFlow::Break(()));
+            }
+            Ok(ControlFlow::Continue(()))
+        },
+    )?;
+
+    summary.elapsed_ms = started.elapsed().as_millis() as u64;
+    // The hash of a partly read input would not identify it
+    summary.sha256 = reader.finish().filter(|_| !summary.interrupted);
+    if let Some(sha256) = &summary.sha256 {
+        writer.write_hash_comment(sha256)?;
+        writer.flush()?;
+    }
+    if let Some(progress) = &mut progress {
+        progress.finish();
+    }
+    if windows.len() > 1 && config.overlap == 0 {
+        eprintln!("Warning: --overlap 0 leaves no overlap between windows; strings crossing a window boundary are split");
+    } else if windows.len() > 1 && at_risk > 0 {
+        eprintln!(
+            "Warning: {} strings are close to the {} byte window overlap and may be split at window boundaries; consider a larger --overlap",
+            at_risk, config.overlap
+        );
+    }
+    print_results(config, "Results", &summary, baseline.is_some());
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DedupMode, EncodingType};
+    use clap::Parser;
+    use std::io::{Seek, SeekFrom, Write};
+
+    #[test]
+    fn test_plan_input_budget_and_threads() {
+        const MB: u64 = 1024 * 1024;
+        let plan = |args: &[&str], file_size| plan_input(&Config::parse_from([&["memstrap", "x"], args].concat()), file_size).unwrap();
+
+        assert!(plan(&["--max-memory", "32"], 64 * MB).streaming);
+        assert!(!plan(&["--max-memory", "64"], 64 * MB).streaming);
+        // Options that rule out streaming keep the scan in memory
+        assert!(!plan(&["--max-memory", "32", "--detect-tables"], 64 * MB).streaming);
+
+        // An explicit thread count is honored for large inputs, the default is capped
+        assert_eq!(plan(&["--max-memory", "4096", "-j", "32"], 1024 * MB).num_threads, 32);
+        assert!(plan(&["--max-memory", "4096"], 1024 * MB).num_threads <= 8);
+    }
+
+    #[test]
+    fn test_over_memory_budget_uses_the_plan() {
+        let input = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(input.path(), vec![b'a'; 2 * 1024 * 1024]).unwrap();
+        let path = input.path().to_str().unwrap();
+        let over = |args: &[&str]| over_memory_budget(&Config::parse_from([&["memstrap", path, "--quiet"], args].concat())).unwrap();
+
+        assert!(over(&["--max-memory", "1"]));
+        assert!(!over(&["--max-memory", "2"]));
+        // Warned about, but scanned in memory
+        assert!(!over(&["--max-memory", "1", "--detect-tables"]));
+    }
+
+    #[test]
+    fn test_sample_rate_skips_blocks_of_a_single_chunk() {
+        // 4 MiB, a single chunk, with a string in the middle of every 64 KiB
+        let mut data = vec![0u8; 4 * 1024 * 1024];
+        for (i, block) in data.chunks_mut(64 * 1024).enumerate() {
+            block[32 * 1024..32 * 1024 + 11].copy_from_slice(format!("string {:04}", i).as_bytes());
+        }
+        let input = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(input.path(), &data).unwrap();
+        let path = input.path().to_str().unwrap();
+        let found = |args: &[&str]| {
+            let config = Config::parse_from([&["memstrap", path, "--quiet"], args].concat());
+            let extractor = build_extractor(&config).unwrap();
+            scan_file(&config, input.path(), &extractor, None, None, &|| false).unwrap().0
+        };
+
+        assert_eq!(found(&[]).len(), 64);
+        let sampled = found(&["--sample-rate", "0.5"]);
+        assert_eq!(sampled.len(), 32);
+    }
+
+    #[test]
+    fn test_string_longer_than_overlap_is_flagged() {
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None).unwrap();
+
+        // A 40-byte string straddling the boundary at 64, with only 16 bytes of overlap
+        let mut data = [0u8; 128];
+        data[50..90].copy_from_slice(&[b'A'; 40]);
+
+        let mut results: Vec<FoundString> = crate::plan::plan_chunks(data.len(), 2, 16)
+            .into_iter()
+            .flat_map(|(start, end, base_offset)| extractor.extract_strings(&data[start..end], base_offset))
+            .collect();
+        dedup_results(&mut results, DedupMode::Offset);
+
+        // The overlap is too small, so the string is split into two fragments
+        assert_eq!(results.len(), 2);
+        assert_eq!((results[0].offset.get(), results[0].byte_length), (50, 30));
+        assert_eq!((results[1].offset.get(), results[1].byte_length), (64, 26));
+        assert_eq!(count_overlap_risks(&results, 16), 2);
+        assert_eq!(count_overlap_risks(&results, 4096), 0);
+        assert_eq!(count_overlap_risks(&results, 0), 0);
+    }
+
+    #[test]
+    fn test_no_dedup_keeps_overlap_duplicates() {
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None).unwrap();
+
+        // A string just past the boundary at 64, inside the 16 bytes of overlap the first chunk also scans
+        let mut data = [0u8; 128];
+        data[66..76].copy_from_slice(b"in overlap");
+
+        let offsets = |args: &[&str]| -> Vec<(u64, usize)> {
+            let config = Config::parse_from(["memstrap", "dump.raw"].iter().chain(args));
+            let mut results: Vec<FoundString> = crate::plan::plan_chunks(data.len(), 2, 16)
+                .into_iter()
+                .flat_map(|(start, end, base_offset)| extractor.extract_strings(&data[start..end], base_offset))
+                .collect();
+            dedup_results(&mut results, config.dedup_mode());
+            results.iter().map(|s| (s.offset.get(), s.byte_length)).collect()
+        };
+
+        assert_eq!(offsets(&[]), [(66, 10)]);
+        assert_eq!(offsets(&["--no-dedup"]), [(66, 10), (66, 10)]);
+    }
+
+    #[test]
+    fn test_no_dedup_through_scan_file() {
+        // Two 16 MiB chunks; the string starts just past the boundary, in the first chunk's overlap
+        let boundary = 16 * 1024 * 1024;
+        let input = tempfile::NamedTempFile::new().unwrap();
+        input.as_file().set_len(2 * boundary as u64).unwrap();
+        let mut file = input.as_file();
+        file.seek(SeekFrom::Start(boundary as u64 + 2)).unwrap();
+        file.write_all(b"in overlap").unwrap();
+        let path = input.path().to_str().unwrap();
+        let found = |args: &[&str]| {
+            let config = Config::parse_from([&["memstrap", path, "-j", "2", "-e", "ascii", "--quiet"], args].concat());
+            let extractor = build_extractor(&config).unwrap();
+            let results = scan_file(&config, input.path(), &extractor, None, None, &|| false).unwrap().0;
+            results.iter().map(|s| s.offset.get()).collect::<Vec<_>>()
+        };
+
+        let offset = boundary as u64 + 2;
+        assert_eq!(found(&[]), [offset]);
+        assert_eq!(found(&["--no-dedup"]), [offset, offset]);
+        assert!(Config::try_parse_from(["memstrap", path, "--dedup", "content", "--no-dedup"]).is_err());
+    }
+}
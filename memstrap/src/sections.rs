@@ -0,0 +1,93 @@
+//! ELF/PE section lookup for `--sections`
+
+use crate::error::{MemstrapError, Result};
+use goblin::elf::section_header::SHT_NOBITS;
+use goblin::elf::Elf;
+use goblin::pe::PE;
+use std::ops::Range;
+
+/// A named section of an executable, located by its bytes in the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Section name as stored in the file, e.g. `.rodata`
+    pub name: String,
+    /// File offset of the section's first byte
+    pub start: u64,
+    /// Size of the section's data in the file
+    pub len: u64,
+}
+
+impl Section {
+    /// Byte range of the section within a file of `file_len` bytes
+    ///
+    /// Truncated at the end of the file; empty when the section starts past it.
+    pub fn range(&self, file_len: usize) -> Range<usize> {
+        let clamp = |offset: u64| offset.min(file_len as u64) as usize;
+        clamp(self.start)..clamp(self.start.saturating_add(self.len))
+    }
+}
+
+/// List the sections of the ELF or PE file `data` whose name is in `names`
+///
+/// Sections are returned in file order. Sections without file data (such as
+/// `.bss`) are skipped. Fails if `data` is not a parseable ELF or PE file.
+pub fn read_sections(data: &[u8], names: &[String]) -> Result<Vec<Section>> {
+    let invalid = |reason: String| MemstrapError::BinaryParse(reason);
+    let wanted = |name: &str| names.iter().any(|n| n == name);
+
+    let mut sections = if data.starts_with(b"\x7FELF") {
+        let elf = Elf::parse(data).map_err(|e| invalid(format!("invalid ELF file: {}", e)))?;
+        elf.section_headers
+            .iter()
+            .filter(|header| header.sh_type != SHT_NOBITS)
+            .filter_map(|header| {
+                let name = elf.shdr_strtab.get_at(header.sh_name)?;
+                wanted(name).then(|| Section { name: name.to_string(), start: header.sh_offset, len: header.sh_size })
+            })
+            .collect::<Vec<_>>()
+    } else if data.starts_with(b"MZ") {
+        let pe = PE::parse(data).map_err(|e| invalid(format!("invalid PE file: {}", e)))?;
+        pe.sections
+            .iter()
+            .filter_map(|section| {
+                let name = section.name().ok()?;
+                wanted(name).then(|| Section {
+                    name: name.to_string(),
+                    start: u64::from(section.pointer_to_raw_data),
+                    len: u64::from(section.size_of_raw_data),
+                })
+            })
+            .collect()
+    } else {
+        return Err(invalid("not an ELF or PE file".to_string()));
+    };
+
+    sections.sort_by_key(|section| section.start);
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ELF64 object whose `.rodata` holds "secret in rodata" at file offset 0x64
+    const RODATA_ELF: &[u8] = include_bytes!("../tests/fixtures/rodata.elf");
+
+    #[test]
+    fn test_read_elf_sections() {
+        let sections = read_sections(RODATA_ELF, &[".rodata".to_string(), ".missing".to_string()]).unwrap();
+        assert_eq!(sections, [Section { name: ".rodata".to_string(), start: 0x60, len: 24 }]);
+        assert_eq!(&RODATA_ELF[sections[0].range(RODATA_ELF.len())][4..20], b"secret in rodata");
+
+        let both = read_sections(RODATA_ELF, &[".shstrtab".to_string(), ".text".to_string()]).unwrap();
+        let names: Vec<_> = both.iter().map(|section| section.name.as_str()).collect();
+        assert_eq!(names, [".text", ".shstrtab"]);
+    }
+
+    #[test]
+    fn test_read_sections_rejects_other_files() {
+        assert!(matches!(read_sections(b"plain data", &[".rodata".to_string()]), Err(MemstrapError::BinaryParse(_))));
+        assert!(read_sections(&RODATA_ELF[..40], &[".rodata".to_string()]).is_err());
+        assert!(read_sections(b"MZ\x90\x00", &[".rdata".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! Minimal HTTP service for `--serve`, returning scan results as JSON
+//!
+//! `GET /scan?path=FILE` scans a file readable by the server process and
+//! answers with its strings; `min_len`, `encodings` and `search` take the
+//! values of the matching command line options. Requests are handled one at a
+//! time, each scan using all threads.
+
+use crate::config::{is_stdin, Config};
+use crate::error::{MemstrapError, Result};
+use crate::extractor::FoundString;
+use crate::image::ScannedImage;
+use crate::run::build_extractor;
+use clap::Parser;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Parameters of a `GET /scan` request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanRequest {
+    /// File to scan, as seen by the server
+    pub path: PathBuf,
+    /// `--min-len`
+    pub min_len: Option<usize>,
+    /// `--encoding`, e.g. `ascii,utf16le`
+    pub encodings: Option<String>,
+    /// `--search`
+    pub search: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct ScanResponse<'a> {
+    path: &'a Path,
+    count: usize,
+    results: &'a [FoundString],
+}
+
+impl ScanRequest {
+    /// Parse the target of a request such as `/scan?path=%2Ftmp%2Fmem.raw&min_len=6`
+    ///
+    /// Fails on a missing `path`, an unknown parameter or a malformed value.
+    pub fn from_url(url: &str) -> Result<Self> {
+        let invalid = |reason: String| MemstrapError::Config(reason);
+        let query = url.split_once('?').map_or("", |(_, query)| query);
+
+        let mut request = ScanRequest::default();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value).ok_or_else(|| invalid(format!("malformed value for {}", name)))?;
+            match name {
+                "path" => request.path = PathBuf::from(value),
+                "min_len" => {
+                    request.min_len = Some(value.parse().map_err(|_| invalid(format!("invalid min_len {:?}", value)))?)
+                }
+                "encodings" => request.encodings = Some(value),
+                "search" => request.search = Some(value),
+                _ => return Err(invalid(format!("unknown parameter {:?}", name))),
+            }
+        }
+        if request.path.as_os_str().is_empty() {
+            return Err(invalid("missing path parameter".to_string()));
+        }
+        if is_stdin(&request.path) {
+            return Err(invalid("standard input cannot be scanned over HTTP".to_string()));
+        }
+        Ok(request)
+    }
+
+    /// The command line configuration equivalent to this request
+    fn config(&self) -> Result<Config> {
+        let mut args: Vec<OsString> = vec!["memstrap".into(), self.path.clone().into(), "--quiet".into()];
+        if let Some(min_len) = self.min_len {
+            args.push(format!("--min-len={}", min_len).into());
+        }
+        if let Some(encodings) = &self.encodings {
+            args.push(format!("--encoding={}", encodings).into());
+        }
+        if let Some(search) = &self.search {
+            args.push(format!("--search={}", search).into());
+        }
+        Config::try_parse_from(args).map_err(|e| MemstrapError::Config(e.to_string()))
+    }
+}
+
+/// Scan the file named by `request` and return its strings as a JSON document
+///
+/// The document holds the `path`, the `count` of strings and the `results`,
+/// each a serialized [`FoundString`].
+pub fn handle(request: &ScanRequest) -> Result<String> {
+    let config = request.config()?;
+    config.validate()?;
+    config.check_encodings()?;
+    config.check_inputs()?;
+    let extractor = build_extractor(&config)?;
+
+    let results = ScannedImage::load(&request.path, config.no_mmap)?.search(&config, &extractor)?;
+
+    let response = ScanResponse { path: &request.path, count: results.len(), results: &results };
+    serde_json::to_string(&response).map_err(|e| MemstrapError::Server(e.to_string()))
+}
+
+/// Status code and JSON body answering a `GET` of `url`
+///
+/// Bad parameters and unreadable inputs are client errors (400); other
+/// failures are reported as 500.
+pub fn respond(url: &str) -> (u16, String) {
+    let path = url.split_once('?').map_or(url, |(path, _)| path);
+    if path != "/scan" {
+        return (404, error_json("not found; use /scan?path=FILE"));
+    }
+    match ScanRequest::from_url(url).and_then(|request| handle(&request)) {
+        Ok(body) => (200, body),
+        Err(e) if e.exit_code() == 2 || matches!(e, MemstrapError::Io(_)) => (400, error_json(&e.to_string())),
+        Err(e) => (500, error_json(&e.to_string())),
+    }
+}
+
+/// Answer requests on `addr` until the process is stopped
+pub fn serve(addr: &str) -> Result<()> {
+    let server = tiny_http::Server::http(addr)
+        .map_err(|e| MemstrapError::Server(format!("cannot listen on {}: {}", addr, e)))?;
+    eprintln!("Serving scans on http://{}/scan?path=FILE", addr);
+
+    let content_type = tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+    for request in server.incoming_requests() {
+        let (status, body) = if *request.method() == tiny_http::Method::Get {
+            respond(request.url())
+        } else {
+            (405, error_json("only GET is supported"))
+        };
+        let response = tiny_http::Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(e) = request.respond(response) {
+            eprintln!("Warning: could not send a response: {}", e);
+        }
+    }
+    Ok(())
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Decode `%XX` escapes and `+` (a space) in a query value
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'%' => {
+                let hex = [input.next()?, input.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            }
+            b'+' => bytes.push(b' '),
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_parse_request() {
+        let request = ScanRequest::from_url("/scan?path=%2Ftmp%2Fmem+1.raw&min_len=6&encodings=ascii,utf16le&search=pass").unwrap();
+        assert_eq!(
+            request,
+            ScanRequest {
+                path: PathBuf::from("/tmp/mem 1.raw"),
+                min_len: Some(6),
+                encodings: Some("ascii,utf16le".to_string()),
+                search: Some("pass".to_string()),
+            }
+        );
+
+        assert!(ScanRequest::from_url("/scan?min_len=6").is_err());
+        assert!(ScanRequest::from_url("/scan?path=x&min_len=six").is_err());
+        assert!(ScanRequest::from_url("/scan?path=x&color=always").is_err());
+        assert!(ScanRequest::from_url("/scan?path=%ZZ").is_err());
+    }
+
+    #[test]
+    fn test_handle_returns_json_results() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"\x00\x00password=hunter2\x00\x00short\x00\x00passkey\x00").unwrap();
+        let url = format!("/scan?path={}&min_len=6&encodings=ascii&search=pass", file.path().display());
+
+        let (status, body) = respond(&url);
+        assert_eq!(status, 200, "{}", body);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["count"], 2);
+        assert_eq!(json["results"][0]["content"], "password=hunter2");
+        assert_eq!(json["results"][0]["offset"], 2);
+        assert_eq!(json["results"][1]["content"], "passkey");
+
+        // Unknown routes, bad parameters and missing files are reported as JSON errors
+        assert_eq!(respond("/other").0, 404);
+        let (status, body) = respond(&format!("{}&encodings=latin1", url));
+        assert_eq!(status, 400);
+        assert!(serde_json::from_str::<serde_json::Value>(&body).unwrap()["error"].is_string());
+        assert_eq!(respond("/scan?path=/nonexistent/memstrap.raw").0, 400);
+    }
+
+    #[test]
+    fn test_handle_matches_command_line() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"\x00\x00config=/etc/app.conf\x00\x00").unwrap();
+        file.write_all(&"wide user name".encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<_>>()).unwrap();
+        file.write_all(b"\x00\x00short\x00").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let (status, body) = respond(&format!("/scan?path={}&min_len=5&encodings=ascii,utf16le", path));
+        assert_eq!(status, 200, "{}", body);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let served: Vec<(u64, String)> = json["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| (result["offset"].as_u64().unwrap(), result["content"].as_str().unwrap().to_string()))
+            .collect();
+
+        let output = NamedTempFile::new().unwrap();
+        let args = ["memstrap", path, "-n", "5", "-e", "ascii,utf16le", "-o", output.path().to_str().unwrap(), "--quiet"];
+        crate::run::run(&Config::parse_from(args)).unwrap();
+        let scanned: Vec<(u64, String)> = csv::Reader::from_path(output.path())
+            .unwrap()
+            .records()
+            .map(|record| {
+                let record = record.unwrap();
+                (record[2].parse().unwrap(), record[5].to_string())
+            })
+            .collect();
+
+        assert_eq!(served.len(), 3);
+        assert_eq!(served, scanned);
+    }
+}
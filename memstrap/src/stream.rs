@@ -0,0 +1,284 @@
+//! Sequential windowed extraction for `--stream` and `--checkpoint`
+//!
+//! Instead of mapping the whole file, streaming mode reads fixed-size windows
+//! (plus the configured overlap) one after another and hands each window's
+//! results to the caller as soon as it is done. Window boundaries are always
+//! `start + k * window_size`, so a scan resumed from a checkpoint visits the
+//! same windows the interrupted scan would have.
+
+use crate::error::{MemstrapError, Result};
+use crate::extractor::{Encoding, FoundString, StringExtractor};
+use crate::plan::is_sampled;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::{ControlFlow, Range};
+use std::path::Path;
+
+/// Default bytes per streaming window
+pub const DEFAULT_WINDOW_SIZE: usize = 64 * 1024 * 1024;
+
+/// Default size, in KB, of the streaming output buffer
+pub const DEFAULT_OUTPUT_BUFFER_KB: usize = 64;
+
+/// Where a streaming scan stands after a window
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Last fully processed offset
+    pub offset: u64,
+    /// End of the last reported string of each encoding that reaches past `offset`
+    ///
+    /// The next window skips the tails of these strings, which it would
+    /// otherwise report as new strings.
+    pub covered: HashMap<Encoding, u64>,
+}
+
+/// Read a checkpoint file, or `None` if it does not exist yet
+///
+/// The first line holds the offset; each further line an encoding name and
+/// the end of its last string, as written by [`save_checkpoint`].
+pub fn load_checkpoint(path: &Path) -> Result<Option<Checkpoint>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let invalid = || MemstrapError::Config(format!("invalid checkpoint file {}: {:?}", path.display(), text.trim()));
+    let mut lines = text.lines();
+    let offset = lines.next().and_then(|line| line.trim().parse().ok()).ok_or_else(invalid)?;
+    let mut covered = HashMap::new();
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        let (name, end) = line.trim().split_once(' ').ok_or_else(invalid)?;
+        let encoding = name
+            .parse()
+            .ok()
+            .or_else(|| encoding_rs::Encoding::for_label(name.as_bytes()).map(|codec| Encoding::Custom(codec.name())))
+            .ok_or_else(invalid)?;
+        covered.insert(encoding, end.parse().map_err(|_| invalid())?);
+    }
+    Ok(Some(Checkpoint { offset, covered }))
+}
+
+/// Record `checkpoint` as the progress of the scan
+///
+/// The value is written to a temporary file and renamed into place so an
+/// interruption never leaves a truncated checkpoint behind.
+pub fn save_checkpoint(path: &Path, checkpoint: &Checkpoint) -> Result<()> {
+    let mut text = format!("{}\n", checkpoint.offset);
+    for (encoding, end) in &checkpoint.covered {
+        text.push_str(&format!("{} {}\n", encoding.as_str(), end));
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, text)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Split `range` into consecutive owned windows of `window_size` bytes
+pub fn plan_windows(range: Range<u64>, window_size: usize) -> Vec<Range<u64>> {
+    let step = window_size.max(1) as u64;
+    let mut windows = Vec::new();
+    let mut start = range.start;
+    while start < range.end {
+        let end = range.end.min(start + step);
+        windows.push(start..end);
+        start = end;
+    }
+    windows
+}
+
+/// Scan `range` of `reader` window by window
+///
+/// Seeks to the start of `range` and hands the remaining work to
+/// [`scan_reader`]; see there for how windows and their results are laid out.
+pub fn scan_windows<R, F>(
+    reader: &mut R,
+    range: Range<u64>,
+    window_size: usize,
+    overlap: usize,
+    extractor: &StringExtractor,
+    on_window: F,
+) -> Result<()>
+where
+    R: Read + Seek,
+    F: FnMut(u64, Vec<FoundString>) -> Result<ControlFlow<()>>,
+{
+    reader.seek(SeekFrom::Start(range.start))?;
+    scan_reader(reader.take(range.end - range.start), range.start, window_size, overlap, extractor, on_window)
+}
+
+/// Scan everything `reader` yields, window by window, starting at offset `base`
+///
+/// Each window is read together with `overlap` trailing bytes so strings that
+/// cross its end are still found whole, but only strings starting inside the
+/// window are kept; the tail of such a string is not reported again by the
+/// next window. `on_window` receives the window's end offset (the new
+/// checkpoint) and its results in extraction order, and can stop the scan
+/// after that window by returning `ControlFlow::Break`.
+pub fn scan_reader<R, F>(
+    reader: R,
+    base: u64,
+    window_size: usize,
+    overlap: usize,
+    extractor: &StringExtractor,
+    on_window: F,
+) -> Result<()>
+where
+    R: Read,
+    F: FnMut(u64, Vec<FoundString>) -> Result<ControlFlow<()>>,
+{
+    let mut on_window = on_window;
+    let start = Checkpoint { offset: base, ..Default::default() };
+    scan_reader_sampled(reader, start, window_size, overlap, extractor, 1.0, |checkpoint, results| {
+        on_window(checkpoint.offset, results)
+    })
+}
+
+/// Like [`scan_reader`], but only extract strings from a `sample_rate` fraction of the windows
+///
+/// The scan starts at `start.offset`, skipping the tails of the strings in
+/// `start.covered`, and `on_window` receives the checkpoint after each window.
+/// Windows are picked with [`is_sampled`] by their index `start / window_size`,
+/// so a resumed scan samples the same windows as the original one. Skipped
+/// windows are still read and passed to `on_window`, with no results.
+pub fn scan_reader_sampled<R, F>(
+    mut reader: R,
+    start: Checkpoint,
+    window_size: usize,
+    overlap: usize,
+    extractor: &StringExtractor,
+    sample_rate: f64,
+    mut on_window: F,
+) -> Result<()>
+where
+    R: Read,
+    F: FnMut(&Checkpoint, Vec<FoundString>) -> Result<ControlFlow<()>>,
+{
+    let window_size = window_size.max(1);
+    let mut buffer = Vec::new();
+    let Checkpoint { offset: mut start, mut covered } = start;
+    let mut eof = false;
+    loop {
+        // Top the buffer up to one window plus its overlap
+        if !eof {
+            let wanted = window_size + overlap - buffer.len();
+            let read = reader.by_ref().take(wanted as u64).read_to_end(&mut buffer)?;
+            eof = read < wanted;
+        }
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let owned = window_size.min(buffer.len());
+        let end = start + owned as u64;
+        let mut results = if is_sampled((start / window_size as u64) as usize, sample_rate) {
+            extractor.extract_strings(&buffer, start)
+        } else {
+            Vec::new()
+        };
+        results.retain(|s| s.offset < end && s.offset >= covered.get(&s.encoding).copied().unwrap_or(0));
+        for s in &results {
+            let string_end = s.offset.get() + s.byte_length as u64;
+            let entry = covered.entry(s.encoding).or_insert(0);
+            *entry = (*entry).max(string_end);
+        }
+        // Only strings reaching past the window still matter to the next one
+        covered.retain(|_, &mut covered_end| covered_end > end);
+        let checkpoint = Checkpoint { offset: end, covered };
+        if on_window(&checkpoint, results)?.is_break() {
+            return Ok(());
+        }
+        covered = checkpoint.covered;
+
+        buffer.drain(..owned);
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EncodingType;
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plan_windows_is_aligned_to_start() {
+        assert_eq!(plan_windows(0..100, 40), vec![0..40, 40..80, 80..100]);
+        assert_eq!(plan_windows(40..100, 40), vec![40..80, 80..100]);
+        assert!(plan_windows(100..100, 40).is_empty());
+    }
+
+    #[test]
+    fn test_windows_match_whole_scan() {
+        let mut data = vec![0u8; 200];
+        data[10..20].copy_from_slice(b"first one!");
+        data[60..76].copy_from_slice(b"crosses boundary");
+        data[150..160].copy_from_slice(b"last words");
+
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None).unwrap();
+        let mut streamed = Vec::new();
+        let mut checkpoints = Vec::new();
+        scan_windows(&mut Cursor::new(&data), 0..200, 64, 32, &extractor, |end, results| {
+            checkpoints.push(end);
+            streamed.extend(results);
+            Ok(ControlFlow::Continue(()))
+        })
+        .unwrap();
+
+        assert_eq!(checkpoints, [64, 128, 192, 200]);
+        assert_eq!(streamed, extractor.extract_strings(&data, 0));
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("scan.ckpt");
+
+        assert_eq!(load_checkpoint(&path).unwrap(), None);
+        let checkpoint = Checkpoint {
+            offset: 0x4000,
+            covered: HashMap::from([(Encoding::Utf16Le, 0x4010), (Encoding::Custom("windows-1251"), 0x4002)]),
+        };
+        save_checkpoint(&path, &checkpoint).unwrap();
+        assert_eq!(load_checkpoint(&path).unwrap(), Some(checkpoint));
+
+        // A bare offset is a checkpoint without covered strings
+        fs::write(&path, "4096\n").unwrap();
+        assert_eq!(load_checkpoint(&path).unwrap(), Some(Checkpoint { offset: 4096, ..Default::default() }));
+
+        fs::write(&path, "not a number").unwrap();
+        assert!(load_checkpoint(&path).is_err());
+        fs::write(&path, "4096\nklingon 4100\n").unwrap();
+        assert!(load_checkpoint(&path).is_err());
+    }
+
+    #[test]
+    fn test_resume_skips_the_tail_of_a_crossing_string() {
+        let mut data = [0u8; 200];
+        data[60..76].copy_from_slice(b"crosses boundary");
+        let extractor = StringExtractor::new(4, vec![EncodingType::Ascii], None, false, None).unwrap();
+        let scan = |start: Checkpoint| {
+            let mut checkpoints = Vec::new();
+            let mut found = Vec::new();
+            let reader = Cursor::new(&data[start.offset as usize..]);
+            scan_reader_sampled(reader, start, 64, 32, &extractor, 1.0, |checkpoint, results| {
+                checkpoints.push(checkpoint.clone());
+                found.extend(results.into_iter().map(|s| s.content));
+                Ok(ControlFlow::Continue(()))
+            })
+            .unwrap();
+            (checkpoints, found)
+        };
+
+        let (checkpoints, found) = scan(Checkpoint::default());
+        assert_eq!(found, ["crosses boundary"]);
+        assert_eq!(checkpoints[0].covered, HashMap::from([(Encoding::Ascii, 76)]));
+        assert!(checkpoints[1].covered.is_empty());
+
+        // Resuming after the first window does not report "boundary" again
+        let (_, resumed) = scan(checkpoints[0].clone());
+        assert!(resumed.is_empty(), "{:?}", resumed);
+    }
+}
@@ -0,0 +1,267 @@
+//! Interactive terminal browser for extraction results (`--tui`)
+
+use crate::error::Result;
+use crate::extractor::FoundString;
+use crate::output::hexdump_lines;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+
+/// Rows moved by PageUp/PageDown
+const PAGE_SIZE: usize = 20;
+
+/// State of the result browser
+pub struct App {
+    results: Vec<FoundString>,
+    filter: String,
+    visible: Vec<usize>,
+    table_state: TableState,
+    editing_filter: bool,
+    should_quit: bool,
+}
+
+impl App {
+    /// Create a browser over the given results with no filter applied
+    pub fn new(results: Vec<FoundString>) -> Self {
+        let mut app = App {
+            visible: (0..results.len()).collect(),
+            results,
+            filter: String::new(),
+            table_state: TableState::default(),
+            editing_filter: false,
+            should_quit: false,
+        };
+        app.select_first();
+        app
+    }
+
+    /// Current filter text
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// Show only results whose content contains `filter` (case-insensitive)
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_string();
+        let needle = filter.to_lowercase();
+        self.visible = self
+            .results
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| needle.is_empty() || s.content.to_lowercase().contains(&needle))
+            .map(|(idx, _)| idx)
+            .collect();
+        self.select_first();
+    }
+
+    /// Results that pass the current filter, in display order
+    pub fn visible_results(&self) -> impl Iterator<Item = &FoundString> {
+        self.visible.iter().map(|&idx| &self.results[idx])
+    }
+
+    /// Currently selected result, if any
+    pub fn selected(&self) -> Option<&FoundString> {
+        self.table_state
+            .selected()
+            .and_then(|row| self.visible.get(row))
+            .map(|&idx| &self.results[idx])
+    }
+
+    /// Whether the user asked to leave the browser
+    pub fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+
+    /// Move the selection by `delta` rows, clamping to the visible range
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let current = self.table_state.selected().unwrap_or(0) as isize;
+        let last = self.visible.len() as isize - 1;
+        self.table_state.select(Some((current + delta).clamp(0, last) as usize));
+    }
+
+    fn select_first(&mut self) {
+        let first = if self.visible.is_empty() { None } else { Some(0) };
+        self.table_state.select(first);
+    }
+
+    fn select_last(&mut self) {
+        self.table_state.select(self.visible.len().checked_sub(1));
+    }
+
+    /// Apply a key press to the browser state
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Enter | KeyCode::Esc => self.editing_filter = false,
+                KeyCode::Backspace => {
+                    let mut filter = self.filter.clone();
+                    filter.pop();
+                    self.set_filter(&filter);
+                }
+                KeyCode::Char(c) => {
+                    let filter = format!("{}{}", self.filter, c);
+                    self.set_filter(&filter);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(PAGE_SIZE as isize),
+            KeyCode::PageUp => self.move_selection(-(PAGE_SIZE as isize)),
+            KeyCode::Home | KeyCode::Char('g') => self.select_first(),
+            KeyCode::End | KeyCode::Char('G') => self.select_last(),
+            _ => {}
+        }
+    }
+}
+
+/// Run the interactive browser until the user quits
+pub fn browse(results: Vec<FoundString>) -> Result<()> {
+    let mut app = App::new(results);
+    let mut terminal = ratatui::init();
+
+    let outcome = (|| -> Result<()> {
+        while !app.should_quit() {
+            terminal.draw(|frame| draw(frame, &mut app))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    })();
+
+    ratatui::restore();
+    outcome
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(5), Constraint::Length(10)])
+        .split(frame.area());
+
+    let filter_title = if app.editing_filter {
+        "Filter (Enter to apply)"
+    } else {
+        "Filter (/ to edit, q to quit)"
+    };
+    let filter = Paragraph::new(app.filter.as_str())
+        .block(Block::default().borders(Borders::ALL).title(filter_title));
+    frame.render_widget(filter, areas[0]);
+
+    let rows: Vec<Row> = app
+        .visible_results()
+        .map(|s| {
+            Row::new(vec![
+                s.offset.hex(),
+                s.encoding.to_string(),
+                s.content.escape_debug().to_string(),
+            ])
+        })
+        .collect();
+    let title = format!("Results ({}/{})", app.visible.len(), app.results.len());
+    let table = Table::new(rows, [Constraint::Length(18), Constraint::Length(10), Constraint::Min(10)])
+        .header(Row::new(vec!["Offset", "Encoding", "Content"]).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, areas[1], &mut app.table_state);
+
+    let context_lines: Vec<Line> = match app.selected() {
+        Some(s) if s.context_before.is_some() || s.context_after.is_some() => {
+            let mut lines = Vec::new();
+            if let Some(ref before) = s.context_before {
+                let start = u64::from(s.offset).saturating_sub(before.len() as u64);
+                lines.extend(hexdump_lines(before, start).into_iter().map(Line::from));
+            }
+            lines.push(Line::from(format!("-- string: {} bytes at {} --", s.byte_length, s.offset.hex())));
+            if let Some(ref after) = s.context_after {
+                let start = u64::from(s.offset) + s.byte_length as u64;
+                lines.extend(hexdump_lines(after, start).into_iter().map(Line::from));
+            }
+            lines
+        }
+        Some(_) => vec![Line::from("No context captured (rerun with -C <NUM_BYTES>)")],
+        None => Vec::new(),
+    };
+    let context = Paragraph::new(context_lines)
+        .block(Block::default().borders(Borders::ALL).title("Context"));
+    frame.render_widget(context, areas[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::Encoding;
+    use crossterm::event::KeyModifiers;
+
+    fn found(offset: u64, content: &str) -> FoundString {
+        FoundString {
+            offset: offset.into(),
+            content: content.to_string(),
+            encoding: Encoding::Ascii,
+            byte_length: content.len(),
+            context_before: None,
+            context_after: None,
+            raw_bytes: None,
+            wide_offset: None,
+            had_bom: false,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note: None,
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_and_selection() {
+        let mut app = App::new(vec![
+            found(0, "kernel32.dll"),
+            found(16, "Hello World"),
+            found(32, "KERNELBASE.dll"),
+        ]);
+        assert_eq!(app.visible_results().count(), 3);
+        assert_eq!(app.selected().unwrap().offset, 0);
+
+        app.set_filter("kernel");
+        let offsets: Vec<u64> = app.visible_results().map(|s| s.offset.get()).collect();
+        assert_eq!(offsets, [0, 32]);
+
+        app.move_selection(5);
+        assert_eq!(app.selected().unwrap().offset, 32);
+
+        // Typing into the filter box narrows the list further
+        app.handle_key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE));
+        app.handle_key(KeyEvent::new(KeyCode::Char('b'), KeyModifiers::NONE));
+        assert_eq!(app.filter(), "kernelb");
+        assert_eq!(app.visible_results().count(), 1);
+
+        app.set_filter("missing");
+        assert!(app.selected().is_none());
+    }
+
+    #[test]
+    fn test_hexdump_lines() {
+        let lines = hexdump_lines(b"ABCDEFGHIJKLMNOP\x00\x01", 0x10);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("00000010  41 42 43"));
+        assert!(lines[0].ends_with("|ABCDEFGHIJKLMNOP|"));
+        assert_eq!(lines[1], format!("00000020  00 01{}  |..|", " ".repeat(42)));
+    }
+}
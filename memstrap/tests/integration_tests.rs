@@ -1,5 +1,8 @@
-use memstrap::{Config, StringExtractor, CsvOutput, FoundString};
+use clap::Parser;
+use memstrap::config::ContextView;
+use memstrap::{run, run_until, Config, StringExtractor, CsvOutput, XmlOutput, FoundString, MemstrapError, Offset, OutputOptions};
 use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
 
@@ -35,20 +38,40 @@ fn test_csv_output() {
     // Create test data
     let found_strings = vec![
         FoundString {
-            offset: 0,
+            offset: Offset(0),
             content: "Hello World".to_string(),
             encoding: memstrap::Encoding::Utf8,
             byte_length: 11,
             context_before: None,
             context_after: None,
+            raw_bytes: None,
+            wide_offset: None,
+            had_bom: false,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note: None,
+            confidence: None,
         },
         FoundString {
-            offset: 20,
+            offset: Offset(20),
             content: "Test String".to_string(),
             encoding: memstrap::Encoding::Ascii,
             byte_length: 11,
             context_before: None,
             context_after: None,
+            raw_bytes: None,
+            wide_offset: None,
+            had_bom: false,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note: None,
+            confidence: None,
         },
     ];
     
@@ -158,3 +181,1469 @@ fn test_gbk_integration() {
     assert!(results.iter().any(|s| s.content.contains("你好世界测试")));
     assert!(results.iter().any(|s| s.encoding == memstrap::Encoding::Gbk));
 }
+
+#[test]
+fn test_run_reports_metrics() {
+    let mut input = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut input, b"\x00\x00First string\x00\x00Second string\x00").unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    let config = Config::parse_from([
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output.path().as_os_str(),
+        "--quiet".as_ref(),
+    ]);
+    let summary = run(&config).unwrap();
+
+    assert_eq!(summary.bytes_scanned, 30);
+    assert_eq!(summary.strings_found, 2);
+    assert_eq!(summary.duplicates_removed, 0);
+
+    let line = summary.timing_line();
+    assert!(line.starts_with(&format!("elapsed_ms={} ", summary.elapsed_ms)));
+    assert!(line.contains("bytes=30 strings=2 throughput_mbps="));
+}
+
+#[test]
+fn test_no_mmap_matches_mmap() {
+    let mut input = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(
+        &mut input,
+        b"\x00\x00C:\\Windows\\explorer.exe\x00H\x00e\x00l\x00l\x00o\x00\x00\x00tail text\x00",
+    )
+    .unwrap();
+
+    let scan = |extra: &[&str]| {
+        let output = NamedTempFile::new().unwrap();
+        let mut args: Vec<&std::ffi::OsStr> = vec![
+            "memstrap".as_ref(),
+            input.path().as_os_str(),
+            "-o".as_ref(),
+            output.path().as_os_str(),
+            "-e".as_ref(),
+            "ascii".as_ref(),
+            "-e".as_ref(),
+            "utf16le".as_ref(),
+            "--quiet".as_ref(),
+        ];
+        args.extend(extra.iter().map(|a| std::ffi::OsStr::new(*a)));
+        let summary = run(&Config::parse_from(args)).unwrap();
+        (summary.strings_found, std::fs::read_to_string(output.path()).unwrap())
+    };
+
+    let (mapped_count, mapped_csv) = scan(&[]);
+    let (buffered_count, buffered_csv) = scan(&["--no-mmap"]);
+
+    assert_eq!(mapped_count, 3);
+    assert_eq!(buffered_count, mapped_count);
+    assert_eq!(buffered_csv, mapped_csv);
+}
+
+#[test]
+fn test_raw_hex_column() {
+    // "id=42" in ASCII, then "你好世界" in GBK
+    let data = b"id=42\x00\xC4\xE3\xBA\xC3\xCA\xC0\xBD\xE7\x00";
+
+    let extractor = StringExtractor::new(
+        4,
+        vec![memstrap::config::EncodingType::Ascii, memstrap::config::EncodingType::Gbk],
+        None,
+        false,
+        None,
+    ).unwrap().with_raw_bytes(true);
+
+    let results = extractor.extract_strings(data, 0);
+    assert_eq!(results.len(), 2);
+
+    let options = OutputOptions { emit_raw_hex: true, ..Default::default() };
+    let mut buffer = Vec::new();
+    CsvOutput::write_results_with(&mut buffer, &results, &PathBuf::from("raw.bin"), &options).unwrap();
+
+    let csv_content = String::from_utf8(buffer).unwrap();
+    let mut lines = csv_content.lines();
+    assert!(lines.next().unwrap().ends_with(",RawBytesHex"));
+    let rows: Vec<&str> = lines.collect();
+    assert!(rows.iter().any(|row| row.contains(",ASCII,") && row.ends_with(",69643d3432")));
+    assert!(rows.iter().any(|row| row.contains(",GBK,") && row.ends_with(",c4e3bac3cac0bde7")));
+}
+
+#[test]
+fn test_baseline_suppression() {
+    let mut input = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut input, b"known string\x00fresh string\x00also known\x00").unwrap();
+    let mut baseline = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut baseline, b"known string\r\nalso known\nnot in input\n").unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    let config = Config::parse_from([
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output.path().as_os_str(),
+        "--baseline".as_ref(),
+        baseline.path().as_os_str(),
+        "--quiet".as_ref(),
+    ]);
+    let summary = run(&config).unwrap();
+
+    assert_eq!(summary.baseline_suppressed, 2);
+    assert_eq!(summary.strings_found, 1);
+
+    let csv_content = fs::read_to_string(output.path()).unwrap();
+    assert!(csv_content.contains("fresh string"));
+    assert!(!csv_content.contains("known string"));
+    assert!(!csv_content.contains("also known"));
+}
+
+#[test]
+fn test_tab_printable_by_default() {
+    let parse = |args: &[&str]| Config::parse_from([&["memstrap", "x"], args].concat()).exclude_tab;
+    assert!(!parse(&[]));
+    assert!(parse(&["--exclude-tab"]));
+    assert!(!parse(&["--exclude-tab", "--allow-tab"]));
+
+    let extractor = StringExtractor::new(4, vec![memstrap::config::EncodingType::Ascii], None, false, None).unwrap();
+    let results = extractor.extract_strings(b"key	value ", 0);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].content, "key\tvalue");
+}
+
+#[test]
+fn test_csv_escapes_multiline_strings() {
+    let extractor = StringExtractor::new(
+        4,
+        vec![memstrap::config::EncodingType::Ascii],
+        None,
+        false,
+        None,
+    ).unwrap().with_whitespace(true, true);
+
+    let results = extractor.extract_strings(b"line one\nline\ttwo\x00", 0);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].content, "line one\nline\ttwo");
+
+    let mut buffer = Vec::new();
+    CsvOutput::write_results(&mut buffer, &results, &PathBuf::from("multi.bin")).unwrap();
+    let csv_content = String::from_utf8(buffer).unwrap();
+    assert!(csv_content.contains(",\"line one\nline\ttwo\","));
+
+    // The embedded newline must survive a CSV round trip as a single field
+    let mut reader = csv::Reader::from_reader(csv_content.as_bytes());
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(&record[5], "line one\nline\ttwo");
+}
+
+#[test]
+fn test_xml_output() {
+    let found_strings = vec![
+        FoundString {
+            offset: Offset(0x10),
+            content: "<a href=\"x\">Tom & Jerry's</a>".to_string(),
+            encoding: memstrap::Encoding::Ascii,
+            byte_length: 29,
+            context_before: Some(vec![0x00, 0x41]),
+            context_after: None,
+            raw_bytes: None,
+            wide_offset: None,
+            had_bom: false,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note: None,
+            confidence: None,
+        },
+        FoundString {
+            offset: Offset(0x40),
+            content: "tab\there\x01bell".to_string(),
+            encoding: memstrap::Encoding::Utf16Le,
+            byte_length: 26,
+            context_before: None,
+            context_after: None,
+            raw_bytes: None,
+            wide_offset: None,
+            had_bom: false,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note: None,
+            confidence: None,
+        },
+    ];
+
+    let mut buffer = Vec::new();
+    XmlOutput::write_results(&mut buffer, &found_strings, &PathBuf::from("dump<1>.raw")).unwrap();
+    let xml = String::from_utf8(buffer).unwrap();
+
+    let document = roxmltree::Document::parse(&xml).unwrap();
+    let root = document.root_element();
+    assert_eq!(root.tag_name().name(), "strings");
+    assert_eq!(root.attribute("file"), Some("dump<1>.raw"));
+
+    let strings: Vec<_> = root.children().filter(|n| n.is_element()).collect();
+    assert_eq!(strings.len(), 2);
+    assert_eq!(strings[0].attribute("offset"), Some("0x10"));
+    assert_eq!(strings[0].attribute("encoding"), Some("ASCII"));
+    assert_eq!(strings[0].attribute("length"), Some("29"));
+    assert_eq!(strings[0].attribute("context_before"), Some("0041"));
+    assert_eq!(strings[0].attribute("context_after"), None);
+    assert_eq!(strings[0].text(), Some("<a href=\"x\">Tom & Jerry's</a>"));
+    assert_eq!(strings[1].attribute("encoding"), Some("UTF-16LE"));
+    assert_eq!(strings[1].text(), Some("tab\there\u{FFFD}bell"));
+}
+
+#[test]
+fn test_encoding_all() {
+    use memstrap::config::EncodingType;
+
+    let config = Config::parse_from(["memstrap", "dump.raw", "--encoding", "all"]);
+    assert_eq!(config.get_encodings(), EncodingType::ALL);
+    assert!(config.check_encodings().is_ok());
+
+    // Comma lists and repeated flags still select specific encodings
+    let config = Config::parse_from(["memstrap", "dump.raw", "-e", "utf16le,ascii", "-e", "ascii"]);
+    assert_eq!(config.get_encodings(), [EncodingType::Utf16Le, EncodingType::Ascii]);
+
+    assert!(Config::try_parse_from(["memstrap", "dump.raw", "--encoding", "all,ascii"]).is_err());
+    assert!(Config::try_parse_from(["memstrap", "dump.raw", "-e", "latin1"]).is_err());
+
+    let config = Config::parse_from(["memstrap", "dump.raw", "-e", "all", "-e", "gbk"]);
+    assert!(config.check_encodings().is_err());
+}
+
+#[test]
+fn test_hexdump_context_view() {
+    let found = FoundString {
+        offset: Offset(0x114),
+        content: "key".to_string(),
+        encoding: memstrap::Encoding::Ascii,
+        byte_length: 3,
+        context_before: Some(b"HKLM\\SOFTWARE\\Micr\x00\x01".to_vec()),
+        context_after: Some(b"\x00".to_vec()),
+        raw_bytes: None,
+        wide_offset: None,
+        had_bom: false,
+        partition: None,
+        section: None,
+        section_offset: None,
+        region: None,
+        gap_before: None,
+        decode_note: None,
+        confidence: None,
+    };
+    let options = OutputOptions { context_view: ContextView::Hexdump, ..Default::default() };
+    let mut buffer = Vec::new();
+    CsvOutput::write_results_with(&mut buffer, &[found], &PathBuf::from("dump.raw"), &options).unwrap();
+
+    let mut reader = csv::Reader::from_reader(buffer.as_slice());
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(
+        &record[6],
+        "00000100  48 4b 4c 4d 5c 53 4f 46 54 57 41 52 45 5c 4d 69  |HKLM\\SOFTWARE\\Mi|\n\
+         00000110  63 72 00 01                                      |cr..|"
+    );
+    assert_eq!(&record[7], format!("00000117  00{}  |.|", " ".repeat(45)));
+}
+
+#[test]
+fn test_checkpoint_resume_skips_processed_offsets() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let input = dir.path().join("image.raw");
+    let output = dir.path().join("strings.csv");
+    let checkpoint = dir.path().join("scan.ckpt");
+
+    let mut data = vec![0u8; 256];
+    data[8..20].copy_from_slice(b"early string");
+    data[70..84].copy_from_slice(b"resumed string");
+    data[200..211].copy_from_slice(b"late string");
+    fs::write(&input, &data).unwrap();
+
+    // An interrupted scan got through the first 128-byte window
+    fs::write(&checkpoint, "128\n").unwrap();
+    let previous = "FilePath,Offset(Hex),Offset(Dec),Encoding,Length,Content,ContextBefore,ContextAfter\n\
+                    image.raw,0x8,8,ASCII,12,early string,,\n\
+                    image.raw,0x46,70,ASCII,14,resumed string,,\n";
+    fs::write(&output, previous).unwrap();
+
+    let config = Config::parse_from([
+        "memstrap".as_ref(),
+        input.as_os_str(),
+        "-o".as_ref(),
+        output.as_os_str(),
+        "--checkpoint".as_ref(),
+        checkpoint.as_os_str(),
+        "--window-size".as_ref(),
+        "128".as_ref(),
+        "--overlap".as_ref(),
+        "16".as_ref(),
+        "--quiet".as_ref(),
+    ]);
+    let summary = run(&config).unwrap();
+
+    assert_eq!(summary.bytes_scanned, 128);
+    assert_eq!(summary.strings_found, 1);
+    assert_eq!(fs::read_to_string(&checkpoint).unwrap().trim(), "256");
+
+    let written = fs::read_to_string(&output).unwrap();
+    assert!(written.starts_with(previous));
+    let appended: Vec<&str> = written[previous.len()..].lines().collect();
+    assert_eq!(appended.len(), 1);
+    assert!(appended[0].contains(",0xC8,200,ASCII,11,late string,"));
+
+    // Running again from a finished checkpoint finds nothing new
+    let summary = run(&config).unwrap();
+    assert_eq!(summary.strings_found, 0);
+    assert_eq!(fs::read_to_string(&output).unwrap(), written);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_msgpack_round_trip() {
+    use memstrap::{Encoding, MsgpackOutput};
+
+    let results = vec![
+        FoundString {
+            offset: Offset(0x10),
+            content: r"C:\Windows\explorer.exe".to_string(),
+            encoding: Encoding::Ascii,
+            byte_length: 22,
+            context_before: Some(vec![0, 0]),
+            context_after: None,
+            raw_bytes: None,
+            wide_offset: None,
+            had_bom: false,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note: None,
+            confidence: None,
+        },
+        FoundString {
+            offset: Offset(0x200),
+            content: "你好世界".to_string(),
+            encoding: Encoding::Gbk,
+            byte_length: 8,
+            context_before: None,
+            context_after: None,
+            raw_bytes: Some(vec![0xC4, 0xE3, 0xBA, 0xC3, 0xCA, 0xC0, 0xBD, 0xE7]),
+            wide_offset: None,
+            had_bom: false,
+            partition: None,
+            section: None,
+            section_offset: None,
+            region: None,
+            gap_before: None,
+            decode_note: None,
+            confidence: None,
+        },
+    ];
+
+    let mut buffer = Vec::new();
+    MsgpackOutput::write_results(&mut buffer, &results).unwrap();
+
+    let decoded: Vec<FoundString> = rmp_serde::from_slice(&buffer).unwrap();
+    assert_eq!(decoded, results);
+}
+
+#[test]
+fn test_group_by_encoding() {
+    use memstrap::config::GroupBy;
+    use memstrap::Encoding;
+
+    let found = |offset: u64, content: &str, encoding: Encoding| FoundString {
+        offset: Offset(offset),
+        content: content.to_string(),
+        encoding,
+        byte_length: content.len(),
+        context_before: None,
+        context_after: None,
+        raw_bytes: None,
+        wide_offset: None,
+        had_bom: false,
+        partition: None,
+        section: None,
+        section_offset: None,
+        region: None,
+        gap_before: None,
+        decode_note: None,
+        confidence: None,
+    };
+    let results = vec![
+        found(0x10, "first", Encoding::Ascii),
+        found(0x20, "wide", Encoding::Utf16Le),
+        found(0x40, "second", Encoding::Ascii),
+        found(0x80, "wider", Encoding::Utf16Le),
+        found(0x100, "third", Encoding::Ascii),
+    ];
+
+    let options = OutputOptions { group_by: Some(GroupBy::Encoding), ..Default::default() };
+    let mut buffer = Vec::new();
+    CsvOutput::write_results_with(&mut buffer, &results, &PathBuf::from("dump.raw"), &options).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+
+    let lines: Vec<&str> = output.lines().skip(1).collect();
+    let markers: Vec<&str> = lines.iter().copied().filter(|l| l.starts_with('#')).collect();
+    assert_eq!(markers, ["# Encoding: ASCII (3 strings)", "# Encoding: UTF-16LE (2 strings)"]);
+
+    let contents: Vec<&str> = lines
+        .iter()
+        .filter(|l| !l.starts_with('#'))
+        .map(|l| l.split(',').nth(5).unwrap())
+        .collect();
+    assert_eq!(contents, ["first", "second", "third", "wide", "wider"]);
+}
+
+#[test]
+fn test_multiple_input_files() {
+    let mut first = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut first, b"\x00first dump string\x00").unwrap();
+    let mut second = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut second, b"\x00second one\x00another\x00").unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    let scan = |format: &str| {
+        let config = Config::parse_from([
+            "memstrap".as_ref(),
+            first.path().as_os_str(),
+            second.path().as_os_str(),
+            "-o".as_ref(),
+            output.path().as_os_str(),
+            "-f".as_ref(),
+            format.as_ref(),
+            "--quiet".as_ref(),
+        ]);
+        let summary = run(&config).unwrap();
+        (summary, fs::read_to_string(output.path()).unwrap())
+    };
+
+    let (summary, csv) = scan("csv");
+    assert_eq!(summary.strings_found, 3);
+    assert_eq!(summary.bytes_scanned, 19 + 20);
+    let per_file: Vec<(&std::path::Path, usize)> = summary
+        .per_file
+        .iter()
+        .map(|(path, file)| (path.as_path(), file.strings_found))
+        .collect();
+    assert_eq!(per_file, [(first.path(), 1), (second.path(), 2)]);
+
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let rows: Vec<(String, String)> = reader
+        .records()
+        .map(|r| r.unwrap())
+        .map(|r| (r[0].to_string(), r[5].to_string()))
+        .collect();
+    let first_path = first.path().to_string_lossy().into_owned();
+    let second_path = second.path().to_string_lossy().into_owned();
+    assert_eq!(
+        rows,
+        [
+            (first_path.clone(), "first dump string".to_string()),
+            (second_path.clone(), "second one".to_string()),
+            (second_path.clone(), "another".to_string()),
+        ]
+    );
+
+    // XML gets one <strings> element per input under a <scan> root
+    let (_, xml) = scan("xml");
+    let doc = roxmltree::Document::parse(&xml).unwrap();
+    assert_eq!(doc.root_element().tag_name().name(), "scan");
+    let files: Vec<&str> = doc
+        .root_element()
+        .children()
+        .filter(|n| n.is_element())
+        .map(|n| n.attribute("file").unwrap())
+        .collect();
+    assert_eq!(files, [first_path.as_str(), second_path.as_str()]);
+}
+
+#[test]
+fn test_correlate_wide_kernel32() {
+    let mut data = b"\x00\x00Kernel32\x00\x00\x00\x00".to_vec();
+    data.extend("Kernel32".encode_utf16().flat_map(|u| u.to_le_bytes()));
+    data.extend_from_slice(&[0, 0]);
+
+    let extractor = StringExtractor::new(
+        4,
+        vec![memstrap::config::EncodingType::Ascii, memstrap::config::EncodingType::Utf16Le],
+        None,
+        false,
+        None,
+    ).unwrap();
+    let mut results = extractor.extract_strings(&data, 0);
+    assert_eq!(results.len(), 2);
+
+    assert_eq!(memstrap::postprocess::correlate_wide(&mut results, 64), 1);
+    assert_eq!(results.len(), 1);
+
+    let options = OutputOptions { wide_offset: true, ..Default::default() };
+    let mut buffer = Vec::new();
+    CsvOutput::write_results_with(&mut buffer, &results, &PathBuf::from("dump.raw"), &options).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[0].ends_with(",WideOffset"));
+    assert_eq!(lines[1], "dump.raw,0x2,2,ASCII,8,Kernel32,,,0xE");
+}
+
+#[test]
+fn test_csv_delimiter_and_quote_style() {
+    use memstrap::config::QuoteStyle;
+
+    let results = vec![FoundString {
+        offset: Offset(0x10),
+        content: "a,b\tc".to_string(),
+        encoding: memstrap::Encoding::Ascii,
+        byte_length: 5,
+        context_before: None,
+        context_after: None,
+        raw_bytes: None,
+        wide_offset: None,
+        had_bom: false,
+        partition: None,
+        section: None,
+        section_offset: None,
+        region: None,
+        gap_before: None,
+        decode_note: None,
+        confidence: None,
+    }];
+    let write = |options: OutputOptions| {
+        let mut buffer = Vec::new();
+        CsvOutput::write_results_with(&mut buffer, &results, &PathBuf::from("dump.raw"), &options).unwrap();
+        String::from_utf8(buffer).unwrap()
+    };
+
+    let tsv = write(OutputOptions { delimiter: b'\t', ..Default::default() });
+    assert_eq!(
+        tsv,
+        "FilePath\tOffset(Hex)\tOffset(Dec)\tEncoding\tLength\tContent\tContextBefore\tContextAfter\n\
+         dump.raw\t0x10\t16\tASCII\t5\t\"a,b\tc\"\t\t\n"
+    );
+
+    let quoted = write(OutputOptions { quote_style: QuoteStyle::Always, ..Default::default() });
+    assert_eq!(
+        quoted.lines().nth(1).unwrap(),
+        "\"dump.raw\",\"0x10\",\"16\",\"ASCII\",\"5\",\"a,b\tc\",\"\",\"\""
+    );
+
+    assert_eq!(Config::parse_from(["memstrap", "x", "--delimiter", "tab"]).delimiter, b'\t');
+    assert_eq!(Config::parse_from(["memstrap", "x", "--delimiter", r"\t"]).delimiter, b'\t');
+    assert_eq!(Config::parse_from(["memstrap", "x", "--delimiter", ";"]).delimiter, b';');
+    assert!(Config::try_parse_from(["memstrap", "x", "--delimiter", "::"]).is_err());
+    assert!(Config::try_parse_from(["memstrap", "x", "--delimiter", "§"]).is_err());
+}
+
+#[test]
+fn test_dry_run_does_not_scan() {
+    let mut input = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut input, b"\x00\x00First string\x00").unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+    let output_path = output_dir.path().join("out.csv");
+
+    let config = Config::parse_from([
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output_path.as_os_str(),
+        "--dry-run".as_ref(),
+    ]);
+    let summary = run(&config).unwrap();
+
+    assert_eq!(summary.bytes_scanned, 0);
+    assert!(!output_path.exists());
+}
+
+#[test]
+fn test_count_only_matches_full_extraction() {
+    let mut data = b"\x00\x00First string\x00\x00second one\x00\x00no\x00\x00\x00\x00".to_vec();
+    data.extend("wide string".encode_utf16().flat_map(u16::to_le_bytes));
+    data.extend_from_slice(b"\x00\x00\x00\x00third string\x00");
+    let mut input = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut input, &data).unwrap();
+
+    let extractor = StringExtractor::new(4, vec![memstrap::config::EncodingType::Ascii, memstrap::config::EncodingType::Utf16Le], None, false, None).unwrap();
+    let expected = extractor.extract_strings(&data, 0).len();
+    assert_eq!(expected, 4);
+
+    // Copied past the 16 MiB boundary of two chunks, into the first one's overlap, each string still counts once
+    let boundary = 16 * 1024 * 1024;
+    let mut chunked = vec![0u8; 2 * boundary];
+    chunked[boundary + 2..boundary + 2 + data.len()].copy_from_slice(&data);
+    let mut chunked_input = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut chunked_input, &chunked).unwrap();
+
+    for (input, threads) in [(&input, "1"), (&chunked_input, "2")] {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_memstrap"))
+            .arg(input.path())
+            .args(["-e", "ascii,utf16le", "--count-only", "-j", threads])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains(&format!("  Total: {}\n", expected)), "{}", stdout);
+        assert!(stdout.contains("  ASCII: 3\n"), "{}", stdout);
+        assert!(stdout.contains("  UTF-16LE: 1\n"), "{}", stdout);
+    }
+}
+
+#[test]
+fn test_compressed_output() {
+    use std::io::Read;
+
+    let mut input = NamedTempFile::new().unwrap();
+    input.write_all(b"\x00\x00First string\x00\x00second one\x00\x00third string\x00").unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+    let write = |name: &str, extra: &[&str]| {
+        let output_path = output_dir.path().join(name);
+        let mut args: Vec<&std::ffi::OsStr> = vec!["memstrap".as_ref(), input.path().as_os_str(), "-o".as_ref(), output_path.as_os_str(), "-q".as_ref()];
+        args.extend(extra.iter().map(std::ffi::OsStr::new));
+        run(&Config::parse_from(args)).unwrap();
+    };
+
+    write("plain.csv", &[]);
+    let expected = fs::read_to_string(output_dir.path().join("plain.csv")).unwrap();
+    assert!(expected.contains("second one"));
+
+    for stream in [false, true] {
+        let extra: &[&str] = if stream { &["--stream"] } else { &[] };
+        write("out.csv", &[&["--compress", "gzip"], extra].concat());
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(fs::File::open(output_dir.path().join("out.csv.gz")).unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, expected, "gzip, stream: {}", stream);
+
+        write("out.csv", &[&["--compress", "zstd"], extra].concat());
+        let decoded = zstd::decode_all(fs::File::open(output_dir.path().join("out.csv.zst")).unwrap()).unwrap();
+        assert_eq!(String::from_utf8(decoded).unwrap(), expected, "zstd, stream: {}", stream);
+    }
+    assert!(!output_dir.path().join("out.csv").exists());
+
+    // Compression only applies to file output
+    assert!(Config::try_parse_from(["memstrap", "x", "--compress", "gzip"]).is_err());
+}
+
+#[cfg(feature = "ndjson")]
+#[test]
+fn test_ndjson_records_carry_source_hash() {
+    let data = b"\x00\x00First string\x00\x00second one\x00\x00third string\x00";
+    let mut input = NamedTempFile::new().unwrap();
+    input.write_all(data).unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+    let output_path = output_dir.path().join("out.ndjson");
+
+    let config = Config::parse_from([
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output_path.as_os_str(),
+        "--format=ndjson".as_ref(),
+        "--emit-hash".as_ref(),
+        "-q".as_ref(),
+    ]);
+    run(&config).unwrap();
+
+    let expected = memstrap::hash::sha256_hex(data);
+    let output = fs::read_to_string(&output_path).unwrap();
+    let records: Vec<serde_json::Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[1]["content"], "second one");
+    assert_eq!(records[1]["file_path"], input.path().to_str().unwrap());
+    for record in &records {
+        assert_eq!(record["source_sha256"], expected.as_str());
+    }
+
+    // Without --emit-hash the field is left out
+    let config = Config::parse_from([
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output_path.as_os_str(),
+        "--format=ndjson".as_ref(),
+        "-q".as_ref(),
+    ]);
+    run(&config).unwrap();
+    assert!(!fs::read_to_string(&output_path).unwrap().contains("source_sha256"));
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_parquet_output_row_count() {
+    use memstrap::ParquetOutput;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let extractor = StringExtractor::new(4, vec![memstrap::config::EncodingType::Ascii], None, false, Some(4)).unwrap();
+    let results = extractor.extract_strings(b"\x00first\x00\x00second\x00\x00third one\x00", 0);
+    assert_eq!(results.len(), 3);
+
+    let output = NamedTempFile::new().unwrap();
+    ParquetOutput::write_results(output.reopen().unwrap(), &results).unwrap();
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(output.reopen().unwrap()).unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+    assert_eq!(reader.schema().as_ref(), &ParquetOutput::schema());
+
+    let rows: usize = reader.build().unwrap().map(|batch| batch.unwrap().num_rows()).sum();
+    assert_eq!(rows, 3);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_parquet_requires_output() {
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), b"some text\x00").unwrap();
+    let config = Config::parse_from(["memstrap", input.path().to_str().unwrap(), "--format", "parquet"]);
+    let error = config.check_inputs().unwrap_err();
+    assert_eq!(error.to_string(), "Configuration error: parquet output requires --output");
+}
+
+#[test]
+fn test_emit_hash_known_vector() {
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    let mut input = NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut input, b"abc").unwrap();
+    let output = NamedTempFile::new().unwrap();
+    let scan = |extra: &[&str]| {
+        let mut args = vec![
+            "memstrap".as_ref(),
+            input.path().as_os_str(),
+            "-o".as_ref(),
+            output.path().as_os_str(),
+            "-n".as_ref(),
+            "3".as_ref(),
+            "--emit-hash".as_ref(),
+            "--quiet".as_ref(),
+        ];
+        args.extend(extra.iter().map(|arg| std::ffi::OsStr::new(*arg)));
+        let summary = run(&Config::parse_from(args)).unwrap();
+        assert_eq!(summary.per_file[0].1.sha256.as_deref(), Some(ABC_SHA256));
+        fs::read_to_string(output.path()).unwrap()
+    };
+
+    let trailer = format!("# SHA256 ({}) = {}\n", input.path().display(), ABC_SHA256);
+    for extra in [&[][..], &["--stream"][..]] {
+        let csv = scan(extra);
+        assert_eq!(csv.lines().count(), 3, "{:?}", extra);
+        assert!(csv.ends_with(&trailer), "{:?}: {}", extra, csv);
+    }
+
+    let xml = scan(&["--format", "xml"]);
+    let doc = roxmltree::Document::parse(&xml).unwrap();
+    assert_eq!(doc.root_element().attribute("sha256"), Some(ABC_SHA256));
+}
+
+#[test]
+fn test_empty_file_writes_header_only() {
+    let input = NamedTempFile::new().unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    for extra in [None, Some("--no-mmap"), Some("--stream")] {
+        let mut args = vec![
+            "memstrap".as_ref(),
+            input.path().as_os_str(),
+            "-o".as_ref(),
+            output.path().as_os_str(),
+            "--quiet".as_ref(),
+        ];
+        args.extend(extra.map(std::ffi::OsStr::new));
+        let summary = run(&Config::parse_from(args)).unwrap();
+
+        assert_eq!((summary.bytes_scanned, summary.strings_found), (0, 0), "{:?}", extra);
+        let csv = fs::read_to_string(output.path()).unwrap();
+        assert_eq!(
+            csv.lines().collect::<Vec<_>>(),
+            ["FilePath,Offset(Hex),Offset(Dec),Encoding,Length,Content,ContextBefore,ContextAfter"],
+            "{:?}",
+            extra
+        );
+    }
+}
+
+#[test]
+fn test_missing_and_non_regular_inputs() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.raw");
+
+    let err = run(&Config::parse_from(["memstrap".as_ref(), missing.as_os_str()])).unwrap_err();
+    assert!(matches!(&err, MemstrapError::FileNotFound(path) if *path == missing));
+    assert_eq!(err.exit_code(), 2);
+
+    let err = run(&Config::parse_from(["memstrap".as_ref(), dir.path().as_os_str()])).unwrap_err();
+    assert!(matches!(&err, MemstrapError::NotRegularFile(path) if path == dir.path()));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_fifo_input_is_read_buffered() {
+    let dir = tempfile::tempdir().unwrap();
+    let fifo = dir.path().join("dump.fifo");
+    let status = std::process::Command::new("mkfifo").arg(&fifo).status().unwrap();
+    assert!(status.success());
+
+    let err = run(&Config::parse_from(["memstrap".as_ref(), fifo.as_os_str(), "--stream".as_ref()])).unwrap_err();
+    assert!(matches!(err, MemstrapError::Config(_)));
+
+    let writer = {
+        let fifo = fifo.clone();
+        std::thread::spawn(move || fs::write(fifo, b"\x00\x01piped password\x00\x02").unwrap())
+    };
+    let output = NamedTempFile::new().unwrap();
+    let summary = run(&Config::parse_from([
+        "memstrap".as_ref(),
+        fifo.as_os_str(),
+        "-o".as_ref(),
+        output.path().as_os_str(),
+        "--quiet".as_ref(),
+    ]))
+    .unwrap();
+    writer.join().unwrap();
+
+    assert_eq!(summary.bytes_scanned, 18);
+    assert!(fs::read_to_string(output.path()).unwrap().contains("piped password"));
+}
+
+#[test]
+fn test_stream_output_with_tiny_buffer() {
+    let mut input = NamedTempFile::new().unwrap();
+    for i in 0..500 {
+        write!(input, "\x00streamed string number {:04}\x00", i).unwrap();
+    }
+    input.flush().unwrap();
+
+    let scan = |buffer_kb: &str| {
+        let output = NamedTempFile::new().unwrap();
+        let summary = run(&Config::parse_from([
+            "memstrap".as_ref(),
+            input.path().as_os_str(),
+            "-o".as_ref(),
+            output.path().as_os_str(),
+            "--stream".as_ref(),
+            "--window-size".as_ref(),
+            "4096".as_ref(),
+            "--overlap".as_ref(),
+            "64".as_ref(),
+            "--output-buffer".as_ref(),
+            buffer_kb.as_ref(),
+            "--quiet".as_ref(),
+        ]))
+        .unwrap();
+        (summary.strings_found, fs::read_to_string(output.path()).unwrap())
+    };
+
+    // A 1 KB buffer is far smaller than the ~20 KB of rows, forcing many flushes
+    let (found, tiny) = scan("1");
+    assert_eq!(found, 500);
+    assert_eq!(tiny.lines().count(), 501);
+    assert!(tiny.contains("streamed string number 0499"));
+    assert_eq!(tiny, scan("1024").1);
+}
+
+#[test]
+fn test_interrupted_scan_writes_partial_results() {
+    let mut data = vec![0u8; 1024];
+    for (i, window) in data.chunks_mut(256).enumerate() {
+        window[8..21].copy_from_slice(format!("window {} text", i).as_bytes());
+    }
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), &data).unwrap();
+    let output = NamedTempFile::new().unwrap();
+    let args = |extra: &[&str]| {
+        let mut args = vec!["memstrap", input.path().to_str().unwrap(), "-o", output.path().to_str().unwrap(), "--quiet"];
+        args.extend(extra);
+        Config::parse_from(args)
+    };
+
+    // The flag is raised while the second of four windows is processed
+    let checks = std::sync::atomic::AtomicUsize::new(0);
+    let stop_after_second = || checks.fetch_add(1, std::sync::atomic::Ordering::Relaxed) >= 1;
+    let config = args(&["--stream", "--window-size", "256", "--overlap", "32", "--emit-hash"]);
+    let summary = run_until(&config, &stop_after_second).unwrap();
+
+    assert!(summary.interrupted);
+    assert_eq!(summary.strings_found, 2);
+    let csv = fs::read_to_string(output.path()).unwrap();
+    assert!(csv.contains("window 0 text") && csv.contains("window 1 text"));
+    assert!(!csv.contains("window 2 text"));
+    assert!(!csv.contains("SHA256"));
+
+    // In-memory scans skip the remaining chunks but still write the header
+    let summary = run_until(&args(&[]), &|| true).unwrap();
+    assert!(summary.interrupted);
+    assert_eq!(summary.strings_found, 0);
+    assert_eq!(fs::read_to_string(output.path()).unwrap().lines().count(), 1);
+}
+
+#[test]
+fn test_base_offset_shifts_reported_offsets() {
+    let data = b"\x00\x00First string\x00\x00Second string\x00";
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), data).unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    let expected: Vec<String> = StringExtractor::new(4, vec![memstrap::config::EncodingType::Ascii], None, false, None)
+        .unwrap()
+        .extract_strings(data, 0x10_0000)
+        .iter()
+        .map(|s| format!("{},{}", s.offset.hex(), s.offset.dec()))
+        .collect();
+    assert_eq!(expected, ["0x100002,1048578", "0x100010,1048592"]);
+
+    for extra in [None, Some("--stream")] {
+        let mut args = vec![
+            "memstrap".as_ref(),
+            input.path().as_os_str(),
+            "-o".as_ref(),
+            output.path().as_os_str(),
+            "-e".as_ref(),
+            "ascii".as_ref(),
+            "--base-offset".as_ref(),
+            "0x100000".as_ref(),
+            "--quiet".as_ref(),
+        ];
+        args.extend(extra.map(std::ffi::OsStr::new));
+        run(&Config::parse_from(args)).unwrap();
+
+        let csv = fs::read_to_string(output.path()).unwrap();
+        let offsets: Vec<String> = csv
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').skip(1).take(2).collect::<Vec<_>>().join(","))
+            .collect();
+        assert_eq!(offsets, expected, "{:?}", extra);
+    }
+}
+
+#[test]
+fn test_consolidate_merges_encodings_of_same_content() {
+    let mut data = b"\x00\x00Test\x00\x00\x00\x00".to_vec();
+    data.extend("Test".encode_utf16().flat_map(u16::to_le_bytes));
+    data.extend_from_slice(b"\x00\x00Test\x00");
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), &data).unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    let config = Config::parse_from([
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output.path().as_os_str(),
+        "-e".as_ref(),
+        "ascii,utf16le".as_ref(),
+        "--consolidate".as_ref(),
+        "--quiet".as_ref(),
+    ]);
+    run(&config).unwrap();
+
+    let csv = fs::read_to_string(output.path()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "FilePath,Content,Count,Encodings,Offsets");
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].ends_with(",Test,3,ASCII;UTF-16LE,0x2;0xA;0x14"), "{}", lines[1]);
+}
+
+#[test]
+fn test_color_highlights_search_matches() {
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), b"\x00my password is PASSWORD\x00no match here\x00").unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    // Console output, through a pipe
+    let print = |extra: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_memstrap")).arg(input.path()).arg("-q").args(extra).output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let csv = print(&["-s", "password", "--color", "always"]);
+    assert!(csv.contains(",my \x1b[1;31mpassword\x1b[0m is PASSWORD,"), "{:?}", csv);
+
+    let csv = print(&["-s", "(?i)pass(word)", "-r", "--color", "always"]);
+    assert!(csv.contains(",my \x1b[1;31mpassword\x1b[0m is \x1b[1;31mPASSWORD\x1b[0m,"), "{:?}", csv);
+
+    for color in ["auto", "never"] {
+        assert!(!print(&["-s", "password", "--color", color]).contains('\x1b'));
+    }
+
+    // Files never get escape codes in their content, whatever --color says
+    let scan = |extra: &[&str]| {
+        let mut args = vec!["memstrap", input.path().to_str().unwrap(), "-o", output.path().to_str().unwrap(), "-q"];
+        args.extend(extra);
+        run(&Config::parse_from(args)).unwrap();
+        fs::read_to_string(output.path()).unwrap()
+    };
+    for extra in [&["--color", "always"][..], &["--color", "always", "--stream"], &["--color", "always", "-r"]] {
+        let csv = scan(&[&["-s", "password"][..], extra].concat());
+        assert!(csv.contains(",my password is PASSWORD,"), "{:?}", csv);
+        assert!(!csv.contains("\x1b["), "{:?}", csv);
+    }
+}
+
+#[test]
+fn test_partition_table_reports_partition_relative_offsets() {
+    // MBR with one Linux partition covering sectors 8-15
+    let mut image = vec![0u8; 16 * 512];
+    image[446 + 4] = 0x83;
+    image[446 + 8..446 + 12].copy_from_slice(&8u32.to_le_bytes());
+    image[446 + 12..446 + 16].copy_from_slice(&8u32.to_le_bytes());
+    image[510..512].copy_from_slice(&[0x55, 0xAA]);
+    image[4096 + 0x100..4096 + 0x10E].copy_from_slice(b"PartitionKnown");
+    // Unpartitioned space is not scanned
+    image[2048..2060].copy_from_slice(b"Unallocated!");
+
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), &image).unwrap();
+    let output = NamedTempFile::new().unwrap();
+    let args = [
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output.path().as_os_str(),
+        "-e".as_ref(),
+        "ascii".as_ref(),
+        "--partition-table".as_ref(),
+        "--quiet".as_ref(),
+    ];
+    run(&Config::parse_from(args)).unwrap();
+
+    let csv = fs::read_to_string(output.path()).unwrap();
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().ends_with(",Partition"));
+    let rows: Vec<&str> = lines.collect();
+    assert_eq!(rows.len(), 1, "{}", csv);
+    assert!(rows[0].ends_with(",0x100,256,ASCII,14,PartitionKnown,,,1"), "{}", rows[0]);
+
+    // A region map covers the whole image, not each partition
+    let map = NamedTempFile::new().unwrap();
+    fs::write(map.path(), "0x0,0x200,boot sector\n0x1000,0x2000,partition one\n").unwrap();
+    let args = [
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output.path().as_os_str(),
+        "-e".as_ref(),
+        "ascii".as_ref(),
+        "--partition-table".as_ref(),
+        "--region-map".as_ref(),
+        map.path().as_os_str(),
+        "--quiet".as_ref(),
+    ];
+    run(&Config::parse_from(args)).unwrap();
+    let csv = fs::read_to_string(output.path()).unwrap();
+    assert!(csv.lines().nth(1).unwrap().ends_with(",PartitionKnown,,,1,partition one"), "{}", csv);
+
+    // Flat scanning remains the default
+    let args = [
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output.path().as_os_str(),
+        "-e".as_ref(),
+        "ascii".as_ref(),
+        "--quiet".as_ref(),
+    ];
+    run(&Config::parse_from(args)).unwrap();
+    let csv = fs::read_to_string(output.path()).unwrap();
+    assert!(csv.contains(",0x800,2048,ASCII,12,Unallocated!,,"));
+    assert!(csv.contains(",0x1100,4352,ASCII,14,PartitionKnown,,"));
+
+    let blank = NamedTempFile::new().unwrap();
+    fs::write(blank.path(), [0u8; 1024]).unwrap();
+    let args = ["memstrap".as_ref(), blank.path().as_os_str(), "--partition-table".as_ref(), "--quiet".as_ref()];
+    assert!(matches!(run(&Config::parse_from(args)), Err(MemstrapError::PartitionTable(_))));
+}
+
+#[test]
+fn test_skip_header_leaves_header_unscanned() {
+    let mut first = vec![0u8; 64];
+    first[4..16].copy_from_slice(b"HeaderMagic!");
+    first[40..52].copy_from_slice(b"BodyString-1");
+    let mut second = vec![0u8; 64];
+    second[0..12].copy_from_slice(b"OtherHeader!");
+    second[30..42].copy_from_slice(b"BodyString-2");
+    let inputs = [NamedTempFile::new().unwrap(), NamedTempFile::new().unwrap()];
+    fs::write(inputs[0].path(), &first).unwrap();
+    fs::write(inputs[1].path(), &second).unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    for extra in [None, Some("--stream")] {
+        let mut args = vec![
+            "memstrap".as_ref(),
+            inputs[0].path().as_os_str(),
+            inputs[1].path().as_os_str(),
+            "-o".as_ref(),
+            output.path().as_os_str(),
+            "-e".as_ref(),
+            "ascii".as_ref(),
+            "--skip-header".as_ref(),
+            "0x10".as_ref(),
+            "--quiet".as_ref(),
+        ];
+        args.extend(extra.map(std::ffi::OsStr::new));
+        run(&Config::parse_from(args)).unwrap();
+
+        let csv = fs::read_to_string(output.path()).unwrap();
+        let rows: Vec<String> = csv
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').skip(1).take(5).collect::<Vec<_>>().join(","))
+            .collect();
+        assert_eq!(rows, ["0x28,40,ASCII,12,BodyString-1", "0x1E,30,ASCII,12,BodyString-2"], "{:?}", extra);
+    }
+}
+
+#[test]
+fn test_decode_base64_columns() {
+    let data = b"\x00cG93ZXJzaGVsbCAtZW5jIEpBQjFBSE1BWlFCeUFBPT0=\x00Constantinopolitanus\x00";
+    let results = StringExtractor::new(4, vec![memstrap::config::EncodingType::Ascii], None, false, None)
+        .unwrap()
+        .extract_strings(data, 0);
+    assert_eq!(results.len(), 2);
+
+    let options = OutputOptions { classify: true, decode_base64: true, ..Default::default() };
+    let mut buffer = Vec::new();
+    CsvOutput::write_results_with(&mut buffer, &results, &PathBuf::from("blob.bin"), &options).unwrap();
+
+    let csv_content = String::from_utf8(buffer).unwrap();
+    let mut lines = csv_content.lines();
+    assert!(lines.next().unwrap().ends_with(",Kind,Base64Hex,Base64Preview"));
+    let expected_hex = hex::encode("powershell -enc JAB1AHMAZQByAA==");
+    assert!(lines
+        .next()
+        .unwrap()
+        .ends_with(&format!(",Base64,{},powershell -enc JAB1AHMAZQByAA==", expected_hex)));
+    assert!(lines.next().unwrap().ends_with(",Constantinopolitanus,,,,,"));
+}
+
+#[test]
+fn test_sample_rate_scans_half_the_windows() {
+    let mut data = vec![0u8; 10 * 64];
+    for (i, window) in data.chunks_mut(64).enumerate() {
+        window[8..21].copy_from_slice(format!("window {} text", i).as_bytes());
+    }
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), &data).unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    let summary = run(&Config::parse_from([
+        "memstrap",
+        input.path().to_str().unwrap(),
+        "-o",
+        output.path().to_str().unwrap(),
+        "--stream",
+        "--window-size",
+        "64",
+        "--overlap",
+        "16",
+        "--sample-rate",
+        "0.5",
+        "--quiet",
+    ]))
+    .unwrap();
+
+    // Every other window is scanned, and the output says so
+    assert_eq!(summary.strings_found, 5);
+    let csv = fs::read_to_string(output.path()).unwrap();
+    let mut lines = csv.lines().skip(1);
+    assert_eq!(lines.next(), Some("# Sampled scan: 50% of chunks scanned, results are incomplete"));
+    for (line, window) in lines.zip([0, 2, 4, 6, 8]) {
+        assert!(line.contains(&format!("window {} text", window)), "{}", line);
+    }
+}
+
+#[test]
+fn test_split_by_encoding_writes_one_file_per_encoding() {
+    let mut data = b"\x00\x00ascii string one\x00\x00\x00\x00".to_vec();
+    data.extend("wide string".encode_utf16().flat_map(u16::to_le_bytes));
+    data.extend(b"\x00\x00\x00\x00ascii string two\x00");
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), &data).unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+    let output_path = output_dir.path().join("out.csv");
+
+    run(&Config::parse_from([
+        "memstrap".as_ref(),
+        input.path().as_os_str(),
+        "-o".as_ref(),
+        output_path.as_os_str(),
+        "-e".as_ref(),
+        "ascii,utf8,utf16le".as_ref(),
+        "--split-by-encoding".as_ref(),
+        "--quiet".as_ref(),
+    ]))
+    .unwrap();
+
+    assert!(!output_path.exists());
+    let read = |tag: &str| fs::read_to_string(output_dir.path().join(format!("out.{}.csv", tag))).unwrap();
+
+    let ascii = read("ascii");
+    let lines: Vec<&str> = ascii.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("FilePath,"));
+    assert!(lines[1].contains(",ASCII,") && lines[1].contains("ascii string one"));
+    assert!(lines[2].contains(",ASCII,") && lines[2].contains("ascii string two"));
+
+    let utf16le = read("utf16le");
+    let lines: Vec<&str> = utf16le.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].contains(",UTF-16LE,") && lines[1].contains("wide string"));
+
+    // An encoding that found nothing still gets a file with just the header
+    let utf8 = read("utf8");
+    assert_eq!(utf8.lines().count(), 1);
+    assert_eq!(utf8.lines().next(), lines.first().copied());
+}
+
+#[test]
+fn test_merge_adjacent_joins_fragments() {
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), b"\x00Hello\x01World\x00\x00\x00\x00\x00Separate\x00").unwrap();
+    let output = NamedTempFile::new().unwrap();
+    let scan = |extra: &[&str]| {
+        let mut args = vec!["memstrap", input.path().to_str().unwrap(), "-o", output.path().to_str().unwrap(), "-e", "ascii", "--quiet"];
+        args.extend(extra);
+        let summary = run(&Config::parse_from(args)).unwrap();
+        let csv = fs::read_to_string(output.path()).unwrap();
+        let rows: Vec<String> = csv.lines().skip(1).map(|line| line.split(',').skip(1).take(5).collect::<Vec<_>>().join(",")).collect();
+        (summary, rows)
+    };
+
+    let (_, rows) = scan(&[]);
+    assert_eq!(rows, ["0x1,1,ASCII,5,Hello", "0x7,7,ASCII,5,World", "0x11,17,ASCII,8,Separate"]);
+
+    let (summary, rows) = scan(&["--merge-adjacent", "1"]);
+    assert_eq!(summary.fragments_merged, 1);
+    assert_eq!(rows, ["0x1,1,ASCII,11,HelloWorld", "0x11,17,ASCII,8,Separate"]);
+}
+
+#[test]
+fn test_reverse_reconstructs_string_before_known_terminator() {
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), b"\x90\x90\x01C:\\Windows\\evil.exe\x00\x90\x90tail string\x00").unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    // The terminator after "evil.exe" is at 0x16; 0x100 is past the end of the input
+    let summary = run(&Config::parse_from([
+        "memstrap",
+        input.path().to_str().unwrap(),
+        "-o",
+        output.path().to_str().unwrap(),
+        "--reverse",
+        "0x16,0x100",
+        "--quiet",
+    ]))
+    .unwrap();
+
+    assert_eq!(summary.strings_found, 1);
+    let csv = fs::read_to_string(output.path()).unwrap();
+    let rows: Vec<String> = csv.lines().skip(1).map(|line| line.split(',').skip(1).take(5).collect::<Vec<_>>().join(",")).collect();
+    assert_eq!(rows, ["0x3,3,ASCII,19,C:\\Windows\\evil.exe"]);
+}
+
+/// Run the full extraction over `tests/fixtures/<name>.bin` and compare the CSV with `tests/golden/<name>.csv`
+///
+/// The FilePath column is reduced to the fixture's file name so the comparison
+/// does not depend on where the crate is checked out. After an intended output
+/// change, run the tests with `MEMSTRAP_BLESS=1` to rewrite the golden files.
+fn assert_matches_golden_csv(name: &str, extra_args: &[&str]) {
+    let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let fixture = tests_dir.join("fixtures").join(format!("{}.bin", name));
+    let golden = tests_dir.join("golden").join(format!("{}.csv", name));
+    let fixture_path = fixture.to_str().unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    let mut args = vec!["memstrap", fixture_path, "-o", output.path().to_str().unwrap(), "--quiet"];
+    args.extend(extra_args);
+    run(&Config::parse_from(args)).unwrap();
+
+    let mut reader = csv::ReaderBuilder::new().has_headers(false).from_path(output.path()).unwrap();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let file_name = format!("{}.bin", name);
+    for record in reader.records() {
+        let record = record.unwrap();
+        let fields = record.iter().map(|field| if field == fixture_path { file_name.as_str() } else { field });
+        writer.write_record(fields).unwrap();
+    }
+    let actual = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+    if std::env::var_os("MEMSTRAP_BLESS").is_some() {
+        fs::create_dir_all(golden.parent().unwrap()).unwrap();
+        fs::write(&golden, &actual).unwrap();
+    }
+    let expected = fs::read_to_string(&golden).unwrap();
+    assert_eq!(actual, expected, "output differs from {}; rerun with MEMSTRAP_BLESS=1 if the change is intended", golden.display());
+}
+
+#[test]
+fn test_golden_csv_ascii() {
+    assert_matches_golden_csv("ascii", &[]);
+}
+
+#[test]
+fn test_golden_csv_mixed_encodings() {
+    // Every encoding, with context and decode notes to cover the optional columns too
+    assert_matches_golden_csv("mixed", &["-e", "all", "-C", "4", "--emit-decode-notes"]);
+}
+
+#[cfg(feature = "binary-parse")]
+#[test]
+fn test_sections_scan_only_named_elf_sections() {
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rodata.elf");
+    let scan = |input: &str| {
+        let output = NamedTempFile::new().unwrap();
+        let args = ["memstrap", input, "-o", output.path().to_str().unwrap(), "--sections", ".rodata,.data", "--quiet"];
+        run(&Config::parse_from(args)).unwrap();
+        let csv = fs::read_to_string(output.path()).unwrap();
+        csv.lines().map(|line| line.split(',').skip(1).collect::<Vec<_>>().join(",")).collect::<Vec<_>>()
+    };
+
+    // Only .rodata is scanned: absolute offset first, section-relative offset last
+    let rows = scan(fixture.to_str().unwrap());
+    assert_eq!(rows[0], "Offset(Hex),Offset(Dec),Encoding,Length,Content,ContextBefore,ContextAfter,Section,SectionOffset");
+    assert_eq!(rows[1..], ["0x64,100,ASCII,16,secret in rodata,,,.rodata,0x4"]);
+
+    // Anything else is scanned whole
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), b"\x00not an executable\x00").unwrap();
+    let rows = scan(input.path().to_str().unwrap());
+    assert_eq!(rows[1..], ["0x1,1,ASCII,17,not an executable,,,,"]);
+}
+
+#[test]
+fn test_min_len_unit_counts_utf16_bytes() {
+    let input = NamedTempFile::new().unwrap();
+    let mut data = b"\x00\x00".to_vec();
+    data.extend("wide".encode_utf16().flat_map(u16::to_le_bytes));
+    data.extend_from_slice(b"\x00\x00");
+    fs::write(input.path(), data).unwrap();
+    let output = NamedTempFile::new().unwrap();
+    let scan = |unit: &str| {
+        let args = ["memstrap", input.path().to_str().unwrap(), "-o", output.path().to_str().unwrap(), "-e", "utf16le", "-n", "6", "--min-len-unit", unit, "--quiet"];
+        run(&Config::parse_from(args)).unwrap().strings_found
+    };
+
+    // Four characters, eight bytes
+    assert_eq!(scan("chars"), 0);
+    assert_eq!(scan("bytes"), 1);
+}
+
+#[test]
+fn test_detect_tables_reports_consecutive_strings() {
+    let input = NamedTempFile::new().unwrap();
+    let mut data = vec![0u8; 16];
+    for i in 0..10 {
+        data.extend_from_slice(format!("string{:02}", i).as_bytes());
+        data.push(0);
+    }
+    data.extend_from_slice(&[0u8; 64]);
+    data.extend_from_slice(b"standalone\x00");
+    fs::write(input.path(), data).unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    let args = ["memstrap", input.path().to_str().unwrap(), "-o", output.path().to_str().unwrap(), "-e", "ascii", "--detect-tables", "--quiet"];
+    let summary = run(&Config::parse_from(args)).unwrap();
+    assert_eq!(summary.strings_found, 11);
+    let tables = &summary.per_file[0].1.tables;
+    assert_eq!(tables.len(), 1);
+    assert_eq!((tables[0].offset.get(), tables[0].count, tables[0].avg_len), (16, 10, 8.0));
+}
+
+#[test]
+fn test_repeated_format_writes_one_file_per_format() {
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), b"\x00first string\x00\x00second one\x00").unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("strings.out");
+    let args = ["memstrap", input.path().to_str().unwrap(), "-o", base.to_str().unwrap(), "--format", "csv", "--format", "xml", "--quiet"];
+    let summary = run(&Config::parse_from(args)).unwrap();
+    assert_eq!(summary.strings_found, 2);
+    assert!(!base.exists());
+
+    let csv = fs::read_to_string(dir.path().join("strings.csv")).unwrap();
+    let csv_rows: Vec<(String, String, String)> = csv::Reader::from_reader(csv.as_bytes())
+        .records()
+        .map(|r| r.unwrap())
+        .map(|r| (r[1].to_string(), r[3].to_string(), r[5].to_string()))
+        .collect();
+    let xml = fs::read_to_string(dir.path().join("strings.xml")).unwrap();
+    let doc = roxmltree::Document::parse(&xml).unwrap();
+    let xml_rows: Vec<(String, String, String)> = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("string"))
+        .map(|n| (n.attribute("offset").unwrap().to_string(), n.attribute("encoding").unwrap().to_string(), n.text().unwrap_or_default().to_string()))
+        .collect();
+    assert_eq!(csv_rows.len(), 2);
+    assert_eq!(csv_rows, xml_rows);
+
+    // Several formats cannot share stdout
+    let args = ["memstrap", input.path().to_str().unwrap(), "--format", "csv", "--format", "xml"];
+    assert!(run(&Config::parse_from(args)).is_err());
+}
+
+#[test]
+fn test_run_validates_a_config_built_in_code() {
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), b"\x00\x00First string\x00").unwrap();
+    let mut config = Config::parse_from(["memstrap", input.path().to_str().unwrap(), "--quiet"]);
+    config.regex = true;
+    let error = run(&config).unwrap_err();
+    assert_eq!(error.to_string(), "Configuration error: --regex requires a --search pattern");
+}
+
+#[test]
+fn test_gbk_max_len_ignored_without_gbk() {
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), b"\x00\x00First string\x00").unwrap();
+    let args = ["memstrap", input.path().to_str().unwrap(), "-e", "ascii", "--min-len", "2000", "--quiet"];
+    assert_eq!(run(&Config::parse_from(args)).unwrap().strings_found, 0);
+}
+
+#[test]
+fn test_largest_min_len_finds_nothing() {
+    let input = NamedTempFile::new().unwrap();
+    fs::write(input.path(), b"\x00\x00First string\x00").unwrap();
+    let max = usize::MAX.to_string();
+    let args = ["memstrap", input.path().to_str().unwrap(), "-e", "all", "--min-len", &max, "--gbk-max-len", &max, "--quiet"];
+    let summary = run(&Config::parse_from(args)).unwrap();
+    assert_eq!(summary.strings_found, 0);
+}
+
+#[test]
+fn test_region_map_labels_each_result() {
+    let input = NamedTempFile::new().unwrap();
+    let mut data = vec![0u8; 0xA0];
+    data[0x10..0x1C].copy_from_slice(b"in the stack");
+    data[0x48..0x53].copy_from_slice(b"in the heap");
+    data[0x90..0x9B].copy_from_slice(b"unmapped!!!");
+    fs::write(input.path(), data).unwrap();
+    let map = NamedTempFile::new().unwrap();
+    fs::write(map.path(), "start,end,label\n0x40,0x80,heap\n0x0,0x40,stack\n").unwrap();
+    let output = NamedTempFile::new().unwrap();
+
+    // The map holds image offsets, so --base-offset does not change the labels
+    for extra in [&[][..], &["--base-offset", "0x7FF0000"]] {
+        let args = ["memstrap", input.path().to_str().unwrap(), "-o", output.path().to_str().unwrap(), "--region-map", map.path().to_str().unwrap(), "--quiet"];
+        run(&Config::parse_from([&args[..], extra].concat())).unwrap();
+        let csv = fs::read_to_string(output.path()).unwrap();
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        assert_eq!(reader.headers().unwrap().iter().next_back(), Some("Region"));
+        let rows: Vec<(String, String)> = reader
+            .records()
+            .map(|r| r.unwrap())
+            .map(|r| (r[5].to_string(), r[r.len() - 1].to_string()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("in the stack".to_string(), "stack".to_string()),
+                ("in the heap".to_string(), "heap".to_string()),
+                ("unmapped!!!".to_string(), String::new()),
+            ]
+        );
+    }
+}
@@ -2,8 +2,51 @@ use std::env;
 use std::process::Command;
 use std::path::Path;
 
-/// 将 memprocfs 路径转换为本地文件系统路径
-fn convert_path(input_path: &str, mode: &str) -> (String, bool) {
+/// 目标路径风格：Windows 盘符 + 反斜杠，或 POSIX 挂载点 + 正斜杠
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathStyle {
+    Windows,
+    Posix,
+}
+
+impl PathStyle {
+    /// 当前编译平台对应的路径风格
+    fn native() -> Self {
+        if cfg!(windows) {
+            PathStyle::Windows
+        } else {
+            PathStyle::Posix
+        }
+    }
+
+    /// 默认的 memprocfs 挂载根目录
+    fn default_mount_root(self) -> &'static str {
+        match self {
+            PathStyle::Windows => "M:",
+            PathStyle::Posix => "/mnt/memprocfs",
+        }
+    }
+
+    /// 将路径中的分隔符统一为该风格的分隔符
+    fn normalize(self, path: &str) -> String {
+        match self {
+            PathStyle::Windows => path.replace('/', "\\"),
+            PathStyle::Posix => path.replace('\\', "/"),
+        }
+    }
+}
+
+/// 读取挂载根目录：优先使用环境变量 MEMPROCFS_MOUNT，未设置时使用该风格的默认值
+fn mount_root(style: PathStyle) -> String {
+    env::var("MEMPROCFS_MOUNT")
+        .ok()
+        .map(|root| root.trim().trim_end_matches(['\\', '/']).to_string())
+        .filter(|root| !root.is_empty())
+        .unwrap_or_else(|| style.default_mount_root().to_string())
+}
+
+/// 将 memprocfs 路径转换为本地文件系统路径，无法映射的 vol3 路径返回错误
+fn convert_path(input_path: &str, mode: &str, mount_root: &str, style: PathStyle) -> Result<(String, bool), String> {
     // 移除开头的反斜杠（如果存在）
     let cleaned_path = input_path.strip_prefix("\\").unwrap_or(input_path);
     
@@ -12,16 +55,16 @@ fn convert_path(input_path: &str, mode: &str) -> (String, bool) {
             // normal 模式：M:\forensic\files\ROOT\ + 目录部分
             if let Some(parent_pos) = cleaned_path.rfind('\\') {
                 let directory_part = &cleaned_path[..parent_pos];
-                let path = format!("M:\\forensic\\files\\ROOT\\{}", directory_part);
+                let path = format!("{}\\forensic\\files\\ROOT\\{}", mount_root, directory_part);
                 (path, true) // 返回目录
             } else {
                 // 如果没有路径分隔符，直接返回 ROOT 目录
-                ("M:\\forensic\\files\\ROOT".to_string(), true)
+                (format!("{}\\forensic\\files\\ROOT", mount_root), true)
             }
         },
         "ntfs" => {
             // ntfs 模式：M:\forensic\ntfs\ + 原路径
-            let path = format!("M:\\forensic\\ntfs\\{}", cleaned_path);
+            let path = format!("{}\\forensic\\ntfs\\{}", mount_root, cleaned_path);
             (path, false) // 返回文件
         },
         "vol2" => {
@@ -32,43 +75,132 @@ fn convert_path(input_path: &str, mode: &str) -> (String, bool) {
                 if parts.len() >= 3 {
                     // 提取卷号 (HarddiskVolumeX)
                     let volume_part = parts[1]; // HarddiskVolumeX
-                    if volume_part.starts_with("HarddiskVolume") {
-                        if let Ok(volume_num) = volume_part["HarddiskVolume".len()..].parse::<i32>() {
+                    if let Some(volume_index) = volume_part.strip_prefix("HarddiskVolume") {
+                        if let Ok(volume_num) = volume_index.parse::<i32>() {
                             // 卷号减1作为目录索引
                             let target_num = volume_num - 1;
                             let remaining_path = parts[2]; // 剩余路径部分
-                            let path = format!("M:\\forensic\\ntfs\\{}\\{}", target_num, remaining_path);
+                            let path = format!("{}\\forensic\\ntfs\\{}\\{}", mount_root, target_num, remaining_path);
                             (path, false) // 返回文件
                         } else {
                             // 解析卷号失败，返回原路径
-                            let path = format!("M:\\forensic\\ntfs\\{}", cleaned_path);
+                            let path = format!("{}\\forensic\\ntfs\\{}", mount_root, cleaned_path);
                             (path, false)
                         }
                     } else {
                         // 不是标准的HarddiskVolume格式
-                        let path = format!("M:\\forensic\\ntfs\\{}", cleaned_path);
+                        let path = format!("{}\\forensic\\ntfs\\{}", mount_root, cleaned_path);
                         (path, false)
                     }
                 } else {
                     // 路径格式不正确
-                    let path = format!("M:\\forensic\\ntfs\\{}", cleaned_path);
+                    let path = format!("{}\\forensic\\ntfs\\{}", mount_root, cleaned_path);
                     (path, false)
                 }
             } else {
                 // 不以Device\HarddiskVolume开头，当作普通路径处理
-                let path = format!("M:\\forensic\\ntfs\\{}", cleaned_path);
+                let path = format!("{}\\forensic\\ntfs\\{}", mount_root, cleaned_path);
                 (path, false)
             }
         },
+        "vol3" => {
+            // vol3 模式：解析 \Device\HarddiskVolumeN 以及已规范化的 \Volume<id> 格式，拒绝卷影副本
+            match vol3_volume_index(cleaned_path)? {
+                Some((volume_dir, remaining_path)) => {
+                    let path = format!("{}\\forensic\\ntfs\\{}\\{}", mount_root, volume_dir, remaining_path);
+                    (path, false) // 返回文件
+                }
+                None => {
+                    // 无法识别的格式，当作普通路径处理
+                    let path = format!("{}\\forensic\\ntfs\\{}", mount_root, cleaned_path);
+                    (path, false)
+                }
+            }
+        },
         _ => {
             // 默认使用 ntfs 模式
-            let path = format!("M:\\forensic\\ntfs\\{}", cleaned_path);
+            let path = format!("{}\\forensic\\ntfs\\{}", mount_root, cleaned_path);
             (path, false)
         }
     };
     
-    // 将正斜杠转换为反斜杠（Windows路径格式）
-    (new_path.replace("/", "\\"), is_directory)
+    // 按目标平台统一路径分隔符
+    Ok((style.normalize(&new_path), is_directory))
+}
+
+/// 解析 vol3 路径中的卷目录与剩余路径
+///
+/// - `Device\HarddiskVolumeN\...`：与 vol2 相同，卷号减 1；卷号 0 无对应目录，返回错误
+/// - `Device\HarddiskVolumeShadowCopyN\...`：卷影副本在 ntfs 目录中没有独立的卷，返回错误，
+///   以免与同号的在线卷混淆
+/// - `Volume<id>\...`：已经规范化的形式（如 `\Volumeewf\`），直接使用 `<id>` 作为目录
+///
+/// 无法识别的格式返回 `Ok(None)`，由调用方当作普通路径处理。
+fn vol3_volume_index(cleaned_path: &str) -> Result<Option<(String, &str)>, String> {
+    let parts: Vec<&str> = cleaned_path.splitn(3, '\\').collect();
+
+    if parts.len() == 3 && parts[0] == "Device" {
+        let volume_part = parts[1];
+        if let Some(number) = volume_part.strip_prefix("HarddiskVolumeShadowCopy") {
+            if number.parse::<u32>().is_ok() {
+                return Err(format!("卷影副本 {} 在 memprocfs 的 ntfs 目录中没有对应的卷", volume_part));
+            }
+            return Ok(None);
+        }
+        let Some(volume_num) = volume_part.strip_prefix("HarddiskVolume").and_then(|number| number.parse::<u32>().ok()) else {
+            return Ok(None);
+        };
+        let volume_index = volume_num
+            .checked_sub(1)
+            .ok_or_else(|| format!("卷号从 1 开始，{} 无对应的 ntfs 目录", volume_part))?;
+        return Ok(Some((volume_index.to_string(), parts[2])));
+    }
+
+    let Some((volume_part, remaining_path)) = cleaned_path.split_once('\\') else {
+        return Ok(None);
+    };
+    match volume_part.strip_prefix("Volume") {
+        Some(volume_id) if !volume_id.is_empty() && !remaining_path.is_empty() => {
+            Ok(Some((volume_id.to_string(), remaining_path)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// 打开转换后的路径：Windows 下使用资源管理器选中，其他平台使用 xdg-open
+fn open_path(file_path: &str, is_directory: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if cfg!(windows) {
+        open_and_select_file(file_path, is_directory)
+    } else {
+        open_with_xdg(file_path, is_directory)
+    }
+}
+
+/// 使用 xdg-open 打开目录（文件则打开其所在目录，xdg-open 不支持选中文件）
+fn open_with_xdg(file_path: &str, is_directory: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let target_dir = if is_directory {
+        file_path.to_string()
+    } else if let Some(parent) = Path::new(file_path).parent() {
+        parent.to_string_lossy().to_string()
+    } else {
+        file_path.to_string()
+    };
+
+    if !Path::new(&target_dir).exists() {
+        eprintln!("警告: 目录不存在: {}", target_dir);
+    }
+
+    let output = Command::new("xdg-open")
+        .arg(&target_dir)
+        .output()?;
+
+    if output.status.success() {
+        println!("成功打开目录: {}", target_dir);
+        Ok(())
+    } else {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        Err(format!("无法打开目录 {}: {}", target_dir, error_msg).into())
+    }
 }
 
 /// 在 Windows 资源管理器中打开并选中指定文件/文件夹
@@ -156,6 +288,7 @@ fn show_help() {
     println!("  ntfs     NTFS模式 - 将路径转换为 M:\\forensic\\ntfs\\<路径>");
     println!("  normal   Normal模式 - 将文件路径转换为对应目录 M:\\forensic\\files\\ROOT\\<目录>");
     println!("  vol2     Volatility2模式 - 将 \\Device\\HarddiskVolumeX 路径转换为对应的 ntfs 路径");
+    println!("  vol3     Volatility3模式 - 支持 \\Device\\HarddiskVolumeX 和已规范化的 \\Volume<id> 路径（不支持卷影副本）");
     println!();
     println!("示例:");
     println!("  {} ntfs \"\\0\\Windows\\System32\\config\\SYSTEM\"", 
@@ -170,8 +303,16 @@ fn show_help() {
              env::args().next().unwrap_or_else(|| "open_memprocfs_path".to_string()));
     println!("  将会转换为: M:\\forensic\\ntfs\\0\\Windows\\System32\\wlanhlp.dll");
     println!();
+    println!("  {} vol3 \"\\Volumeewf\\Windows\\System32\\cmd.exe\"",
+             env::args().next().unwrap_or_else(|| "open_memprocfs_path".to_string()));
+    println!("  将会转换为: M:\\forensic\\ntfs\\ewf\\Windows\\System32\\cmd.exe");
+    println!();
     println!("参数:");
     println!("  -h, --help    显示此帮助信息");
+    println!();
+    println!("环境变量:");
+    println!("  MEMPROCFS_MOUNT  memprocfs 挂载根目录 (默认: Windows 为 {}，Linux 为 {})",
+             PathStyle::Windows.default_mount_root(), PathStyle::Posix.default_mount_root());
 }
 
 fn main() {
@@ -203,27 +344,30 @@ fn main() {
     let input_path = &args[2];
 
     // 验证模式参数
-    if mode != "ntfs" && mode != "normal" && mode != "vol2" {
-        // eprintln!("错误: 无效的模式 '{}'，只支持 'ntfs'、'normal' 或 'vol2'", mode);
+    if mode != "ntfs" && mode != "normal" && mode != "vol2" && mode != "vol3" {
+        // eprintln!("错误: 无效的模式 '{}'，只支持 'ntfs'、'normal'、'vol2' 或 'vol3'", mode);
         show_help();
         std::process::exit(1);
     }
 
     // 转换路径
-    let (converted_path, is_directory) = convert_path(input_path, mode);
+    let style = PathStyle::native();
+    let (converted_path, is_directory) = match convert_path(input_path, mode, &mount_root(style), style) {
+        Ok(converted) => converted,
+        Err(_e) => {
+            // eprintln!("错误: {}", _e);
+            std::process::exit(1);
+        }
+    };
     
     // println!("模式: {}", mode);
     // println!("原路径: {}", input_path);
     // println!("转换后: {}", converted_path);
 
-    // 在资源管理器中打开并选中文件
-    match open_and_select_file(&converted_path, is_directory) {
+    // 在资源管理器（或 xdg-open）中打开并选中文件
+    match open_path(&converted_path, is_directory) {
         Ok(()) => {
-            if is_directory {
-                println!("success");
-            } else {
-                println!("success");
-            }
+            println!("success");
         }
         Err(_e) => {
             // eprintln!("错误: {}", _e);
@@ -233,35 +377,36 @@ fn main() {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_convert_path_ntfs() {
-        let (path, is_dir) = convert_path("\\0\\Windows\\System32\\config\\SYSTEM", "ntfs");
+        let (path, is_dir) = convert_path("\\0\\Windows\\System32\\config\\SYSTEM", "ntfs", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\ntfs\\0\\Windows\\System32\\config\\SYSTEM");
         assert_eq!(is_dir, false);
         
-        let (path, is_dir) = convert_path("0\\Windows\\System32\\config\\SYSTEM", "ntfs");
+        let (path, is_dir) = convert_path("0\\Windows\\System32\\config\\SYSTEM", "ntfs", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\ntfs\\0\\Windows\\System32\\config\\SYSTEM");
         assert_eq!(is_dir, false);
         
-        let (path, is_dir) = convert_path("\\0\\test\\file.txt", "ntfs");
+        let (path, is_dir) = convert_path("\\0\\test\\file.txt", "ntfs", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\ntfs\\0\\test\\file.txt");
         assert_eq!(is_dir, false);
     }
 
     #[test]
     fn test_convert_path_normal() {
-        let (path, is_dir) = convert_path("\\Windows\\System32\\en-US\\KernelBase.dll.mui", "normal");
+        let (path, is_dir) = convert_path("\\Windows\\System32\\en-US\\KernelBase.dll.mui", "normal", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\files\\ROOT\\Windows\\System32\\en-US");
         assert_eq!(is_dir, true);
         
-        let (path, is_dir) = convert_path("Windows\\System32\\config\\SYSTEM", "normal");
+        let (path, is_dir) = convert_path("Windows\\System32\\config\\SYSTEM", "normal", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\files\\ROOT\\Windows\\System32\\config");
         assert_eq!(is_dir, true);
         
-        let (path, is_dir) = convert_path("test.txt", "normal");
+        let (path, is_dir) = convert_path("test.txt", "normal", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\files\\ROOT");
         assert_eq!(is_dir, true);
     }
@@ -269,21 +414,95 @@ mod tests {
     #[test]
     fn test_convert_path_vol2() {
         // 测试标准的 Device\HarddiskVolumeX 格式
-        let (path, is_dir) = convert_path("\\Device\\HarddiskVolume1\\Windows\\System32\\wlanhlp.dll", "vol2");
+        let (path, is_dir) = convert_path("\\Device\\HarddiskVolume1\\Windows\\System32\\wlanhlp.dll", "vol2", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\ntfs\\0\\Windows\\System32\\wlanhlp.dll");
         assert_eq!(is_dir, false);
         
-        let (path, is_dir) = convert_path("Device\\HarddiskVolume2\\Windows\\notepad.exe", "vol2");
+        let (path, is_dir) = convert_path("Device\\HarddiskVolume2\\Windows\\notepad.exe", "vol2", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\ntfs\\1\\Windows\\notepad.exe");
         assert_eq!(is_dir, false);
         
-        let (path, is_dir) = convert_path("\\Device\\HarddiskVolume3\\Program Files\\test.dll", "vol2");
+        let (path, is_dir) = convert_path("\\Device\\HarddiskVolume3\\Program Files\\test.dll", "vol2", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\ntfs\\2\\Program Files\\test.dll");
         assert_eq!(is_dir, false);
         
         // 测试不标准的路径格式（应该当作普通ntfs路径处理）
-        let (path, is_dir) = convert_path("\\SomeOther\\Path\\file.txt", "vol2");
+        let (path, is_dir) = convert_path("\\SomeOther\\Path\\file.txt", "vol2", "M:", PathStyle::Windows).unwrap();
         assert_eq!(path, "M:\\forensic\\ntfs\\SomeOther\\Path\\file.txt");
         assert_eq!(is_dir, false);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_convert_path_custom_mount_root() {
+        let (path, is_dir) = convert_path("\\0\\Windows\\System32\\config\\SYSTEM", "ntfs", "X:", PathStyle::Windows).unwrap();
+        assert_eq!(path, "X:\\forensic\\ntfs\\0\\Windows\\System32\\config\\SYSTEM");
+        assert!(!is_dir);
+
+        let (path, is_dir) = convert_path("\\Windows\\System32\\en-US\\KernelBase.dll.mui", "normal", "X:", PathStyle::Windows).unwrap();
+        assert_eq!(path, "X:\\forensic\\files\\ROOT\\Windows\\System32\\en-US");
+        assert!(is_dir);
+
+        let (path, is_dir) = convert_path("test.txt", "normal", "X:", PathStyle::Windows).unwrap();
+        assert_eq!(path, "X:\\forensic\\files\\ROOT");
+        assert!(is_dir);
+
+        let (path, is_dir) = convert_path("\\Device\\HarddiskVolume2\\Windows\\notepad.exe", "vol2", "X:", PathStyle::Windows).unwrap();
+        assert_eq!(path, "X:\\forensic\\ntfs\\1\\Windows\\notepad.exe");
+        assert!(!is_dir);
+
+        let (path, is_dir) = convert_path("\\SomeOther\\Path\\file.txt", "vol2", "X:", PathStyle::Windows).unwrap();
+        assert_eq!(path, "X:\\forensic\\ntfs\\SomeOther\\Path\\file.txt");
+        assert!(!is_dir);
+    }
+
+    #[test]
+    fn test_convert_path_posix() {
+        let root = "/mnt/memprocfs";
+
+        let (path, is_dir) = convert_path("\\0\\Windows\\System32\\config\\SYSTEM", "ntfs", root, PathStyle::Posix).unwrap();
+        assert_eq!(path, "/mnt/memprocfs/forensic/ntfs/0/Windows/System32/config/SYSTEM");
+        assert!(!is_dir);
+
+        let (path, is_dir) = convert_path("\\Windows\\System32\\en-US\\KernelBase.dll.mui", "normal", root, PathStyle::Posix).unwrap();
+        assert_eq!(path, "/mnt/memprocfs/forensic/files/ROOT/Windows/System32/en-US");
+        assert!(is_dir);
+
+        let (path, is_dir) = convert_path("test.txt", "normal", root, PathStyle::Posix).unwrap();
+        assert_eq!(path, "/mnt/memprocfs/forensic/files/ROOT");
+        assert!(is_dir);
+
+        let (path, is_dir) = convert_path("\\Device\\HarddiskVolume1\\Windows\\System32\\wlanhlp.dll", "vol2", root, PathStyle::Posix).unwrap();
+        assert_eq!(path, "/mnt/memprocfs/forensic/ntfs/0/Windows/System32/wlanhlp.dll");
+        assert!(!is_dir);
+
+        let (path, is_dir) = convert_path("\\SomeOther\\Path\\file.txt", "vol2", root, PathStyle::Posix).unwrap();
+        assert_eq!(path, "/mnt/memprocfs/forensic/ntfs/SomeOther/Path/file.txt");
+        assert!(!is_dir);
+    }
+
+    #[test]
+    fn test_convert_path_vol3() {
+        // 卷影副本没有对应的 ntfs 目录，不能映射到同号的在线卷
+        assert!(convert_path("\\Device\\HarddiskVolumeShadowCopy1\\Windows\\System32\\cmd.exe", "vol3", "M:", PathStyle::Windows).is_err());
+        assert!(convert_path("Device\\HarddiskVolumeShadowCopy3\\Users\\a\\ntuser.dat", "vol3", "M:", PathStyle::Windows).is_err());
+
+        // 卷号从 1 开始
+        assert!(convert_path("\\Device\\HarddiskVolume0\\Windows\\notepad.exe", "vol3", "M:", PathStyle::Windows).is_err());
+
+        // 普通卷路径与 vol2 一致
+        let (path, _) = convert_path("\\Device\\HarddiskVolume2\\Windows\\notepad.exe", "vol3", "M:", PathStyle::Windows).unwrap();
+        assert_eq!(path, "M:\\forensic\\ntfs\\1\\Windows\\notepad.exe");
+
+        // 已规范化的 \Volume<id> 路径
+        let (path, is_dir) = convert_path("\\Volumeewf\\Windows\\System32\\drivers\\etc\\hosts", "vol3", "M:", PathStyle::Windows).unwrap();
+        assert_eq!(path, "M:\\forensic\\ntfs\\ewf\\Windows\\System32\\drivers\\etc\\hosts");
+        assert!(!is_dir);
+
+        let (path, _) = convert_path("\\Volume0\\pagefile.sys", "vol3", "M:", PathStyle::Windows).unwrap();
+        assert_eq!(path, "M:\\forensic\\ntfs\\0\\pagefile.sys");
+
+        // 无法识别的格式当作普通路径处理
+        let (path, _) = convert_path("\\Device\\HarddiskVolumeShadowCopyX\\file.txt", "vol3", "M:", PathStyle::Windows).unwrap();
+        assert_eq!(path, "M:\\forensic\\ntfs\\Device\\HarddiskVolumeShadowCopyX\\file.txt");
+    }
+}